@@ -0,0 +1,358 @@
+use std::io::{self, BufRead, Write};
+
+use crate::minefield::{MineField, MineFieldState};
+use crate::position::Position;
+use crate::tile::TileState;
+use crate::timer::Timer;
+
+/// The most opened tiles that are read out one by one after a move.
+const LISTED_TILES: usize = 8;
+
+const HELP: &str = "\
+commands, with rows and columns counted from 1:
+  d ROW COLUMN   dig a tile
+  f ROW COLUMN   flag a tile, or take its flag off
+  c ROW COLUMN   chord on a number, digging around it
+  l ROW COLUMN   look at a tile and what's around it
+  r ROW          read out a whole row
+  s              how the game is going
+  h              show these commands
+  q              give up and quit";
+
+/// Something the player has asked to do.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+enum Action {
+    Dig(Position),
+    Flag(Position),
+    Chord(Position),
+    Look(Position),
+    Row(usize),
+    Status,
+    Help,
+    Quit,
+}
+
+impl Action {
+    /// Parse a command like `d 3 4` or `look 3 4`, counting rows and
+    /// columns from 1, into an action on the field's own positions.
+    fn parse(line: &str, field: &MineField) -> Result<Self, String> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let numbers: Vec<usize> = words[1..]
+            .iter()
+            .map(|word| word.parse())
+            .collect::<Result<_, _>>()
+            .map_err(|_| format!("'{}' needs numbers after it.", words[0]))?;
+
+        let make: fn(Position) -> Action = match words[0] {
+            "d" | "dig" => Action::Dig,
+            "f" | "flag" => Action::Flag,
+            "c" | "chord" => Action::Chord,
+            "l" | "look" => Action::Look,
+            "r" | "row" => {
+                return match numbers[..] {
+                    [row] if (1..=field.height()).contains(&row) => Ok(Action::Row(row - 1)),
+                    [row] => Err(format!(
+                        "There's no row {}. The field has {} rows.",
+                        row,
+                        field.height()
+                    )),
+                    _ => Err(String::from("'r' needs a row.")),
+                };
+            }
+            "s" | "status" => return Ok(Action::Status),
+            "h" | "help" | "?" => return Ok(Action::Help),
+            "q" | "quit" => return Ok(Action::Quit),
+            word => return Err(format!("Unknown command '{}'. Type 'h' for help.", word)),
+        };
+
+        match numbers[..] {
+            [row, column]
+                if (1..=field.height()).contains(&row) && (1..=field.width()).contains(&column) =>
+            {
+                Ok(make(Position::new(row - 1, column - 1)))
+            }
+            [row, column] => Err(format!(
+                "There's no row {}, column {}. The field has {} rows and {} columns.",
+                row,
+                column,
+                field.height(),
+                field.width()
+            )),
+            _ => Err(format!("'{}' needs a row and a column.", words[0])),
+        }
+    }
+}
+
+/// Name a tile, like `row 3, column 4`, counting from 1.
+fn name(position: Position) -> String {
+    format!("row {}, column {}", position.row + 1, position.column + 1)
+}
+
+/// Describe what's on a tile in a word or two, as the player sees it.
+fn describe_tile(field: &MineField, position: Position) -> String {
+    field.describe_tile(position).unwrap()
+}
+
+/// Describe a tile and what's around it.
+fn look(field: &MineField, position: Position) -> String {
+    let around = field.get_indices_near(position).unwrap();
+    let count = |state| {
+        around
+            .iter()
+            .filter(|&&neighbour| field.get_tile_state(neighbour) == Ok(state))
+            .count()
+    };
+
+    format!(
+        "{} is {}, with {} hidden and {} flagged around it.",
+        capitalise(&name(position)),
+        describe_tile(field, position),
+        count(TileState::Hidden),
+        count(TileState::Flagged)
+    )
+}
+
+/// Read out a row, with runs of the same tile counted up, like
+/// `2 hidden, a 1, 3 empty`.
+fn read_row(field: &MineField, row: usize) -> String {
+    let mut runs: Vec<(String, usize)> = Vec::new();
+
+    for column in 0..field.width() {
+        let tile = describe_tile(field, Position::new(row, column));
+
+        match runs.last_mut() {
+            Some((last, length)) if *last == tile => *length += 1,
+            _ => runs.push((tile, 1)),
+        }
+    }
+
+    let runs: Vec<String> = runs
+        .iter()
+        .map(|(tile, length)| match length {
+            1 => tile.clone(),
+            length => format!("{} {}", length, tile.trim_start_matches("a ")),
+        })
+        .collect();
+
+    format!("Row {}: {}.", row + 1, runs.join(", "))
+}
+
+/// Describe how the game is going.
+fn status(field: &MineField, timer: &Timer) -> String {
+    let (revealed, total) = field.progress();
+
+    format!(
+        "{}, {} of {} safe tiles open, {} seconds.",
+        describe_mines_left(field.mines_remaining()),
+        revealed,
+        total,
+        timer.elapsed().as_secs()
+    )
+}
+
+/// Describe how many mines are left to flag, like `3 mines left`.
+fn describe_mines_left(mines: usize) -> String {
+    match mines {
+        1 => String::from("1 mine left"),
+        mines => format!("{} mines left", mines),
+    }
+}
+
+/// Describe the tiles that a dig or chord opened.
+fn describe_opened(before: &MineField, after: &MineField) -> String {
+    let opened: Vec<Position> = before
+        .diff(after)
+        .iter()
+        .filter(|change| change.after.state == TileState::Visible)
+        .map(|change| change.position)
+        .collect();
+
+    match opened[..] {
+        [] => String::from("Nothing changed."),
+        [position] => format!(
+            "{} is {}.",
+            capitalise(&name(position)),
+            describe_tile(after, position)
+        ),
+        _ if opened.len() > LISTED_TILES => {
+            let rows = opened.iter().map(|position| position.row + 1);
+            let columns = opened.iter().map(|position| position.column + 1);
+
+            format!(
+                "Opened {} tiles, from row {} to {} and column {} to {}.",
+                opened.len(),
+                rows.clone().min().unwrap(),
+                rows.max().unwrap(),
+                columns.clone().min().unwrap(),
+                columns.max().unwrap()
+            )
+        }
+        _ => {
+            let tiles: Vec<String> = opened
+                .iter()
+                .map(|&position| {
+                    format!("{} is {}", name(position), describe_tile(after, position))
+                })
+                .collect();
+
+            format!("Opened {} tiles: {}.", opened.len(), tiles.join("; "))
+        }
+    }
+}
+
+fn capitalise(text: &str) -> String {
+    let mut chars = text.chars();
+
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Play a game by typing commands, with everything that happens
+/// written out as plain lines of text.
+///
+/// Nothing is drawn at a position on the screen, so that a screen
+/// reader can follow along. It ends when the game is won or lost,
+/// the player quits, or the input runs out.
+pub fn play(mut field: MineField, input: impl BufRead, mut output: impl Write) -> io::Result<()> {
+    let timer = Timer::start();
+
+    writeln!(
+        output,
+        "A {} by {} field with {} mines. {} Type 'h' for help.",
+        field.width(),
+        field.height(),
+        field.mines(),
+        status(&field, &timer)
+    )?;
+
+    // the first opening is cleared already, so say where it is.
+    let mut covered = field.clone();
+    covered.cover_all();
+
+    if !covered.diff(&field).is_empty() {
+        writeln!(output, "{}", describe_opened(&covered, &field))?;
+    }
+
+    write!(output, "> ")?;
+    output.flush()?;
+
+    for line in input.lines() {
+        let line = line?;
+
+        if line.trim().is_empty() {
+            write!(output, "> ")?;
+            output.flush()?;
+            continue;
+        }
+
+        let before = field.clone();
+
+        let reply = match Action::parse(&line, &field) {
+            Err(e) => e,
+            Ok(Action::Dig(position)) => {
+                field.flood_empty_tiles(position).unwrap();
+                describe_opened(&before, &field)
+            }
+            Ok(Action::Chord(position)) => {
+                field.do_chord(position).unwrap();
+                describe_opened(&before, &field)
+            }
+            Ok(Action::Flag(position)) => match field.toggle_flag(position) {
+                Err(e) => e.to_string(),
+                Ok(()) => match field.get_tile_state(position).unwrap() {
+                    TileState::Flagged => format!(
+                        "Flagged {}. {}.",
+                        name(position),
+                        describe_mines_left(field.mines_remaining())
+                    ),
+                    TileState::Hidden => format!("Took the flag off {}.", name(position)),
+                    TileState::Visible => {
+                        format!(
+                            "{} is open, so it can't be flagged.",
+                            capitalise(&name(position))
+                        )
+                    }
+                },
+            },
+            Ok(Action::Look(position)) => look(&field, position),
+            Ok(Action::Row(row)) => read_row(&field, row),
+            Ok(Action::Status) => status(&field, &timer),
+            Ok(Action::Help) => String::from(HELP),
+            Ok(Action::Quit) => {
+                writeln!(
+                    output,
+                    "You gave up after {} seconds.",
+                    timer.elapsed().as_secs()
+                )?;
+                return Ok(());
+            }
+        };
+
+        writeln!(output, "{}", reply)?;
+
+        // the game is over as soon as it's won or lost.
+        match field.get_state() {
+            MineFieldState::InProgress => (),
+            MineFieldState::Cleared => {
+                writeln!(
+                    output,
+                    "Cleared! You won in {} seconds.",
+                    timer.elapsed().as_secs()
+                )?;
+                return Ok(());
+            }
+            MineFieldState::Failed => {
+                writeln!(
+                    output,
+                    "Boom! You lost after {} seconds.",
+                    timer.elapsed().as_secs()
+                )?;
+                return Ok(());
+            }
+        }
+
+        write!(output, "> ")?;
+        output.flush()?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn commands_are_answered_in_words() {
+        let field = MineField::from_layout("*...\n....\n...*").unwrap();
+        let input = "s\nf 1 1\nl 2 2\nd 9 9\nr 1\nd 1 4\nd 3 1\n";
+        let mut output = Vec::new();
+
+        play(field, input.as_bytes(), &mut output).unwrap();
+
+        let text = String::from_utf8(output).unwrap();
+        let replies: Vec<&str> = text
+            .lines()
+            .map(|line| line.trim_start_matches("> "))
+            .collect();
+
+        assert_eq!(
+            replies[1..],
+            [
+                "2 mines left, 0 of 10 safe tiles open, 0 seconds.",
+                "Flagged row 1, column 1. 1 mine left.",
+                "Row 2, column 2 is hidden, with 7 hidden and 1 flagged around it.",
+                "There's no row 9, column 9. The field has 3 rows and 4 columns.",
+                "Row 1: flagged, 3 hidden.",
+                "Opened 6 tiles: row 1, column 2 is a 1; row 1, column 3 is empty; \
+                 row 1, column 4 is empty; row 2, column 2 is a 1; row 2, column 3 is a 1; \
+                 row 2, column 4 is a 1.",
+                "Opened 4 tiles: row 2, column 1 is a 1; row 3, column 1 is empty; \
+                 row 3, column 2 is empty; row 3, column 3 is a 1.",
+                "Cleared! You won in 0 seconds.",
+            ]
+        );
+    }
+}
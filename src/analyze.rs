@@ -0,0 +1,133 @@
+use std::fs;
+use std::io::{self, Read};
+
+use crate::partial::PartialBoard;
+use crate::position::Position;
+use crate::solver::{self, Probability, Verdict};
+use crate::view::{BoardView, Cell};
+
+/// Read a position from a file, or from stdin if the path is `-`.
+fn read_position(path: &str) -> io::Result<String> {
+    if path == "-" {
+        let mut text = String::new();
+        io::stdin().read_to_string(&mut text)?;
+        Ok(text)
+    } else {
+        fs::read_to_string(path)
+    }
+}
+
+/// Draw the position with every deduced tile marked.
+fn annotate(board: &PartialBoard, verdicts: &[(usize, usize, Verdict)]) -> String {
+    let mut lines = Vec::new();
+
+    for row in 0..board.height() {
+        let line: Vec<String> = (0..board.width())
+            .map(|column| {
+                let verdict = verdicts
+                    .iter()
+                    .find(|&&(r, c, _)| (r, c) == (row, column))
+                    .map(|&(_, _, verdict)| verdict);
+
+                match (board.cell(row, column), verdict) {
+                    (_, Some(Verdict::Safe)) => String::from("o"),
+                    (_, Some(Verdict::Mine)) => String::from("*"),
+                    (Cell::Hidden, None) => String::from("."),
+                    (Cell::Flagged, None) => String::from("F"),
+                    (Cell::Revealed(n), None) => n.to_string(),
+                    (Cell::Void, None) => String::from(" "),
+                }
+            })
+            .collect();
+
+        lines.push(line.join(" "));
+    }
+
+    lines.join("\n")
+}
+
+/// Draw the chance of a mine on each hidden tile, as a percentage.
+fn chance_grid(board: &PartialBoard, probabilities: &[Probability]) -> String {
+    let mut lines = Vec::new();
+
+    for row in 0..board.height() {
+        let line: Vec<String> = (0..board.width())
+            .map(|column| {
+                let probability = probabilities
+                    .iter()
+                    .find(|probability| probability.position == Position::new(row, column));
+
+                match (board.cell(row, column), probability) {
+                    (Cell::Hidden, Some(probability)) => {
+                        format!("{:>3}%", (probability.mine * 100.0).round())
+                    }
+                    (Cell::Hidden, None) => String::from("   ?"),
+                    (Cell::Flagged, _) => String::from("   F"),
+                    (Cell::Revealed(n), _) => format!("{:>4}", n),
+                    (Cell::Void, _) => String::from("    "),
+                }
+            })
+            .collect();
+
+        lines.push(line.join(" "));
+    }
+
+    lines.join("\n")
+}
+
+/// Analyse a position and describe everything that can be deduced.
+pub fn analyze(path: &str, mines: Option<usize>) -> Result<String, String> {
+    let text = read_position(path).map_err(|e| format!("couldn't read '{}': {}", path, e))?;
+    let mut board = PartialBoard::parse(&text).map_err(String::from)?;
+
+    if let Some(mines) = mines {
+        board.set_mines(mines);
+    }
+
+    let verdicts: Vec<(usize, usize, Verdict)> = solver::deduce(&board)
+        .into_iter()
+        .map(|deduction| {
+            let Position { row, column } = deduction.position;
+            (row, column, deduction.verdict)
+        })
+        .collect();
+
+    let safe = verdicts
+        .iter()
+        .filter(|&&(_, _, verdict)| verdict == Verdict::Safe)
+        .count();
+
+    let mut report = annotate(&board, &verdicts);
+
+    report.push_str(&format!(
+        "\n\n{} safe tiles (o) and {} mines (*) can be deduced.",
+        safe,
+        verdicts.len() - safe
+    ));
+
+    let probabilities = solver::probabilities(&board);
+
+    if probabilities.is_empty() {
+        report.push_str("\nNo mine chances could be worked out. Are the flags right?");
+        return Ok(report);
+    }
+
+    report.push_str("\n\nChance of a mine on each hidden tile:\n\n");
+    report.push_str(&chance_grid(&board, &probabilities));
+
+    // the safest guess is only interesting if there's nothing certain.
+    let safest = probabilities
+        .iter()
+        .min_by(|a, b| a.mine.partial_cmp(&b.mine).unwrap());
+
+    if let (0, Some(safest)) = (safe, safest) {
+        report.push_str(&format!(
+            "\n\nSafest guess: row {}, column {} ({:.0}% chance of a mine).",
+            safest.position.row + 1,
+            safest.position.column + 1,
+            safest.mine * 100.0
+        ));
+    }
+
+    Ok(report)
+}
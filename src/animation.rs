@@ -0,0 +1,77 @@
+use std::time::Duration;
+
+use crate::position::Position;
+
+/// The most frames a flood is shown over, however far it spreads, so
+/// that a huge opening doesn't hold up the game.
+const MAX_FLOOD_FRAMES: usize = 25;
+
+/// How quickly a flood spreads out across the screen when it's dug.
+#[derive(Debug, Default, Eq, PartialEq, Copy, Clone)]
+pub enum FloodSpeed {
+    Off,
+    Slow,
+    #[default]
+    Normal,
+    Fast,
+}
+
+impl FloodSpeed {
+    /// Read a speed by its name in the config file.
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "off" | "false" | "no" => Some(FloodSpeed::Off),
+            "slow" => Some(FloodSpeed::Slow),
+            "normal" | "on" | "true" | "yes" => Some(FloodSpeed::Normal),
+            "fast" => Some(FloodSpeed::Fast),
+            _ => None,
+        }
+    }
+
+    /// How long each frame of a flood is shown for, if it's shown at all.
+    pub fn frame_time(self) -> Option<Duration> {
+        match self {
+            FloodSpeed::Off => None,
+            FloodSpeed::Slow => Some(Duration::from_millis(60)),
+            FloodSpeed::Normal => Some(Duration::from_millis(30)),
+            FloodSpeed::Fast => Some(Duration::from_millis(15)),
+        }
+    }
+}
+
+/// Split the waves of a flood into the frames it's shown over, joining
+/// neighbouring waves together when there are too many to show alone.
+pub fn flood_frames(waves: &[Vec<Position>]) -> Vec<Vec<Position>> {
+    let per_frame = waves.len().div_ceil(MAX_FLOOD_FRAMES);
+
+    waves
+        .chunks(per_frame.max(1))
+        .map(|chunk| chunk.concat())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn long_floods_are_squeezed_into_fewer_frames() {
+        let waves: Vec<Vec<Position>> = (0..60).map(|row| vec![Position::new(row, 0)]).collect();
+
+        let frames = flood_frames(&waves);
+        assert_eq!(frames.len(), 20);
+        assert_eq!(
+            frames[0],
+            [
+                Position::new(0, 0),
+                Position::new(1, 0),
+                Position::new(2, 0)
+            ]
+        );
+        assert_eq!(frames.concat().len(), 60);
+
+        assert_eq!(flood_frames(&waves[..4]).len(), 4);
+        assert_eq!(FloodSpeed::parse("fast"), Some(FloodSpeed::Fast));
+        assert_eq!(FloodSpeed::Off.frame_time(), None);
+    }
+}
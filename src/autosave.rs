@@ -0,0 +1,222 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::minefield::MineField;
+use crate::position::Position;
+use crate::storage;
+
+/// The directory in the data directory that games are saved to.
+const AUTOSAVE_DIR: &str = "autosaves";
+
+/// How many unfinished games are kept, going by the ones saved most
+/// recently. Saving another one removes the oldest.
+const MAX_AUTOSAVES: usize = 5;
+
+/// A game saved partway through, to be carried on with later.
+///
+/// It's written as `key = value` lines, then a blank line and the
+/// board as a layout:
+///
+/// ```text
+/// arg = expert
+/// arg = --strict-flags
+/// elapsed = 73.25
+/// cursor = C7
+///
+/// 01f..
+/// ```
+pub struct SavedGame {
+    /// The arguments the game was started with, which set everything
+    /// about it but the board.
+    pub args: Vec<String>,
+    pub elapsed: Duration,
+    pub cursor: Position,
+    pub field: MineField,
+}
+
+impl SavedGame {
+    /// Write the game out to go in a file.
+    fn to_text(&self) -> String {
+        let mut text: String = self
+            .args
+            .iter()
+            .map(|arg| format!("arg = {}\n", arg))
+            .collect();
+
+        text.push_str(&format!("elapsed = {:.3}\n", self.elapsed.as_secs_f64()));
+        text.push_str(&format!("cursor = {}\n\n", self.cursor.to_a1()));
+        text.push_str(&self.field.to_string_layout());
+        text
+    }
+
+    /// Read a game back from what was written out by `to_text`.
+    fn parse(text: &str) -> Result<Self, String> {
+        let (header, layout) = text
+            .split_once("\n\n")
+            .ok_or("The save doesn't have a board in it.")?;
+
+        let mut args = Vec::new();
+        let mut elapsed = None;
+        let mut cursor = None;
+
+        for line in header.lines() {
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| format!("Couldn't read '{}' in the save.", line))?;
+
+            match key.trim() {
+                "arg" => args.push(String::from(value.trim())),
+                "elapsed" => elapsed = value.trim().parse().ok().map(Duration::from_secs_f64),
+                "cursor" => cursor = Position::from_a1(value),
+                _ => return Err(format!("Unknown setting '{}' in the save.", key.trim())),
+            }
+        }
+
+        let field = MineField::from_layout(layout).map_err(|e| e.to_string())?;
+        let cursor = cursor
+            .filter(|cursor| cursor.is_within(field.height(), field.width()))
+            .ok_or("The save doesn't say where the cursor was.")?;
+
+        Ok(Self {
+            args,
+            elapsed: elapsed.ok_or("The save doesn't say how long was played.")?,
+            cursor,
+            field,
+        })
+    }
+}
+
+/// The file a game is saved to while it's played, until it's finished.
+pub struct Autosave {
+    path: PathBuf,
+    args: Vec<String>,
+}
+
+impl Autosave {
+    /// Start saving a new game, started with some arguments, to a file
+    /// of its own. Returns `None` if there's nowhere to keep it.
+    pub fn new(args: Vec<String>) -> Option<Self> {
+        Self::in_dir(&storage::data_subdir(AUTOSAVE_DIR).ok()?, args)
+    }
+
+    /// Start saving a new game to a file of its own in some directory,
+    /// rather than the usual one.
+    pub fn in_dir(dir: &Path, args: Vec<String>) -> Option<Self> {
+        let since_epoch = SystemTime::now().duration_since(UNIX_EPOCH).ok()?;
+
+        Some(Self {
+            path: dir.join(format!("{}.save", since_epoch.as_millis())),
+            args,
+        })
+    }
+
+    /// Save the game as it is now, replacing the last save of it.
+    pub fn save(&self, field: &MineField, elapsed: Duration, cursor: Position) -> io::Result<()> {
+        let game = SavedGame {
+            args: self.args.clone(),
+            elapsed,
+            cursor,
+            field: field.clone(),
+        };
+
+        // a half-written save would be worse than a slightly old one.
+        let partial = self.path.with_extension("partial");
+        fs::write(&partial, game.to_text())?;
+        fs::rename(&partial, &self.path)?;
+
+        match self.path.parent() {
+            Some(dir) => remove_oldest(dir, MAX_AUTOSAVES),
+            None => Ok(()),
+        }
+    }
+
+    /// Remove the save, once there's nothing left to carry on with.
+    pub fn remove(&self) {
+        fs::remove_file(&self.path).ok();
+    }
+}
+
+/// Find the saves of unfinished games in a directory, most recently
+/// saved first.
+fn saves(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut saves: Vec<(SystemTime, PathBuf)> = fs::read_dir(dir)?
+        .filter_map(Result::ok)
+        .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "save"))
+        .filter_map(|entry| Some((entry.metadata().ok()?.modified().ok()?, entry.path())))
+        .collect();
+
+    saves.sort_by(|a, b| b.cmp(a));
+    Ok(saves.into_iter().map(|(_, path)| path).collect())
+}
+
+/// Remove every save in a directory but the most recent few.
+fn remove_oldest(dir: &Path, kept: usize) -> io::Result<()> {
+    for path in saves(dir)?.into_iter().skip(kept) {
+        fs::remove_file(path)?;
+    }
+
+    Ok(())
+}
+
+/// Load the most recently saved game that hasn't been finished, along
+/// with its autosave to carry on saving it to. Saves that can't be
+/// read are passed over.
+pub fn latest() -> io::Result<Option<(SavedGame, Autosave)>> {
+    latest_in(&storage::data_subdir(AUTOSAVE_DIR)?)
+}
+
+/// Load the most recently saved game in some directory, rather than
+/// the usual one.
+pub fn latest_in(dir: &Path) -> io::Result<Option<(SavedGame, Autosave)>> {
+    for path in saves(dir)? {
+        let game = match fs::read_to_string(&path).map(|text| SavedGame::parse(&text)) {
+            Ok(Ok(game)) => game,
+            _ => continue,
+        };
+
+        let autosave = Autosave {
+            path,
+            args: game.args.clone(),
+        };
+
+        return Ok(Some((game, autosave)));
+    }
+
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn saved_games_are_read_back() {
+        let mut field = MineField::from_layout(" .*.\n..f.\n0F..").unwrap();
+        field.dig_tile(Position::new(0, 3)).unwrap();
+
+        let game = SavedGame {
+            args: vec![String::from("load"), String::from("board one.txt")],
+            elapsed: Duration::from_millis(73_250),
+            cursor: Position::new(2, 3),
+            field,
+        };
+
+        let loaded = SavedGame::parse(&game.to_text()).unwrap();
+
+        assert_eq!(loaded.args, game.args);
+        assert_eq!(loaded.elapsed, game.elapsed);
+        assert_eq!(loaded.cursor, game.cursor);
+        assert_eq!(
+            loaded.field.to_string_layout(),
+            game.field.to_string_layout()
+        );
+    }
+
+    #[test]
+    fn saves_without_a_board_are_refused() {
+        assert!(SavedGame::parse("elapsed = 1\ncursor = A1\n").is_err());
+        assert!(SavedGame::parse("elapsed = 1\ncursor = Z9\n\n...\n").is_err());
+    }
+}
@@ -0,0 +1,152 @@
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use crate::difficulty::Difficulty;
+use crate::minefield::{MineField, MineFieldError, MineFieldState, Opening};
+use crate::position::Position;
+use crate::solver::{self, Verdict};
+use crate::view::{BoardView, Cell};
+
+/// How the solver got on over a run of games.
+#[derive(Debug, Clone)]
+pub struct Report {
+    pub seed: u64,
+    pub games: usize,
+    pub wins: usize,
+    pub guesses: usize,
+    pub elapsed: Duration,
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let games = self.games.max(1) as f64;
+
+        writeln!(f, "games:    {} (seed {})", self.games, self.seed)?;
+        writeln!(
+            f,
+            "won:      {} ({:.1}%)",
+            self.wins,
+            self.wins as f64 / games * 100.0
+        )?;
+        writeln!(f, "guesses:  {:.2} per game", self.guesses as f64 / games)?;
+        write!(
+            f,
+            "time:     {:.2}s ({:.2}ms per game)",
+            self.elapsed.as_secs_f64(),
+            self.elapsed.as_secs_f64() * 1000.0 / games
+        )
+    }
+}
+
+/// Pick the hidden tile least likely to be a mine, for when nothing
+/// can be deduced. Without any chances to go on, the first hidden
+/// tile is as good as any other.
+fn safest_guess(field: &MineField) -> Position {
+    let safest = solver::probabilities(field)
+        .into_iter()
+        .min_by(|a, b| a.mine.partial_cmp(&b.mine).unwrap());
+
+    match safest {
+        Some(probability) => probability.position,
+        None => field
+            .positions()
+            .into_iter()
+            .find(|&(row, column)| field.cell(row, column) == Cell::Hidden)
+            .map(Position::from)
+            .unwrap(),
+    }
+}
+
+/// Play a field out with the solver, making every move it can prove
+/// is right and guessing only when it's stuck. Returns the amount of
+/// guesses it had to make.
+fn play_out(field: &mut MineField) -> usize {
+    let mut guesses = 0;
+
+    while field.get_state() == MineFieldState::InProgress {
+        let deductions = solver::deduce(field);
+
+        if deductions.is_empty() {
+            field.flood_empty_tiles(safest_guess(field)).unwrap();
+            guesses += 1;
+            continue;
+        }
+
+        for deduction in deductions {
+            match deduction.verdict {
+                Verdict::Safe => field.flood_empty_tiles(deduction.position),
+                Verdict::Mine => field.toggle_flag(deduction.position),
+            }
+            .unwrap();
+        }
+    }
+
+    guesses
+}
+
+/// Generate some boards and have the solver play each of them to
+/// the end, starting from an opening like a game in the terminal.
+///
+/// The same seed generates the same boards, so that runs can be
+/// compared. Without one, a random seed is picked.
+pub fn run(
+    difficulty: Difficulty,
+    games: usize,
+    seed: Option<u64>,
+) -> Result<Report, MineFieldError> {
+    let (width, height, mines) = difficulty.dimensions();
+    let seed = seed.unwrap_or_else(rand::random);
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    let mut report = Report {
+        seed,
+        games,
+        wins: 0,
+        guesses: 0,
+        elapsed: Duration::default(),
+    };
+
+    let start = Instant::now();
+
+    for _ in 0..games {
+        let mut field = MineField::new_with(width, height, mines, &mut rng)?;
+        field.clear_first_opening_with(Opening::default(), &mut rng);
+
+        report.guesses += play_out(&mut field);
+
+        if field.get_state() == MineFieldState::Cleared {
+            report.wins += 1;
+        }
+    }
+
+    report.elapsed = start.elapsed();
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn solver_plays_every_game_to_the_end() {
+        let easy = Difficulty::Custom {
+            width: 8,
+            height: 8,
+            mines: 1,
+        };
+        let report = run(easy, 20, Some(3)).unwrap();
+        assert_eq!((report.games, report.wins, report.guesses), (20, 20, 0));
+
+        let hard = Difficulty::Custom {
+            width: 8,
+            height: 8,
+            mines: 20,
+        };
+        let report = run(hard, 20, Some(3)).unwrap();
+        assert!(report.wins < 20);
+        assert!(report.guesses >= 20 - report.wins);
+    }
+}
@@ -0,0 +1,257 @@
+//! A window to play in with the mouse, on the same engine as the
+//! terminal game. Build it with `cargo run --features gui --bin
+//! minesweeper-gui`.
+//!
+//! Left click digs a tile, or chords a number. Right click flags it.
+//! The buttons along the top start a new game at each difficulty.
+
+use macroquad::prelude::*;
+
+use minesweeper::difficulty::{self, Difficulty};
+use minesweeper::engine::{Action, Event, GameEngine};
+use minesweeper::minefield::{MineField, MineFieldState, Opening};
+use minesweeper::position::Position;
+use minesweeper::tile::TileState;
+
+/// How big each tile is, in pixels.
+const TILE_SIZE: f32 = 28.0;
+
+/// How tall the bar along the top is, with the buttons and timer.
+const HEADER_HEIGHT: f32 = 56.0;
+
+/// The colours of the numbers, from 1 to 8.
+const NUMBER_COLORS: [Color; 8] = [
+    BLUE, DARKGREEN, RED, DARKBLUE, MAROON, DARKPURPLE, BLACK, DARKGRAY,
+];
+
+/// A game in the window, and how it ended.
+struct Game {
+    engine: GameEngine,
+    difficulty: Difficulty,
+    time_taken: Option<f32>,
+
+    /// The mines that were dug up, to be drawn apart from the rest.
+    fatal_mines: Vec<Position>,
+}
+
+impl Game {
+    /// Start a game at a difficulty, with its first opening cleared,
+    /// and fit the window to it.
+    fn new(difficulty: Difficulty) -> Self {
+        let mut field = MineField::for_difficulty(difficulty).unwrap();
+        field.clear_first_opening(Opening::default());
+
+        let (window_width, window_height) = window_size(field.width(), field.height());
+        request_new_screen_size(window_width, window_height);
+
+        Self {
+            engine: GameEngine::new(field),
+            difficulty,
+            time_taken: None,
+            fatal_mines: Vec::new(),
+        }
+    }
+
+    /// Dig, flag or chord the tile that was clicked on.
+    fn click(&mut self, position: Position, is_right: bool) {
+        let field = self.engine.field();
+
+        let action = match field.get_tile_state(position) {
+            _ if is_right => Action::Flag,
+            Ok(TileState::Visible) => Action::Chord,
+            _ => Action::Dig,
+        };
+
+        self.engine.set_cursor(position);
+
+        for event in self.engine.apply(action) {
+            match event {
+                Event::GameWon => self.time_taken = Some(self.engine.elapsed().as_secs_f32()),
+                Event::GameLost => {
+                    self.time_taken = Some(self.engine.elapsed().as_secs_f32());
+                    self.fatal_mines = dug_mines(self.engine.field());
+                    self.engine.game_over();
+                }
+                _ => (),
+            }
+        }
+    }
+
+    /// Describe how the game is going, for the top right.
+    fn status(&self) -> String {
+        let field = self.engine.field();
+
+        match (field.get_state(), self.time_taken) {
+            (MineFieldState::Cleared, Some(time)) => format!("cleared in {:.1}s!", time),
+            (MineFieldState::Failed, _) => String::from("boom!"),
+            _ => format!(
+                "{} mines left  {}s",
+                field.mines_remaining(),
+                self.engine.elapsed().as_secs()
+            ),
+        }
+    }
+}
+
+/// The size of the window for a field, in pixels.
+fn window_size(width: usize, height: usize) -> (f32, f32) {
+    let width = (width as f32 * TILE_SIZE).max(440.0);
+    (width, height as f32 * TILE_SIZE + HEADER_HEIGHT)
+}
+
+/// The mines that have been dug up on a field.
+fn dug_mines(field: &MineField) -> Vec<Position> {
+    field
+        .iter_positions()
+        .filter(|&position| {
+            let tile = field.get_tile(position).unwrap();
+            tile.has_mine && tile.state == TileState::Visible
+        })
+        .collect()
+}
+
+/// The tile under the mouse, if it's over the field.
+fn tile_under_mouse(field: &MineField) -> Option<Position> {
+    let (x, y) = mouse_position();
+
+    if y < HEADER_HEIGHT || x < 0.0 {
+        return None;
+    }
+
+    let position = Position::new(
+        ((y - HEADER_HEIGHT) / TILE_SIZE) as usize,
+        (x / TILE_SIZE) as usize,
+    );
+
+    match field.position_is_valid(position) {
+        true => Some(position),
+        false => None,
+    }
+}
+
+/// Draw a button with a label, returning how wide it is.
+fn button(label: &str, x: f32, is_selected: bool) -> f32 {
+    let width = measure_text(label, None, 20, 1.0).width + 16.0;
+    let color = if is_selected { GRAY } else { WHITE };
+
+    draw_rectangle(x, 12.0, width, 30.0, color);
+    draw_rectangle_lines(x, 12.0, width, 30.0, 2.0, DARKGRAY);
+    draw_text(label, x + 8.0, 32.0, 20.0, BLACK);
+
+    width
+}
+
+/// Draw a flag on a tile, as a red pennant on a pole.
+fn draw_flag(x: f32, y: f32) {
+    draw_line(x + 10.0, y + 6.0, x + 10.0, y + 22.0, 2.0, BLACK);
+    draw_triangle(
+        vec2(x + 10.0, y + 6.0),
+        vec2(x + 21.0, y + 10.0),
+        vec2(x + 10.0, y + 14.0),
+        RED,
+    );
+}
+
+/// Draw every tile of the field below the header.
+fn draw_field(game: &Game) {
+    let field = game.engine.field();
+    let is_over = field.get_state() != MineFieldState::InProgress;
+
+    for position in field.iter_positions() {
+        let tile = field.get_tile(position).unwrap();
+        let x = position.column as f32 * TILE_SIZE;
+        let y = position.row as f32 * TILE_SIZE + HEADER_HEIGHT;
+        let centre = vec2(x + TILE_SIZE / 2.0, y + TILE_SIZE / 2.0);
+
+        if tile.is_void {
+            continue;
+        }
+
+        let background = match tile.state {
+            TileState::Visible if game.fatal_mines.contains(&position) => RED,
+            TileState::Visible => LIGHTGRAY,
+            _ => GRAY,
+        };
+
+        draw_rectangle(x, y, TILE_SIZE, TILE_SIZE, background);
+        draw_rectangle_lines(x, y, TILE_SIZE, TILE_SIZE, 1.0, DARKGRAY);
+
+        match tile.state {
+            TileState::Hidden => (),
+            TileState::Flagged => {
+                draw_flag(x, y);
+
+                // once it's all over, flags without mines are crossed out.
+                if is_over && !tile.has_mine {
+                    draw_line(x + 4.0, y + 4.0, x + 24.0, y + 24.0, 2.0, BLACK);
+                }
+            }
+            TileState::Visible if tile.has_mine => draw_circle(centre.x, centre.y, 8.0, BLACK),
+            TileState::Visible => match field.count_mines_near(position).unwrap() {
+                0 => (),
+                count => {
+                    let color = NUMBER_COLORS[count - 1];
+                    draw_text(&count.to_string(), x + 9.0, y + 21.0, 24.0, color);
+                }
+            },
+        }
+    }
+}
+
+fn window_conf() -> Conf {
+    let (width, height, _) = Difficulty::Beginner.dimensions();
+    let (window_width, window_height) = window_size(width, height);
+
+    Conf {
+        window_title: String::from("minesweeper"),
+        window_width: window_width as i32,
+        window_height: window_height as i32,
+        ..Default::default()
+    }
+}
+
+#[macroquad::main(window_conf)]
+async fn main() {
+    let mut game = Game::new(Difficulty::Beginner);
+
+    loop {
+        clear_background(LIGHTGRAY);
+
+        // the buttons start a new game, even at the same difficulty.
+        let mut x = 8.0;
+        let (mouse_x, mouse_y) = mouse_position();
+        let is_clicked = is_mouse_button_pressed(MouseButton::Left);
+
+        for difficulty in difficulty::CLASSIC {
+            let width = button(difficulty.name().unwrap(), x, difficulty == game.difficulty);
+            let is_over = (x..x + width).contains(&mouse_x) && (12.0..42.0).contains(&mouse_y);
+
+            if is_clicked && is_over {
+                game = Game::new(difficulty);
+            }
+
+            x += width + 8.0;
+        }
+
+        let status = game.status();
+        let status_width = measure_text(&status, None, 20, 1.0).width;
+        draw_text(
+            &status,
+            screen_width() - status_width - 8.0,
+            32.0,
+            20.0,
+            BLACK,
+        );
+
+        if let Some(position) = tile_under_mouse(game.engine.field()) {
+            if is_mouse_button_pressed(MouseButton::Left) {
+                game.click(position, false);
+            } else if is_mouse_button_pressed(MouseButton::Right) {
+                game.click(position, true);
+            }
+        }
+
+        draw_field(&game);
+        next_frame().await
+    }
+}
@@ -0,0 +1,442 @@
+//! A demo of the terminal game laid out with ratatui's widgets, on the
+//! same engine as the classic one. Build it with `cargo run --features
+//! tui --bin minesweeper-tui`.
+//!
+//! It only covers plain rounds at a preset difficulty: none of the
+//! classic game's modes, options, saves or scores are here, and the
+//! classic game still does its own layout with `write_text_beside` and
+//! `write_text_below`. Moving that over is still to be done.
+//!
+//! Each part of the screen is a widget in its own area: the board, a
+//! sidebar with the stats of the game, a popup with the keys, and a
+//! menu of difficulties. ratatui works out where everything goes each
+//! frame, so nothing has to be positioned by hand.
+
+use std::io::{self, Stdout};
+use std::time::Duration;
+
+use crossterm::event::{self, Event as TermEvent, KeyCode, KeyEventKind, KeyModifiers};
+use crossterm::execute;
+use crossterm::terminal::{self, EnterAlternateScreen, LeaveAlternateScreen};
+use ratatui::backend::CrosstermBackend;
+use ratatui::buffer::Buffer;
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, Widget};
+use ratatui::{Frame, Terminal};
+
+use minesweeper::difficulty::{Difficulty, CLASSIC};
+use minesweeper::engine::{Action, Event, GameEngine};
+use minesweeper::minefield::{MineField, MineFieldState, Opening};
+use minesweeper::motion::Motion;
+use minesweeper::position::Position;
+use minesweeper::tile::TileState;
+
+/// How wide the stats sidebar is, in columns.
+const SIDEBAR_WIDTH: u16 = 24;
+
+/// How often the screen is redrawn without a key, to keep the timer going.
+const TICK: Duration = Duration::from_millis(250);
+
+/// The keys, as they're listed in the help popup.
+const HELP: [(&str, &str); 9] = [
+    ("arrows, hjkl", "move"),
+    ("0, $", "row start, row end"),
+    ("space", "dig"),
+    ("f", "flag"),
+    ("d", "chord"),
+    ("p", "pause"),
+    ("n", "new game"),
+    ("?", "show or hide the keys"),
+    ("q", "quit"),
+];
+
+/// The terminal in raw mode on the alternate screen, which is put back
+/// however it's dropped, even while panicking.
+struct Guard(Terminal<CrosstermBackend<Stdout>>);
+
+impl Guard {
+    fn new() -> io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        execute!(io::stdout(), EnterAlternateScreen)?;
+        Ok(Guard(Terminal::new(CrosstermBackend::new(io::stdout()))?))
+    }
+}
+
+impl Drop for Guard {
+    fn drop(&mut self) {
+        execute!(io::stdout(), LeaveAlternateScreen).ok();
+        terminal::disable_raw_mode().ok();
+        self.0.show_cursor().ok();
+    }
+}
+
+/// The field drawn as a widget, two columns to a tile, with the tile
+/// under the cursor highlighted.
+struct Board<'a> {
+    field: &'a MineField,
+    cursor: Position,
+    fatal_mines: &'a [Position],
+}
+
+impl Board<'_> {
+    /// How much room the board takes up, in columns and rows.
+    fn size(&self) -> (u16, u16) {
+        (self.field.width() as u16 * 2, self.field.height() as u16)
+    }
+
+    /// The character for a tile and its style.
+    fn cell(&self, position: Position) -> (String, Style) {
+        let tile = self.field.get_tile(position).unwrap();
+        let is_over = self.field.get_state() != MineFieldState::InProgress;
+        let plain = Style::default();
+
+        if tile.is_void {
+            return (String::from(" "), plain);
+        }
+
+        match tile.state {
+            TileState::Hidden => (String::from("▒"), plain),
+            TileState::Flagged if is_over && !tile.has_mine => (
+                String::from("⚑"),
+                plain.fg(Color::Red).add_modifier(Modifier::CROSSED_OUT),
+            ),
+            TileState::Flagged => (String::from("⚑"), plain.fg(Color::LightMagenta)),
+            TileState::Visible if tile.has_mine => match self.fatal_mines.contains(&position) {
+                true => (String::from("✹"), plain.bg(Color::Red)),
+                false => (String::from("✹"), plain),
+            },
+            TileState::Visible => match self.field.count_mines_near(position).unwrap() {
+                0 => (String::from(" "), plain),
+                count => match self.field.theme().number_color(count) {
+                    Some(value) => (count.to_string(), plain.fg(Color::Indexed(value))),
+                    None => (count.to_string(), plain),
+                },
+            },
+        }
+    }
+}
+
+impl Widget for Board<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        for position in self.field.iter_positions() {
+            let x = area.x + position.column as u16 * 2;
+            let y = area.y + position.row as u16;
+
+            // anything past the edge of the area is left off.
+            if x >= area.right() || y >= area.bottom() {
+                continue;
+            }
+
+            let (symbol, mut style) = self.cell(position);
+
+            if self.cursor == position {
+                style = style.add_modifier(Modifier::REVERSED);
+            }
+
+            buf.set_string(x, y, symbol, style);
+        }
+    }
+}
+
+/// What the screen is showing.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+enum Screen {
+    Menu,
+    Playing,
+}
+
+/// A game, and everything around it on the screen.
+struct App {
+    engine: GameEngine,
+    difficulty: Difficulty,
+    screen: Screen,
+    menu: ListState,
+    show_help: bool,
+    is_paused: bool,
+    time_taken: Option<Duration>,
+
+    /// The mines that were dug up, to be drawn apart from the rest.
+    fatal_mines: Vec<Position>,
+}
+
+impl App {
+    fn new() -> Self {
+        let mut app = Self {
+            engine: GameEngine::new(new_field(Difficulty::Beginner)),
+            difficulty: Difficulty::Beginner,
+            screen: Screen::Menu,
+            menu: ListState::default(),
+            show_help: false,
+            is_paused: false,
+            time_taken: None,
+            fatal_mines: Vec::new(),
+        };
+
+        app.menu.select(Some(0));
+        app
+    }
+
+    /// Start a new game at a difficulty from the menu.
+    fn start(&mut self, difficulty: Difficulty) {
+        self.engine = GameEngine::new(new_field(difficulty));
+        self.difficulty = difficulty;
+        self.screen = Screen::Playing;
+        self.is_paused = false;
+        self.time_taken = None;
+        self.fatal_mines.clear();
+    }
+
+    /// Handle a key, returning whether the game should carry on.
+    fn handle_key(&mut self, code: KeyCode, modifiers: KeyModifiers) -> bool {
+        // an interrupt leaves straight away, wherever it's pressed.
+        if code == KeyCode::Char('c') && modifiers.contains(KeyModifiers::CONTROL) {
+            return false;
+        }
+
+        if self.show_help {
+            self.show_help = false;
+            return true;
+        }
+
+        match self.screen {
+            Screen::Menu => self.handle_menu_key(code),
+            Screen::Playing => self.handle_game_key(code),
+        }
+    }
+
+    fn handle_menu_key(&mut self, code: KeyCode) -> bool {
+        let selected = self.menu.selected().unwrap_or(0);
+
+        match code {
+            KeyCode::Up | KeyCode::Char('k') => self.menu.select(Some(selected.saturating_sub(1))),
+            KeyCode::Down | KeyCode::Char('j') => self
+                .menu
+                .select(Some((selected + 1).min(CLASSIC.len() - 1))),
+            KeyCode::Enter | KeyCode::Char(' ') => self.start(CLASSIC[selected]),
+            KeyCode::Char('?') => self.show_help = true,
+            KeyCode::Char('q') | KeyCode::Esc => return false,
+            _ => (),
+        }
+
+        true
+    }
+
+    fn handle_game_key(&mut self, code: KeyCode) -> bool {
+        let action = match code {
+            KeyCode::Up | KeyCode::Char('k') => Action::Move(Motion::Up(1)),
+            KeyCode::Down | KeyCode::Char('j') => Action::Move(Motion::Down(1)),
+            KeyCode::Left | KeyCode::Char('h') => Action::Move(Motion::Left(1)),
+            KeyCode::Right | KeyCode::Char('l') => Action::Move(Motion::Right(1)),
+            KeyCode::Char('0') => Action::Move(Motion::RowStart),
+            KeyCode::Char('$') => Action::Move(Motion::RowEnd),
+            KeyCode::Char(' ') => Action::Dig,
+            KeyCode::Char('f') => Action::Flag,
+            KeyCode::Char('d') => Action::Chord,
+            KeyCode::Char('p') => Action::Pause,
+            KeyCode::Char('?') => {
+                self.show_help = true;
+                return true;
+            }
+            KeyCode::Char('n') | KeyCode::Esc => {
+                self.screen = Screen::Menu;
+                return true;
+            }
+            KeyCode::Char('q') => return false,
+            _ => return true,
+        };
+
+        for event in self.engine.apply(action) {
+            match event {
+                Event::Paused => self.is_paused = true,
+                Event::Resumed => self.is_paused = false,
+                Event::GameWon => self.time_taken = Some(self.engine.elapsed()),
+                Event::GameLost => {
+                    self.time_taken = Some(self.engine.elapsed());
+                    self.fatal_mines = dug_mines(self.engine.field());
+                    self.engine.game_over();
+                }
+                _ => (),
+            }
+        }
+
+        true
+    }
+
+    /// The lines of the stats sidebar.
+    fn stats(&self) -> Vec<String> {
+        let field = self.engine.field();
+        let (revealed, total) = field.progress();
+        let seconds = self.time_taken.unwrap_or_else(|| self.engine.elapsed());
+
+        let status = match field.get_state() {
+            MineFieldState::Cleared => "cleared!",
+            MineFieldState::Failed => "boom!",
+            MineFieldState::InProgress if self.is_paused => "paused",
+            MineFieldState::InProgress => "playing",
+        };
+
+        vec![
+            format!(
+                "{} {}x{}",
+                self.difficulty.name().unwrap(),
+                field.width(),
+                field.height()
+            ),
+            String::new(),
+            format!("mines left  {}", field.mines_remaining()),
+            format!("time        {}s", seconds.as_secs()),
+            format!("revealed    {}/{}", revealed, total),
+            String::new(),
+            String::from(status),
+            String::new(),
+            String::from("press ? for keys"),
+        ]
+    }
+
+    /// Draw everything that's showing onto a frame.
+    fn draw(&mut self, frame: &mut Frame) {
+        let area = frame.size();
+        let field = self.engine.field();
+
+        let board = Board {
+            field,
+            cursor: self.engine.cursor(),
+            fatal_mines: &self.fatal_mines,
+        };
+
+        let (board_width, board_height) = board.size();
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([
+                Constraint::Length(board_width + 2),
+                Constraint::Length(SIDEBAR_WIDTH),
+                Constraint::Min(0),
+            ])
+            .split(area);
+
+        let board_area = Rect {
+            height: (board_height + 2).min(columns[0].height),
+            ..columns[0]
+        };
+
+        let board_block = Block::default()
+            .borders(Borders::ALL)
+            .title(" minesweeper ");
+        let inner = board_block.inner(board_area);
+        frame.render_widget(board_block, board_area);
+
+        // a paused game is hidden, so it can't be studied for free.
+        if self.is_paused {
+            frame.render_widget(Paragraph::new("paused! press 'p'"), inner);
+        } else {
+            frame.render_widget(board, inner);
+        }
+
+        let stats: Vec<_> = self.stats().into_iter().map(ListItem::new).collect();
+        frame.render_widget(
+            List::new(stats).block(Block::default().borders(Borders::ALL).title(" stats ")),
+            Rect {
+                height: board_area.height,
+                ..columns[1]
+            },
+        );
+
+        if self.screen == Screen::Menu {
+            self.draw_menu(frame, area);
+        }
+
+        if self.show_help {
+            draw_help(frame, area);
+        }
+    }
+
+    /// Draw the menu of difficulties in a popup.
+    fn draw_menu(&mut self, frame: &mut Frame, area: Rect) {
+        let items: Vec<_> = CLASSIC
+            .iter()
+            .map(|difficulty| {
+                let (width, height, mines) = difficulty.dimensions();
+                let name = difficulty.name().unwrap();
+
+                ListItem::new(format!("{:<13}{}x{}, {} mines", name, width, height, mines))
+            })
+            .collect();
+
+        let popup = centred(area, 36, CLASSIC.len() as u16 + 2);
+        let menu = List::new(items)
+            .block(Block::default().borders(Borders::ALL).title(" new game "))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+
+        frame.render_widget(Clear, popup);
+        frame.render_stateful_widget(menu, popup, &mut self.menu);
+    }
+}
+
+/// Make a field at a difficulty, with its first opening cleared.
+fn new_field(difficulty: Difficulty) -> MineField {
+    let mut field = MineField::for_difficulty(difficulty).unwrap();
+    field.clear_first_opening(Opening::default());
+    field
+}
+
+/// The mines that have been dug up on a field.
+fn dug_mines(field: &MineField) -> Vec<Position> {
+    field
+        .iter_positions()
+        .filter(|&position| {
+            let tile = field.get_tile(position).unwrap();
+            tile.has_mine && tile.state == TileState::Visible
+        })
+        .collect()
+}
+
+/// An area of a given size in the middle of another, shrunk to fit.
+fn centred(area: Rect, width: u16, height: u16) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+
+    Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    }
+}
+
+/// Draw the keys in a popup over everything else.
+fn draw_help(frame: &mut Frame, area: Rect) {
+    let lines: Vec<_> = HELP
+        .iter()
+        .map(|(keys, action)| format!("{:<14}{}", keys, action))
+        .collect();
+
+    let popup = centred(area, 40, HELP.len() as u16 + 2);
+    let help = Paragraph::new(lines.join("\n"))
+        .block(Block::default().borders(Borders::ALL).title(" keys "));
+
+    frame.render_widget(Clear, popup);
+    frame.render_widget(help, popup);
+}
+
+fn main() -> io::Result<()> {
+    let mut guard = Guard::new()?;
+    let mut app = App::new();
+
+    loop {
+        guard.0.draw(|frame| app.draw(frame))?;
+
+        // without a key, the screen is redrawn anyway for the timer.
+        if !event::poll(TICK)? {
+            continue;
+        }
+
+        if let TermEvent::Key(key) = event::read()? {
+            if key.kind == KeyEventKind::Press && !app.handle_key(key.code, key.modifiers) {
+                break;
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,283 @@
+use std::collections::BTreeSet;
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+use crate::cli::Puzzles;
+use crate::minefield::MineField;
+use crate::storage;
+
+const PROGRESS_FILE: &str = "campaign";
+
+/// The puzzles that come with the game, in the order they're played.
+const BUILT_IN: [&str; 5] = [
+    include_str!("../puzzles/01-first-steps.txt"),
+    include_str!("../puzzles/02-hands-off.txt"),
+    include_str!("../puzzles/03-eight-clicks.txt"),
+    include_str!("../puzzles/04-the-wall.txt"),
+    include_str!("../puzzles/05-the-long-way-round.txt"),
+];
+
+/// What it takes to solve a puzzle.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum Objective {
+    /// Clear the board, however it's done.
+    Clear,
+
+    /// Clear the board without placing a single flag.
+    NoFlags,
+
+    /// Clear the board in at most this many clicks.
+    Clicks(usize),
+}
+
+impl Objective {
+    /// Parse an objective as it's written in a puzzle file:
+    /// `clear`, `no-flags` or `clicks N`.
+    fn parse(text: &str) -> Option<Self> {
+        let words: Vec<&str> = text.split_whitespace().collect();
+
+        match words[..] {
+            ["clear"] => Some(Objective::Clear),
+            ["no-flags"] => Some(Objective::NoFlags),
+            ["clicks", clicks] => Some(Objective::Clicks(clicks.parse().ok()?)),
+            _ => None,
+        }
+    }
+
+    /// Describe the objective to the player.
+    pub fn describe(self) -> String {
+        match self {
+            Objective::Clear => String::from("clear the board"),
+            Objective::NoFlags => String::from("clear it without flags"),
+            Objective::Clicks(clicks) => format!("clear it in {} clicks", clicks),
+        }
+    }
+
+    /// Check if a cleared board met the objective, going by the flags
+    /// the player placed and how many clicks they took.
+    pub fn is_met(self, flags_placed: usize, clicks: usize) -> bool {
+        match self {
+            Objective::Clear => true,
+            Objective::NoFlags => flags_placed == 0,
+            Objective::Clicks(most) => clicks <= most,
+        }
+    }
+}
+
+/// A board to be solved, with its name and objective.
+#[derive(Clone)]
+pub struct Puzzle {
+    pub name: String,
+    pub objective: Objective,
+    field: MineField,
+}
+
+impl Puzzle {
+    /// Read a puzzle from its file.
+    ///
+    /// The file starts with lines like `# name: first steps` and
+    /// `# objective: no-flags`, and the rest is the board as a layout.
+    /// Anything revealed in the layout is revealed from the start.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut name = None;
+        let mut objective = Objective::Clear;
+
+        let header: Vec<&str> = text
+            .lines()
+            .take_while(|line| line.starts_with('#'))
+            .collect();
+
+        for line in header.iter() {
+            let (key, value) = match line[1..].split_once(':') {
+                Some((key, value)) => (key.trim(), value.trim()),
+                None => continue,
+            };
+
+            match key {
+                "name" => name = Some(String::from(value)),
+                "objective" => {
+                    objective = Objective::parse(value)
+                        .ok_or_else(|| format!("unknown objective '{}'.", value))?
+                }
+                _ => return Err(format!("unknown puzzle setting '{}'.", key)),
+            }
+        }
+
+        let layout: Vec<&str> = text.lines().skip(header.len()).collect();
+
+        Ok(Self {
+            name: name.ok_or("a puzzle needs a '# name:' line.")?,
+            objective,
+            field: MineField::from_layout(&layout.join("\n")).map_err(|e| e.to_string())?,
+        })
+    }
+
+    /// The puzzle's board, as it starts.
+    #[inline]
+    pub fn field(&self) -> &MineField {
+        &self.field
+    }
+}
+
+/// A run through a pack of puzzles, one after another, remembering
+/// which have been solved between games.
+pub struct Campaign {
+    puzzles: Vec<Puzzle>,
+    current: usize,
+    solved: BTreeSet<String>,
+}
+
+impl Campaign {
+    /// Start a campaign on the first puzzle that hasn't been solved,
+    /// going by the names of the puzzles solved so far.
+    fn new(puzzles: Vec<Puzzle>, solved: BTreeSet<String>) -> Self {
+        let current = puzzles
+            .iter()
+            .position(|puzzle| !solved.contains(&puzzle.name))
+            .unwrap_or(0);
+
+        Self {
+            puzzles,
+            current,
+            solved,
+        }
+    }
+
+    /// Read every puzzle in a directory, played in order of their file names.
+    fn read_dir(dir: &Path) -> Result<Vec<Puzzle>, Box<dyn Error>> {
+        let mut paths: Vec<_> = fs::read_dir(dir)
+            .map_err(|e| format!("couldn't read '{}': {}", dir.display(), e))?
+            .filter_map(|entry| Some(entry.ok()?.path()))
+            .filter(|path| path.is_file())
+            .collect();
+        paths.sort();
+
+        if paths.is_empty() {
+            return Err(format!("there are no puzzles in '{}'.", dir.display()).into());
+        }
+
+        paths
+            .iter()
+            .map(|path| {
+                let text = fs::read_to_string(path)?;
+                Ok(Puzzle::parse(&text)
+                    .map_err(|e| format!("couldn't read '{}': {}", path.display(), e))?)
+            })
+            .collect()
+    }
+
+    /// Load a pack of puzzles, along with the progress through them.
+    pub fn load(puzzles: &Puzzles) -> Result<Self, Box<dyn Error>> {
+        let puzzles = match puzzles {
+            Puzzles::BuiltIn => BUILT_IN
+                .iter()
+                .map(|text| Puzzle::parse(text).unwrap())
+                .collect(),
+            Puzzles::Directory(dir) => Self::read_dir(Path::new(dir))?,
+        };
+
+        let progress = storage::read_data_file(PROGRESS_FILE).unwrap_or_default();
+        let solved = progress.lines().map(String::from).collect();
+
+        Ok(Self::new(puzzles, solved))
+    }
+
+    /// The puzzle being played.
+    #[inline]
+    pub fn puzzle(&self) -> &Puzzle {
+        &self.puzzles[self.current]
+    }
+
+    /// The puzzle being played, counted from one, and how many there are.
+    pub fn position(&self) -> (usize, usize) {
+        (self.current + 1, self.puzzles.len())
+    }
+
+    /// Check if every puzzle has been solved.
+    pub fn is_complete(&self) -> bool {
+        self.puzzles
+            .iter()
+            .all(|puzzle| self.solved.contains(&puzzle.name))
+    }
+
+    /// Create the field for the current puzzle, set up like another field.
+    pub fn field(&self, like: &MineField) -> MineField {
+        let mut field = self.puzzle().field.clone();
+
+        field.set_rules(like.rules());
+        field.set_theme(like.theme());
+        field.set_skin(like.skin());
+
+        field
+    }
+
+    /// Note that the current puzzle was solved, saving the progress.
+    pub fn solve(&mut self) {
+        self.solved.insert(self.puzzle().name.clone());
+
+        let progress: Vec<&str> = self.solved.iter().map(String::as_str).collect();
+
+        // losing track of progress isn't worth stopping the game over.
+        storage::write_data_file(PROGRESS_FILE, &progress.join("\n")).ok();
+    }
+
+    /// Move on to the next puzzle, going back to the first after the last.
+    pub fn advance(&mut self) {
+        self.current = (self.current + 1) % self.puzzles.len();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::minefield::MineFieldState;
+    use crate::solver::{self, Verdict};
+
+    #[test]
+    fn built_in_puzzles_can_be_solved_by_logic() {
+        for text in BUILT_IN.iter() {
+            let puzzle = Puzzle::parse(text).unwrap();
+            let mut field = puzzle.field.clone();
+            assert_eq!(field.get_state(), MineFieldState::InProgress);
+
+            // every move is forced, so nothing is left to chance.
+            while field.get_state() == MineFieldState::InProgress {
+                let deductions = solver::deduce(&field);
+                assert!(!deductions.is_empty(), "'{}' needs a guess", puzzle.name);
+
+                for deduction in deductions {
+                    match deduction.verdict {
+                        Verdict::Safe => field.flood_empty_tiles(deduction.position),
+                        Verdict::Mine => field.toggle_flag(deduction.position),
+                    }
+                    .unwrap();
+                }
+            }
+
+            assert_eq!(field.get_state(), MineFieldState::Cleared);
+        }
+    }
+
+    #[test]
+    fn campaigns_start_on_the_first_unsolved_puzzle() {
+        let puzzles: Vec<Puzzle> = BUILT_IN.iter().map(|t| Puzzle::parse(t).unwrap()).collect();
+        let solved = ["first steps", "eight clicks"]
+            .iter()
+            .map(|s| s.to_string());
+        let mut campaign = Campaign::new(puzzles, solved.collect());
+
+        assert_eq!(campaign.position(), (2, 5));
+        assert_eq!(campaign.puzzle().objective, Objective::NoFlags);
+        assert!(!campaign.puzzle().objective.is_met(1, 3));
+
+        campaign.current = 4;
+        campaign.advance();
+        assert_eq!(campaign.position(), (1, 5));
+        assert!(!campaign.is_complete());
+
+        assert_eq!(Objective::parse("clicks 8"), Some(Objective::Clicks(8)));
+        assert!(Puzzle::parse("# objective: speedy\n.*").is_err());
+        assert!(Puzzle::parse(".*").is_err());
+    }
+}
@@ -0,0 +1,1521 @@
+use std::fmt;
+use std::time::Duration;
+
+use crate::difficulty::Difficulty;
+use crate::export::Format;
+use crate::minefield::{MineField, Opening};
+use crate::mode::Mode;
+use crate::net;
+use crate::pace::Reference;
+use crate::rules::Rules;
+use crate::theme::{Skin, Theme};
+use crate::topology::Adjacency;
+
+pub const USAGE: &str = "\
+usage: minesweeper [hex] [beginner|intermediate|expert|relaxed|evil] [--density D]
+       minesweeper [hex] custom WxH_M
+       minesweeper [hex] [custom] --width W --height H (--mines M|--density D)
+       minesweeper [hex] --mask FILE --mines M
+       minesweeper [hex] gauntlet
+       minesweeper [hex] load FILE
+       minesweeper campaign [DIR]
+       minesweeper daily [hex] [beginner|intermediate|expert|custom WxH_M]
+       minesweeper versus [hex] [beginner|intermediate|expert|custom WxH_M|load FILE]
+       minesweeper host [hex] [beginner|intermediate|expert|custom WxH_M] [--port N]
+       minesweeper join HOST[:PORT]
+       minesweeper watch HOST:PORT
+       minesweeper coop [OPTIONS] [--port N]
+       minesweeper coop join HOST[:PORT]
+       minesweeper analyze --position FILE
+       minesweeper generate --difficulty D [--seed N] [--format F] [--count N] --print|--out DIR
+       minesweeper solve --difficulty D [--games N] [--seed N]
+       minesweeper headless [--json] [OPTIONS]
+       minesweeper --accessible [OPTIONS]
+       minesweeper stats
+       minesweeper tutorial
+       minesweeper continue
+
+without a difficulty, a menu asks which one to play. with 'hex', the
+field is a grid of hexagons where each tile touches six others.
+'relaxed' and 'evil' are expert-sized fields with 10% and 24% of their
+tiles mined. --density fills any of them, or a custom size, with a
+share of mines instead, like '0.18' or '18%'.
+
+a gauntlet plays beginner, intermediate and expert back to back on one
+timer. losing any of them ends the run.
+
+'load' plays the board in a layout file, where '.' is a hidden tile,
+'*' a hidden mine, 'f' and 'F' are flags on a safe tile and a mine, a
+number is a revealed tile and 'X' is a dug mine. a file ending in .mbf
+is read as a Minesweeper Board Format board instead. loaded boards
+don't set best times.
+
+'campaign' plays through a pack of puzzles, each a board with some of
+it revealed and a goal, like clearing it without flags. the puzzles
+that come with the game are played unless a directory of puzzle files
+is given. progress is saved, and picks up at the first unsolved puzzle.
+
+'tutorial' teaches the game on a few small boards, from digging and
+reading the numbers to flags, chords and the 1-2-1 and 1-2-2-1
+patterns. each step asks for a move, and only that move carries on.
+
+'continue' carries on with the last game that was left unfinished,
+with its timer and cursor where they were. games are saved every so
+often as they're played, and when 'q' or ctrl-c leaves them. a game
+is only put away for good once it's won or lost.
+gauntlets, campaigns, dailies and games with others aren't saved.
+
+'daily' plays the day's challenge, a board picked by the date so that
+everyone gets the same one. only the first go at each day's board is
+counted in the stats, where winning days in a row make a streak.
+
+'versus' is a race for two players sharing a keyboard. each takes a
+turn on the same board, one after the other, and whoever clears it
+faster wins. if neither clears it, whoever got further does. --seed
+replays a board from before.
+
+'host' and 'join' race someone over the network. the host picks the
+board and waits for the other player to join, on port 3999 unless
+--port is given. both play the same board at once, seeing how far
+the other has got beside the field.
+
+'coop' shares a board with someone over the network. the host plays
+as normal, with the guest's cursor on the field in magenta, and the
+guest's moves are made on it in the order they come in.
+
+'watch' follows a game being played with --broadcast somewhere else,
+without being able to touch it.
+
+'solve' has the built-in solver play lots of boards, guessing only
+when it has to, and reports how many it won, how often it guessed
+and how long it took.
+
+'headless' plays a game without the terminal interface, for bots and
+scripts. it takes the same options as a game, with beginner as the
+difficulty if none is given. moves are read from stdin one per line,
+as 'dig R C', 'flag R C' or 'chord R C' with rows and columns counted
+from 0, and the board is written to stdout after each one. with
+--json, it's written as a line of JSON instead.
+
+--accessible plays a game as plain lines of text that a screen reader
+can follow, without drawing anything. commands are typed like 'd 3 4'
+to dig, 'f 3 4' to flag or 'look 3 4', with rows and columns counted
+from 1, and every change is described in words. type 'h' for the rest.
+
+options:
+  -w, --width W     width of a custom field, in tiles
+  -H, --height H    height of a custom field, in tiles
+  -m, --mines M     amount of mines on a custom field
+      --density D   share of the tiles that are mines, from 0 to 1 or
+                    as a percentage
+      --mask FILE   take the shape of the field from a text file, with
+                    '#' for tiles and '.' for gaps. needs --mines.
+      --opening N   start with an opening of at least N tiles, placing
+                    the mines again until there is one
+      --safe-start  start in the middle of the field, on an opening
+      --target WxH  win by clearing a WxH area in the middle of the field
+      --no-chord-cascade
+                    reveal only the tiles around a chord, even zeroes
+      --strict-flags
+                    refuse to place more flags than there are mines
+      --no-flags, --nf
+                    play without flags, chording off the numbers alone
+      --adjacency A which tiles a number counts: 'knight' for the ones
+                    a knight's move away, or steps like '-2,0 2,0 0,-2
+                    0,2' as rows down and columns right. can't be hex.
+      --pace REF    compare your pace while playing against a reference
+                    time: 'record', 'best', or a time in seconds
+      --casual      allow moves to be undone with u and redone with
+                    ctrl-r, even a dig that hits a mine. casual games
+                    don't set best times.
+      --auto-flag   flag mines automatically once a number shows they
+                    must be there. these games don't set best times.
+      --lives N     dig up to N - 1 mines without losing. each one is
+                    flagged and costs a life. these games don't set
+                    best times.
+      --leaderboard keep a leaderboard of winning times until the game
+                    is closed, with initials entered after each win
+      --tips        show tips for controls you haven't used yet
+      --theme T     draw the tiles in a theme: 'default', 'colorblind'
+                    for colourblind-safe numbers, or 'mono' for plain
+                    ASCII without colour
+      --skin S      draw the tiles with 'ascii' characters (the default),
+                    'unicode' symbols and shaded blocks, or 'emoji'
+      --seed N      place the mines and pick the openings the same way
+                    every time for the same seed. seeded games don't
+                    set best times.
+      --time-attack MINUTES
+                    clear as many boards as you can before the time runs
+                    out, going straight on to a new board after each one
+      --countdown SECONDS
+                    clear the field before the time runs out, or lose
+      --endless     play on a field that grows whenever you clear close
+                    to its edge, until you dig up a mine
+      --broadcast PORT
+                    let others watch the game from another terminal
+                    with 'minesweeper watch HOST:PORT'
+  -h, --help        show this help screen
+
+analyze options:
+  --position FILE   a text grid of the position to analyse, or - for
+                    stdin. hidden tiles are '.', flags are 'F' and
+                    revealed tiles are their number.
+  --mines M         the total amount of mines in the position, which
+                    makes the mine chances of every tile more accurate
+
+generate options:
+  --difficulty D    beginner, intermediate, expert or WxH_M
+  --seed N          place the mines the same way every time for the
+                    same seed and difficulty. random if not given.
+  --format F        how to print the board: 'ascii' (the default),
+                    'json', 'mbf', or 'layout' to play it with 'load'
+  --count N         how many boards to generate, 1 by default. each
+                    takes the seed after the one before.
+  --print           print the whole board, mines included, instead of
+                    playing it
+  --out DIR         write each board to its own file in DIR, with an
+                    index.tsv giving the seed and 3BV of each one
+
+solve options:
+  --difficulty D    beginner, intermediate, expert or WxH_M
+  --games N         how many boards the solver plays, 1000 by default
+  --seed N          generate the same boards every time for the same
+                    seed and difficulty. random if not given.
+
+controls:
+  arrows - move cursor, or h, j, k and l
+  5l     - move five tiles right, or any count with any direction
+  0, $   - jump to the start or end of the row
+  gg, G  - jump to the top or bottom row, or row N with Ngg or NG
+  shift-arrows
+         - move five tiles at a time
+  ctrl-arrows
+         - jump to the next hidden tile that way
+  home, end, page up, page down
+         - jump to the start or end of the row, or the top or bottom row
+  :      - jump to a tile by its name, like D7 for column D, row 7
+  space  - dig a tile
+  f      - place a flag
+  d      - perform a chord
+  m      - leave a note on a hidden tile: a maybe-mine, a safe guess
+           or marks A to C, pressed again to go through them
+  ?      - highlight a tile that can be deduced, or the safest guess
+  o      - show/hide the chance of each hidden tile being a mine
+  e      - show/hide a log of the latest moves beside the field
+  u      - undo a move, in a casual game
+  ctrl-r - redo a move, in a casual game
+  c      - show/hide mine counts for the endgame
+  p      - pause/unpause, hiding the board until play carries on
+  q      - quit game
+  ctrl-c - quit straight away, from anywhere
+  r      - once a game is over, retry the same board
+  n      - once a game is over, start a new board
+  v      - once a game is over, review the flags and the last move";
+
+/// How many boards `solve` plays without --games.
+const DEFAULT_SOLVE_GAMES: usize = 1000;
+
+/// A field shaped by a mask file, with the amount of mines to put in it.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Shape {
+    pub mask: String,
+    pub mines: usize,
+}
+
+/// Where the puzzles of a campaign come from.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum Puzzles {
+    BuiltIn,
+    Directory(String),
+}
+
+/// Named difficulties beyond the classic three: a size, and how densely
+/// it's filled with mines.
+const PRESETS: [(&str, usize, usize, f64); 2] = [("relaxed", 30, 16, 0.10), ("evil", 30, 16, 0.24)];
+
+/// Look up a named difficulty beyond the classic three.
+pub fn preset(name: &str) -> Option<Difficulty> {
+    PRESETS
+        .iter()
+        .find(|(preset, ..)| *preset == name)
+        .map(|&(_, width, height, density)| Difficulty::Custom {
+            width,
+            height,
+            mines: MineField::mines_for_density(width, height, density),
+        })
+}
+
+/// Everything needed to set up a game from the command line.
+///
+/// Without a difficulty, shape or layout, the player is asked
+/// for a difficulty when the game starts.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Options {
+    pub difficulty: Option<Difficulty>,
+    pub shape: Option<Shape>,
+    pub layout: Option<String>,
+    pub gauntlet: bool,
+    pub mode: Mode,
+    pub opening: Opening,
+    pub hex: bool,
+    pub adjacency: Option<Adjacency>,
+    pub target: Option<(usize, usize)>,
+    pub rules: Rules,
+    pub pace: Option<Reference>,
+    pub casual: bool,
+    pub auto_flag: bool,
+    pub lives: Option<usize>,
+    pub leaderboard: bool,
+    pub tips: bool,
+    pub theme: Option<Theme>,
+    pub skin: Option<Skin>,
+    pub seed: Option<u64>,
+    pub daily: bool,
+    pub versus: bool,
+    pub broadcast: Option<u16>,
+    pub campaign: Option<Puzzles>,
+}
+
+/// What the program has been asked to do.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum Command {
+    Play(Options),
+    Analyze {
+        position: String,
+        mines: Option<usize>,
+    },
+    Generate {
+        difficulty: Difficulty,
+        seed: Option<u64>,
+        format: Format,
+        count: usize,
+
+        /// The directory to write the boards to, or `None` to print them.
+        out: Option<String>,
+    },
+    Solve {
+        difficulty: Difficulty,
+        games: usize,
+        seed: Option<u64>,
+    },
+    Headless {
+        options: Options,
+        json: bool,
+    },
+    Accessible(Options),
+    Host {
+        options: Options,
+        port: u16,
+    },
+    Join {
+        address: String,
+        options: Options,
+    },
+    Watch {
+        address: String,
+    },
+    Coop {
+        options: Options,
+        port: u16,
+    },
+    JoinCoop {
+        address: String,
+    },
+    Stats,
+    Tutorial,
+    Continue,
+    Help,
+}
+
+/// A problem with the command line arguments.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct UsageError(String);
+
+impl fmt::Display for UsageError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+fn usage_error<T>(message: String) -> Result<T, UsageError> {
+    Err(UsageError(message))
+}
+
+/// Parse a positive integer, naming the value in any error.
+fn parse_number(name: &str, value: &str) -> Result<usize, UsageError> {
+    match value.parse() {
+        Ok(0) | Err(_) => usage_error(format!(
+            "{} must be a positive integer, got '{}'.",
+            name, value
+        )),
+        Ok(number) => Ok(number),
+    }
+}
+
+/// Parse a density of mines, as a fraction like `0.18` or a percentage
+/// like `18%`.
+fn parse_density(value: &str) -> Result<f64, UsageError> {
+    let density = match value.strip_suffix('%') {
+        Some(percent) => percent.parse::<f64>().map(|percent| percent / 100.0),
+        None => value.parse(),
+    };
+
+    match density {
+        Ok(density) if density > 0.0 && density < 1.0 => Ok(density),
+        _ => usage_error(format!(
+            "density must be between 0 and 1, or a percentage, got '{}'.",
+            value
+        )),
+    }
+}
+
+/// Parse the old-style custom geometry, in the form WxH_M.
+fn parse_geometry(config: &str) -> Result<(usize, usize, usize), UsageError> {
+    let format_error = || UsageError(format!("expected format 'WxH_M', got '{}'.", config));
+
+    let mut first_split = config.split('_');
+    let geom = first_split.next().ok_or_else(format_error)?;
+    let mines = first_split.next().ok_or_else(format_error)?;
+
+    let mut geom_split = geom.split('x');
+    let width = geom_split.next().ok_or_else(format_error)?;
+    let height = geom_split.next().ok_or_else(format_error)?;
+
+    if first_split.next().is_some() || geom_split.next().is_some() {
+        return Err(format_error());
+    }
+
+    Ok((
+        parse_number("width", width)?,
+        parse_number("height", height)?,
+        mines
+            .parse()
+            .or_else(|_| usage_error(format!("mines must be an integer, got '{}'.", mines)))?,
+    ))
+}
+
+/// Parse a size in the form WxH.
+fn parse_size(size: &str) -> Result<(usize, usize), UsageError> {
+    let mut split = size.split('x');
+
+    match (split.next(), split.next(), split.next()) {
+        (Some(width), Some(height), None) => Ok((
+            parse_number("width", width)?,
+            parse_number("height", height)?,
+        )),
+        _ => usage_error(format!("expected a size like 'WxH', got '{}'.", size)),
+    }
+}
+
+/// Make sure a custom field can actually be played.
+pub fn validate(width: usize, height: usize, mines: usize) -> Result<Difficulty, UsageError> {
+    let tiles = width
+        .checked_mul(height)
+        .ok_or_else(|| UsageError(format!("a {}x{} field is far too large.", width, height)))?;
+
+    if mines >= tiles {
+        return usage_error(format!(
+            "a {}x{} field has room for at most {} mines, got {}.",
+            width,
+            height,
+            tiles - 1,
+            mines
+        ));
+    }
+
+    Ok(Difficulty::Custom {
+        width,
+        height,
+        mines,
+    })
+}
+
+/// Parse the arguments of the `analyze` subcommand.
+fn parse_analyze_args(args: &[String]) -> Result<Command, UsageError> {
+    let mut position = None;
+    let mut mines = None;
+    let mut args = args.iter().map(|arg| arg.trim());
+
+    while let Some(arg) = args.next() {
+        let (flag, inline_value) = match arg.find('=') {
+            Some(index) if arg.starts_with("--") => (&arg[..index], Some(&arg[index + 1..])),
+            _ => (arg, None),
+        };
+
+        let mut value_for = |name: &str| match inline_value {
+            Some(value) => Ok(value),
+            None => args
+                .next()
+                .ok_or_else(|| UsageError(format!("'{}' needs a value.", name))),
+        };
+
+        match flag {
+            "-h" | "--help" => return Ok(Command::Help),
+            "--position" => position = Some(String::from(value_for(flag)?)),
+            "-m" | "--mines" => {
+                let value = value_for(flag)?;
+                mines = Some(value.parse().or_else(|_| {
+                    usage_error(format!("mines must be an integer, got '{}'.", value))
+                })?)
+            }
+            _ => return usage_error(format!("unexpected argument '{}'.", arg)),
+        }
+    }
+
+    match position {
+        Some(position) => Ok(Command::Analyze { position, mines }),
+        None => usage_error(String::from("analyze needs a --position to look at.")),
+    }
+}
+
+/// Parse a difficulty given by name, or as a custom size like `WxH_M`.
+fn parse_difficulty(value: &str) -> Result<Difficulty, UsageError> {
+    match value {
+        name if Difficulty::from_name(name).is_some() => Ok(Difficulty::from_name(name).unwrap()),
+        name if preset(name).is_some() => Ok(preset(name).unwrap()),
+        geometry => {
+            let (width, height, mines) = parse_geometry(geometry)?;
+            validate(width, height, mines)
+        }
+    }
+}
+
+/// Parse the seed for generating boards.
+fn parse_seed(value: &str) -> Result<u64, UsageError> {
+    value
+        .parse()
+        .or_else(|_| usage_error(format!("seed must be a whole number, got '{}'.", value)))
+}
+
+/// Parse the arguments of the `generate` subcommand.
+fn parse_generate_args(args: &[String]) -> Result<Command, UsageError> {
+    let mut difficulty = None;
+    let mut seed = None;
+    let mut format = Format::Ascii;
+    let mut count = 1;
+    let mut print = false;
+    let mut out = None;
+    let mut args = args.iter().map(|arg| arg.trim());
+
+    while let Some(arg) = args.next() {
+        let (flag, inline_value) = match arg.find('=') {
+            Some(index) if arg.starts_with("--") => (&arg[..index], Some(&arg[index + 1..])),
+            _ => (arg, None),
+        };
+
+        let mut value_for = |name: &str| match inline_value {
+            Some(value) => Ok(value),
+            None => args
+                .next()
+                .ok_or_else(|| UsageError(format!("'{}' needs a value.", name))),
+        };
+
+        match flag {
+            "-h" | "--help" => return Ok(Command::Help),
+            "--print" => print = true,
+            "--out" => out = Some(String::from(value_for(flag)?)),
+            "--count" => count = parse_number("count", value_for(flag)?)?,
+            "--difficulty" => difficulty = Some(parse_difficulty(value_for(flag)?)?),
+            "--seed" => seed = Some(parse_seed(value_for(flag)?)?),
+            "--format" => {
+                let value = value_for(flag)?;
+                format = Format::parse(value).ok_or_else(|| {
+                    UsageError(format!(
+                        "--format must be 'ascii', 'json', 'mbf' or 'layout', got '{}'.",
+                        value
+                    ))
+                })?
+            }
+            _ => return usage_error(format!("unexpected argument '{}'.", arg)),
+        }
+    }
+
+    match (difficulty, print, out) {
+        (None, ..) => usage_error(String::from("generate needs a --difficulty.")),
+        (_, true, Some(_)) => {
+            usage_error(String::from("generate can't use both --print and --out."))
+        }
+        (_, false, None) => usage_error(String::from(
+            "generate needs --print or --out, to say what to do with the boards.",
+        )),
+        (Some(difficulty), _, out) => Ok(Command::Generate {
+            difficulty,
+            seed,
+            format,
+            count,
+            out,
+        }),
+    }
+}
+
+/// Parse the arguments of the `solve` subcommand.
+fn parse_solve_args(args: &[String]) -> Result<Command, UsageError> {
+    let mut difficulty = None;
+    let mut games = DEFAULT_SOLVE_GAMES;
+    let mut seed = None;
+    let mut args = args.iter().map(|arg| arg.trim());
+
+    while let Some(arg) = args.next() {
+        let (flag, inline_value) = match arg.find('=') {
+            Some(index) if arg.starts_with("--") => (&arg[..index], Some(&arg[index + 1..])),
+            _ => (arg, None),
+        };
+
+        let mut value_for = |name: &str| match inline_value {
+            Some(value) => Ok(value),
+            None => args
+                .next()
+                .ok_or_else(|| UsageError(format!("'{}' needs a value.", name))),
+        };
+
+        match flag {
+            "-h" | "--help" => return Ok(Command::Help),
+            "--difficulty" => difficulty = Some(parse_difficulty(value_for(flag)?)?),
+            "--games" => games = parse_number("games", value_for(flag)?)?,
+            "--seed" => seed = Some(parse_seed(value_for(flag)?)?),
+            _ => return usage_error(format!("unexpected argument '{}'.", arg)),
+        }
+    }
+
+    match difficulty {
+        Some(difficulty) => Ok(Command::Solve {
+            difficulty,
+            games,
+            seed,
+        }),
+        None => usage_error(String::from("solve needs a --difficulty.")),
+    }
+}
+
+fn parse_port(value: &str) -> Result<u16, UsageError> {
+    value.parse().or_else(|_| {
+        usage_error(format!(
+            "port must be a number up to 65535, got '{}'.",
+            value
+        ))
+    })
+}
+
+/// Check that the options of a game can be raced over the network, where
+/// only the seed, size and shape of the board are shared.
+fn check_race_options(options: &Options) -> Result<(), UsageError> {
+    if options.gauntlet
+        || options.campaign.is_some()
+        || options.daily
+        || options.versus
+        || options.layout.is_some()
+        || options.shape.is_some()
+        || options.target.is_some()
+        || options.adjacency.is_some()
+        || options.mode != Mode::Classic
+        || options.opening != Opening::default()
+    {
+        return usage_error(String::from(
+            "a race can't be a gauntlet, a campaign, a daily challenge or a versus \
+             game, or be on a loaded board or use --mask, --target, --adjacency, \
+             --time-attack, --countdown, --endless, --opening or --safe-start.",
+        ));
+    }
+
+    Ok(())
+}
+
+/// Take the port out of the arguments of a game that's hosted for
+/// another player, returning it and the rest of the arguments.
+fn split_port(args: &[String]) -> Result<(u16, Vec<String>), UsageError> {
+    let mut port = net::DEFAULT_PORT;
+    let mut rest = Vec::new();
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        match arg.trim() {
+            "--port" => match args.next() {
+                Some(value) => port = parse_port(value.trim())?,
+                None => return usage_error(String::from("'--port' needs a value.")),
+            },
+            arg if arg.starts_with("--port=") => port = parse_port(&arg["--port=".len()..])?,
+            _ => rest.push(arg.clone()),
+        }
+    }
+
+    Ok((port, rest))
+}
+
+/// Parse the arguments of `host`, which are those of a game and the port.
+fn parse_host_args(args: &[String]) -> Result<Command, UsageError> {
+    let (port, rest) = split_port(args)?;
+
+    match parse_args(&rest)? {
+        Command::Play(options) => {
+            check_race_options(&options)?;
+            Ok(Command::Host { options, port })
+        }
+        Command::Help => Ok(Command::Help),
+        _ => usage_error(String::from("host takes the options of a game.")),
+    }
+}
+
+/// Parse the arguments of `join`: where to find the host, and options for
+/// how the game looks and plays. The board is the host's to pick.
+fn parse_join_args(args: &[String]) -> Result<Command, UsageError> {
+    let address = match args.first().map(|arg| arg.trim()) {
+        Some("-h" | "--help") => return Ok(Command::Help),
+        Some(address) if !address.starts_with('-') => address,
+        _ => return usage_error(String::from("join needs the address of the host.")),
+    };
+
+    // without a port, the host is on the usual one.
+    let address = match address.contains(':') {
+        true => String::from(address),
+        false => format!("{}:{}", address, net::DEFAULT_PORT),
+    };
+
+    match parse_args(&args[1..])? {
+        Command::Play(options)
+            if options.difficulty.is_some() || options.hex || options.seed.is_some() =>
+        {
+            usage_error(String::from(
+                "the host picks the board, so join can't be given a difficulty, hex or --seed.",
+            ))
+        }
+        Command::Play(options) => {
+            check_race_options(&options)?;
+            Ok(Command::Join { address, options })
+        }
+        Command::Help => Ok(Command::Help),
+        _ => usage_error(String::from("join takes the address of the host.")),
+    }
+}
+
+/// Parse the arguments of `coop`, which either hosts a game with the
+/// options of a game and the port, or joins one with `join HOST[:PORT]`.
+fn parse_coop_args(args: &[String]) -> Result<Command, UsageError> {
+    if args.first().map(|arg| arg.trim()) == Some("join") {
+        return match &args[1..] {
+            [address] if !address.starts_with('-') => Ok(Command::JoinCoop {
+                address: match address.contains(':') {
+                    true => String::from(address.trim()),
+                    false => format!("{}:{}", address.trim(), net::DEFAULT_PORT),
+                },
+            }),
+            _ => usage_error(String::from("coop join needs the address of the host.")),
+        };
+    }
+
+    let (port, rest) = split_port(args)?;
+
+    // the guest's moves can't be taken back, or spared by a life.
+    match parse_args(&rest)? {
+        Command::Play(options)
+            if options.versus
+                || options.casual
+                || options.lives.is_some()
+                || options.mode == Mode::Endless =>
+        {
+            usage_error(String::from(
+                "a co-op game can't be a versus game, or use --casual, --lives or --endless.",
+            ))
+        }
+        Command::Play(options) => Ok(Command::Coop { options, port }),
+        Command::Help => Ok(Command::Help),
+        _ => usage_error(String::from("coop takes the options of a game.")),
+    }
+}
+
+/// Turn the program arguments (excluding the program name) into a command.
+pub fn parse_args(args: &[String]) -> Result<Command, UsageError> {
+    // an accessible game takes the same arguments as one on the screen.
+    if args.iter().any(|arg| arg.trim() == "--accessible") {
+        let args: Vec<String> = args
+            .iter()
+            .filter(|arg| arg.trim() != "--accessible")
+            .cloned()
+            .collect();
+
+        return match parse_args(&args)? {
+            Command::Play(options)
+                if options.gauntlet
+                    || options.campaign.is_some()
+                    || options.versus
+                    || options.mode != Mode::Classic
+                    || options.lives.is_some()
+                    || options.casual
+                    || options.broadcast.is_some() =>
+            {
+                usage_error(String::from(
+                    "--accessible plays a single game, without lives, undo or a broadcast.",
+                ))
+            }
+            Command::Play(options) => Ok(Command::Accessible(options)),
+            Command::Help => Ok(Command::Help),
+            _ => usage_error(String::from("--accessible takes the options of a game.")),
+        };
+    }
+
+    if args.first().map(|arg| arg.trim()) == Some("analyze") {
+        return parse_analyze_args(&args[1..]);
+    }
+
+    if args.first().map(|arg| arg.trim()) == Some("generate") {
+        return parse_generate_args(&args[1..]);
+    }
+
+    if args.first().map(|arg| arg.trim()) == Some("solve") {
+        return parse_solve_args(&args[1..]);
+    }
+
+    // a daily challenge is a game like any other, on a board picked by the date.
+    if args.first().map(|arg| arg.trim()) == Some("daily") {
+        return match parse_args(&args[1..])? {
+            Command::Play(options)
+                if options.gauntlet
+                    || options.campaign.is_some()
+                    || options.versus
+                    || options.layout.is_some()
+                    || options.shape.is_some()
+                    || options.seed.is_some()
+                    || options.opening != Opening::default() =>
+            {
+                usage_error(String::from(
+                    "a daily challenge can't be a gauntlet, a campaign, a versus \
+                     game, a loaded board, or use --mask, --seed, --opening or \
+                     --safe-start.",
+                ))
+            }
+            Command::Play(options) => Ok(Command::Play(Options {
+                daily: true,
+                ..options
+            })),
+            Command::Help => Ok(Command::Help),
+            _ => usage_error(String::from("daily takes the options of a game.")),
+        };
+    }
+
+    // a versus game is played by each player in turn on the same board.
+    if args.first().map(|arg| arg.trim()) == Some("versus") {
+        return match parse_args(&args[1..])? {
+            Command::Play(options)
+                if options.gauntlet
+                    || options.campaign.is_some()
+                    || options.daily
+                    || options.broadcast.is_some()
+                    || options.mode.carries_on()
+                    || options.mode == Mode::Endless =>
+            {
+                usage_error(String::from(
+                    "a versus game can't be a gauntlet, a campaign or a daily challenge, \
+                     or be broadcast or use --time-attack or --endless.",
+                ))
+            }
+            Command::Play(options) => Ok(Command::Play(Options {
+                versus: true,
+                ..options
+            })),
+            Command::Help => Ok(Command::Help),
+            _ => usage_error(String::from("versus takes the options of a game.")),
+        };
+    }
+
+    // a headless game takes the same arguments as one in the terminal.
+    if args.first().map(|arg| arg.trim()) == Some("headless") {
+        let json = args[1..].iter().any(|arg| arg.trim() == "--json");
+        let args: Vec<String> = args[1..]
+            .iter()
+            .filter(|arg| arg.trim() != "--json")
+            .cloned()
+            .collect();
+
+        return match parse_args(&args)? {
+            Command::Play(options)
+                if options.gauntlet
+                    || options.campaign.is_some()
+                    || options.versus
+                    || options.mode != Mode::Classic =>
+            {
+                usage_error(String::from(
+                    "a gauntlet, campaign, versus or timed game can't be played headless.",
+                ))
+            }
+            Command::Play(options) => Ok(Command::Headless { options, json }),
+            Command::Help => Ok(Command::Help),
+            _ => usage_error(String::from("headless takes the options of a game.")),
+        };
+    }
+
+    if args.first().map(|arg| arg.trim()) == Some("host") {
+        return parse_host_args(&args[1..]);
+    }
+
+    if args.first().map(|arg| arg.trim()) == Some("join") {
+        return parse_join_args(&args[1..]);
+    }
+
+    if args.first().map(|arg| arg.trim()) == Some("coop") {
+        return parse_coop_args(&args[1..]);
+    }
+
+    if args.first().map(|arg| arg.trim()) == Some("watch") {
+        return match args[1..] {
+            [ref address] if address.trim() == "-h" || address.trim() == "--help" => {
+                Ok(Command::Help)
+            }
+            [ref address] if !address.starts_with('-') => Ok(Command::Watch {
+                address: String::from(address.trim()),
+            }),
+            _ => usage_error(String::from(
+                "watch needs the address of a game, like 'HOST:PORT'.",
+            )),
+        };
+    }
+
+    if args.first().map(|arg| arg.trim()) == Some("stats") {
+        return match args.get(1) {
+            None => Ok(Command::Stats),
+            Some(arg) => usage_error(format!("unexpected argument '{}'.", arg)),
+        };
+    }
+
+    if args.first().map(|arg| arg.trim()) == Some("continue") {
+        return match args.get(1) {
+            None => Ok(Command::Continue),
+            Some(arg) => usage_error(format!("unexpected argument '{}'.", arg)),
+        };
+    }
+
+    if args.first().map(|arg| arg.trim()) == Some("tutorial") {
+        return match args.get(1) {
+            None => Ok(Command::Tutorial),
+            Some(arg) => usage_error(format!("unexpected argument '{}'.", arg)),
+        };
+    }
+
+    // a hex game takes all the same arguments as a square one.
+    let (hex, args) = match args.first().map(|arg| arg.trim()) {
+        Some("hex") => (true, &args[1..]),
+        _ => (false, args),
+    };
+
+    let mut difficulty = None;
+    let mut geometry = None;
+    let mut width = None;
+    let mut height = None;
+    let mut mines = None;
+    let mut mask = None;
+    let mut layout = None;
+    let mut puzzles = None;
+    let mut target = None;
+    let mut rules = Rules::default();
+    let mut pace = None;
+    let mut casual = false;
+    let mut auto_flag = false;
+    let mut lives = None;
+    let mut leaderboard = false;
+    let mut tips = false;
+    let mut theme = None;
+    let mut skin = None;
+    let mut seed = None;
+    let mut broadcast = None;
+    let mut mode = Mode::Classic;
+    let mut density = None;
+    let mut opening = Opening::default();
+    let mut adjacency = None;
+
+    let mut args = args.iter().map(|arg| arg.trim());
+
+    while let Some(arg) = args.next() {
+        // flags that take a value can be given as `--flag value` or `--flag=value`.
+        let (flag, inline_value) = match arg.find('=') {
+            Some(index) if arg.starts_with("--") => (&arg[..index], Some(&arg[index + 1..])),
+            _ => (arg, None),
+        };
+
+        let mut value_for = |name: &str| match inline_value {
+            Some(value) => Ok(value),
+            None => args
+                .next()
+                .ok_or_else(|| UsageError(format!("'{}' needs a value.", name))),
+        };
+
+        match flag {
+            "-h" | "--help" => return Ok(Command::Help),
+            "--mask" => mask = Some(String::from(value_for(flag)?)),
+            "--target" => target = Some(parse_size(value_for(flag)?)?),
+            "--no-chord-cascade" => rules.chord_cascade = false,
+            "--strict-flags" => rules.strict_flags = true,
+            "--no-flags" | "--nf" => rules.no_flags = true,
+            "--pace" => {
+                let value = value_for(flag)?;
+                pace = Some(Reference::parse(value).ok_or_else(|| {
+                    UsageError(format!(
+                        "--pace must be 'record', 'best' or a time in seconds, got '{}'.",
+                        value
+                    ))
+                })?)
+            }
+            "--casual" => casual = true,
+            "--auto-flag" => auto_flag = true,
+            "--lives" => lives = Some(parse_number("lives", value_for(flag)?)?),
+            "--leaderboard" => leaderboard = true,
+            "--tips" => tips = true,
+            "--seed" => seed = Some(parse_seed(value_for(flag)?)?),
+            "--broadcast" => broadcast = Some(parse_port(value_for(flag)?)?),
+            "--time-attack" | "--countdown" | "--endless" if mode != Mode::Classic => {
+                return usage_error(String::from(
+                    "only one of --time-attack, --countdown and --endless can be used.",
+                ));
+            }
+            "--time-attack" => {
+                let minutes = parse_number("minutes", value_for(flag)?)? as u64;
+                mode = Mode::TimeAttack(Duration::from_secs(minutes * 60))
+            }
+            "--countdown" => {
+                let seconds = parse_number("seconds", value_for(flag)?)? as u64;
+                mode = Mode::Countdown(Duration::from_secs(seconds))
+            }
+            "--endless" => mode = Mode::Endless,
+            "--adjacency" => {
+                let value = value_for(flag)?;
+                adjacency = Some(Adjacency::parse(value).ok_or_else(|| {
+                    UsageError(format!(
+                        "--adjacency must be 'knight' or steps like '-2,0 2,0', got '{}'.",
+                        value
+                    ))
+                })?)
+            }
+            "--skin" => {
+                let value = value_for(flag)?;
+                skin = Some(Skin::parse(value).ok_or_else(|| {
+                    UsageError(format!(
+                        "--skin must be 'ascii', 'unicode' or 'emoji', got '{}'.",
+                        value
+                    ))
+                })?)
+            }
+            "--theme" => {
+                let value = value_for(flag)?;
+                theme = Some(Theme::parse(value).ok_or_else(|| {
+                    UsageError(format!(
+                        "--theme must be 'default', 'colorblind' or 'mono', got '{}'.",
+                        value
+                    ))
+                })?)
+            }
+            "-w" | "--width" => width = Some(parse_number("width", value_for(flag)?)?),
+            "-H" | "--height" => height = Some(parse_number("height", value_for(flag)?)?),
+            "-m" | "--mines" => {
+                let value = value_for(flag)?;
+                mines = Some(value.parse().or_else(|_| {
+                    usage_error(format!("mines must be an integer, got '{}'.", value))
+                })?)
+            }
+            "--density" => density = Some(parse_density(value_for(flag)?)?),
+            "--opening" => opening.min_tiles = parse_number("opening", value_for(flag)?)?,
+            "--safe-start" => opening.is_centred = true,
+
+            "beginner" | "intermediate" | "expert" | "custom" | "gauntlet" | "load"
+            | "campaign"
+                if difficulty.is_none() =>
+            {
+                difficulty = Some(flag)
+            }
+
+            _ if preset(flag).is_some() && difficulty.is_none() => difficulty = Some(flag),
+
+            _ if flag.starts_with('-') => {
+                return usage_error(format!("unknown option '{}'.", flag));
+            }
+
+            _ if difficulty == Some("custom") && geometry.is_none() => {
+                geometry = Some(parse_geometry(flag)?)
+            }
+
+            _ if difficulty == Some("load") && layout.is_none() => {
+                layout = Some(String::from(flag))
+            }
+
+            _ if difficulty == Some("campaign") && puzzles.is_none() => {
+                puzzles = Some(String::from(flag))
+            }
+
+            _ if difficulty.is_none() => {
+                return usage_error(format!("unknown game difficulty '{}'.", flag));
+            }
+
+            _ => return usage_error(format!("unexpected argument '{}'.", flag)),
+        }
+    }
+
+    // an endless field grows row by row on a square grid, filling the
+    // new tiles itself, and undoing a move can't shrink it back.
+    if mode == Mode::Endless
+        && (hex || mask.is_some() || difficulty == Some("load") || target.is_some() || casual)
+    {
+        return usage_error(String::from(
+            "an endless game can't be hex, a loaded board, or use --mask, --target or --casual.",
+        ));
+    }
+
+    // a hex grid already decides which tiles touch, and an endless field
+    // only keeps its numbers right when it grows if tiles touch both ways.
+    if adjacency.is_some() && (hex || mode == Mode::Endless) {
+        return usage_error(String::from(
+            "--adjacency can't be used with hex or --endless.",
+        ));
+    }
+
+    // auto-flagging would put down the very flags a flagless game refuses.
+    if rules.no_flags && auto_flag {
+        return usage_error(String::from("--no-flags can't be used with --auto-flag."));
+    }
+
+    // a mask gives the field its size, so only the mines are needed.
+    if mask.is_some() && density.is_some() {
+        return usage_error(String::from(
+            "--mask can't be used with --density, since the mines are given with --mines.",
+        ));
+    }
+
+    if let Some(mask) = mask {
+        if difficulty.is_some() || width.is_some() || height.is_some() {
+            return usage_error(String::from(
+                "--mask sets the size of the field, so it can't be used with a difficulty, \
+                 --width or --height.",
+            ));
+        }
+
+        let mines = mines.ok_or_else(|| UsageError(String::from("--mask needs --mines too.")))?;
+
+        return Ok(Command::Play(Options {
+            difficulty: None,
+            shape: Some(Shape { mask, mines }),
+            layout: None,
+            gauntlet: false,
+            mode,
+            opening,
+            hex,
+            adjacency,
+            target,
+            rules,
+            pace,
+            casual,
+            auto_flag,
+            lives,
+            leaderboard,
+            tips,
+            theme,
+            skin,
+            seed,
+            daily: false,
+            versus: false,
+            broadcast,
+            campaign: None,
+        }));
+    }
+
+    let has_flags = width.is_some() || height.is_some() || mines.is_some();
+
+    // a layout gives the field its size and mines, and even its progress.
+    if difficulty == Some("load") {
+        let layout = layout.ok_or_else(|| UsageError(String::from("load needs a file.")))?;
+
+        if has_flags || density.is_some() || opening != Opening::default() {
+            return usage_error(String::from(
+                "a loaded board can't be used with --width, --height, --mines, --density, \
+                 --opening or --safe-start.",
+            ));
+        }
+
+        return Ok(Command::Play(Options {
+            difficulty: None,
+            shape: None,
+            layout: Some(layout),
+            gauntlet: false,
+            mode,
+            opening,
+            hex,
+            adjacency,
+            target,
+            rules,
+            pace,
+            casual,
+            auto_flag,
+            lives,
+            leaderboard,
+            tips,
+            theme,
+            skin,
+            seed,
+            daily: false,
+            versus: false,
+            broadcast,
+            campaign: None,
+        }));
+    }
+
+    // each puzzle in a campaign is a whole board of its own.
+    if difficulty == Some("campaign") {
+        if has_flags
+            || density.is_some()
+            || opening != Opening::default()
+            || target.is_some()
+            || hex
+            || adjacency.is_some()
+            || mode != Mode::Classic
+        {
+            return usage_error(String::from(
+                "a campaign can't be used with --width, --height, --mines, --density, \
+                 --opening, --safe-start, --target, hex, --adjacency, --time-attack, \
+                 --countdown or --endless.",
+            ));
+        }
+
+        return Ok(Command::Play(Options {
+            difficulty: None,
+            shape: None,
+            layout: None,
+            gauntlet: false,
+            mode,
+            opening,
+            hex,
+            adjacency,
+            target,
+            rules,
+            pace,
+            casual,
+            auto_flag,
+            lives,
+            leaderboard,
+            tips,
+            theme,
+            skin,
+            seed,
+            daily: false,
+            versus: false,
+            broadcast,
+            campaign: Some(match puzzles {
+                Some(dir) => Puzzles::Directory(dir),
+                None => Puzzles::BuiltIn,
+            }),
+        }));
+    }
+
+    let gauntlet = difficulty == Some("gauntlet");
+
+    // every stage of a gauntlet is timed together, so nothing about
+    // the boards or how their times are compared can be changed.
+    if gauntlet
+        && (has_flags
+            || density.is_some()
+            || target.is_some()
+            || pace.is_some()
+            || leaderboard
+            || mode != Mode::Classic)
+    {
+        return usage_error(String::from(
+            "a gauntlet can't be used with --width, --height, --mines, --density, \
+             --target, --pace, --leaderboard, --time-attack, --countdown or --endless.",
+        ));
+    }
+
+    let difficulty = match difficulty {
+        Some(name) if name != "custom" && has_flags => {
+            return usage_error(String::from(
+                "--width, --height and --mines can only be used with custom games.",
+            ));
+        }
+
+        None if !has_flags => None,
+        Some("gauntlet") => Some(Difficulty::Beginner),
+        Some(name) if Difficulty::from_name(name).is_some() => Difficulty::from_name(name),
+        Some(name) if preset(name).is_some() => preset(name),
+
+        _ => match (geometry, width, height, mines) {
+            (Some(_), Some(_), _, _) | (Some(_), _, Some(_), _) | (Some(_), _, _, Some(_)) => {
+                return usage_error(String::from(
+                    "give either 'WxH_M' or --width/--height/--mines, not both.",
+                ));
+            }
+            (Some((w, h, m)), None, None, None) => Some(validate(w, h, m)?),
+            (None, Some(w), Some(h), Some(m)) => Some(validate(w, h, m)?),
+
+            // the density fills in the mines below.
+            (None, Some(w), Some(h), None) if density.is_some() => Some(validate(w, h, 0)?),
+            _ => {
+                return usage_error(String::from(
+                    "custom games need a width, height and amount of mines.",
+                ));
+            }
+        },
+    };
+
+    // a density fills whatever size was picked with its share of mines.
+    let difficulty = match (difficulty, density) {
+        (difficulty, None) => difficulty,
+        (None, Some(_)) => {
+            return usage_error(String::from(
+                "--density needs a difficulty, or a --width and --height.",
+            ));
+        }
+        (Some(_), Some(_)) if mines.is_some() || geometry.is_some() => {
+            return usage_error(String::from(
+                "give either an amount of mines or --density, not both.",
+            ));
+        }
+        (Some(difficulty), Some(density)) => {
+            let (width, height, _) = difficulty.dimensions();
+            Some(validate(
+                width,
+                height,
+                MineField::mines_for_density(width, height, density),
+            )?)
+        }
+    };
+
+    Ok(Command::Play(Options {
+        difficulty,
+        shape: None,
+        layout: None,
+        gauntlet,
+        mode,
+        opening,
+        hex,
+        adjacency,
+        target,
+        rules,
+        pace,
+        casual,
+        auto_flag,
+        lives,
+        leaderboard,
+        tips,
+        theme,
+        skin,
+        seed,
+        daily: false,
+        versus: false,
+        broadcast,
+        campaign: None,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Parse a command line given as one string, split on spaces.
+    fn parse(line: &str) -> Result<Command, UsageError> {
+        let args: Vec<String> = line.split_whitespace().map(String::from).collect();
+        parse_args(&args)
+    }
+
+    /// Parse a command line that should be played, giving its options.
+    fn options(line: &str) -> Options {
+        match parse(line) {
+            Ok(Command::Play(options)) => options,
+            other => panic!("'{}' wasn't a game: {:?}", line, other),
+        }
+    }
+
+    /// Parse a command line that should be refused, giving the reason.
+    fn error(line: &str) -> String {
+        match parse(line) {
+            Err(error) => error.to_string(),
+            Ok(command) => panic!("'{}' was allowed: {:?}", line, command),
+        }
+    }
+
+    #[test]
+    fn difficulties_are_read_by_name() {
+        assert_eq!(options("").difficulty, None);
+        assert_eq!(options("beginner").difficulty, Some(Difficulty::Beginner));
+        assert_eq!(
+            options("intermediate").difficulty,
+            Some(Difficulty::Intermediate)
+        );
+        assert_eq!(options("expert").difficulty, Some(Difficulty::Expert));
+        assert_eq!(options("evil").difficulty, preset("evil"));
+
+        let gauntlet = options("gauntlet");
+        assert!(gauntlet.gauntlet);
+        assert_eq!(gauntlet.difficulty, Some(Difficulty::Beginner));
+
+        assert!(options("hex expert").hex);
+        assert_eq!(error("novice"), "unknown game difficulty 'novice'.");
+    }
+
+    #[test]
+    fn custom_sizes_are_read_either_way() {
+        let custom = Some(Difficulty::Custom {
+            width: 20,
+            height: 10,
+            mines: 30,
+        });
+
+        assert_eq!(options("custom 20x10_30").difficulty, custom);
+        assert_eq!(options("custom -w 20 -H 10 -m 30").difficulty, custom);
+        assert_eq!(
+            options("custom --width=20 --height=10 --mines=30").difficulty,
+            custom
+        );
+        assert_eq!(
+            options("custom -w 20 -H 10 --density 15%").difficulty,
+            Some(Difficulty::Custom {
+                width: 20,
+                height: 10,
+                mines: MineField::mines_for_density(20, 10, 0.15),
+            })
+        );
+        assert_eq!(options("--target 3x2 expert").target, Some((3, 2)));
+    }
+
+    #[test]
+    fn malformed_sizes_are_refused() {
+        for geometry in ["20x10", "20x10_30_1", "20x10x5_30", "20_30", "20by10_30"] {
+            assert!(
+                error(&format!("custom {}", geometry)).starts_with("expected format 'WxH_M'"),
+                "{}",
+                geometry
+            );
+        }
+
+        assert_eq!(
+            error("custom 0x10_5"),
+            "width must be a positive integer, got '0'."
+        );
+        assert_eq!(
+            error("custom 10xten_5"),
+            "height must be a positive integer, got 'ten'."
+        );
+        assert_eq!(
+            error("custom 10x10_many"),
+            "mines must be an integer, got 'many'."
+        );
+        assert_eq!(
+            error("custom 3x3_9"),
+            "a 3x3 field has room for at most 8 mines, got 9."
+        );
+        assert_eq!(
+            error("expert --target 3"),
+            "expected a size like 'WxH', got '3'."
+        );
+        assert_eq!(
+            error("expert --target 3x2x1"),
+            "expected a size like 'WxH', got '3x2x1'."
+        );
+        assert!(error("expert --density 1.5").starts_with("density must be between 0 and 1"));
+    }
+
+    #[test]
+    fn flags_without_values_are_refused() {
+        assert_eq!(error("custom -w"), "'-w' needs a value.");
+        assert_eq!(error("expert --lives"), "'--lives' needs a value.");
+        assert_eq!(error("expert --seed"), "'--seed' needs a value.");
+        assert_eq!(error("--mask"), "'--mask' needs a value.");
+        assert_eq!(error("analyze --position"), "'--position' needs a value.");
+        assert_eq!(error("load"), "load needs a file.");
+        assert_eq!(
+            error("expert --frobnicate"),
+            "unknown option '--frobnicate'."
+        );
+        assert_eq!(error("expert beginner"), "unexpected argument 'beginner'.");
+    }
+
+    #[test]
+    fn clashing_options_are_refused() {
+        let clashes = [
+            (
+                "expert --time-attack 3 --endless",
+                "only one of --time-attack, --countdown and --endless can be used.",
+            ),
+            (
+                "expert --endless --casual",
+                "an endless game can't be hex, a loaded board, or use --mask, --target or --casual.",
+            ),
+            (
+                "hex expert --endless",
+                "an endless game can't be hex, a loaded board, or use --mask, --target or --casual.",
+            ),
+            (
+                "--endless --mask shape.txt --mines 5",
+                "an endless game can't be hex, a loaded board, or use --mask, --target or --casual.",
+            ),
+            (
+                "expert --endless --target 3x3",
+                "an endless game can't be hex, a loaded board, or use --mask, --target or --casual.",
+            ),
+            (
+                "hex expert --adjacency knight",
+                "--adjacency can't be used with hex or --endless.",
+            ),
+            (
+                "expert --no-flags --auto-flag",
+                "--no-flags can't be used with --auto-flag.",
+            ),
+            (
+                "--mask shape.txt --density 0.2",
+                "--mask can't be used with --density, since the mines are given with --mines.",
+            ),
+            (
+                "expert --mask shape.txt --mines 5",
+                "--mask sets the size of the field, so it can't be used with a difficulty, \
+                 --width or --height.",
+            ),
+            ("--mask shape.txt", "--mask needs --mines too."),
+            (
+                "load board.txt --mines 5",
+                "a loaded board can't be used with --width, --height, --mines, --density, \
+                 --opening or --safe-start.",
+            ),
+            (
+                "campaign --endless",
+                "a campaign can't be used with --width, --height, --mines, --density, \
+                 --opening, --safe-start, --target, hex, --adjacency, --time-attack, \
+                 --countdown or --endless.",
+            ),
+            (
+                "gauntlet --leaderboard",
+                "a gauntlet can't be used with --width, --height, --mines, --density, \
+                 --target, --pace, --leaderboard, --time-attack, --countdown or --endless.",
+            ),
+            (
+                "expert --mines 5",
+                "--width, --height and --mines can only be used with custom games.",
+            ),
+            (
+                "custom 10x10_5 --mines 5",
+                "give either 'WxH_M' or --width/--height/--mines, not both.",
+            ),
+            (
+                "custom -w 10 -H 10",
+                "custom games need a width, height and amount of mines.",
+            ),
+            (
+                "--density 0.2",
+                "--density needs a difficulty, or a --width and --height.",
+            ),
+            (
+                "custom -w 10 -H 10 -m 5 --density 0.2",
+                "give either an amount of mines or --density, not both.",
+            ),
+            (
+                "daily expert --seed 3",
+                "a daily challenge can't be a gauntlet, a campaign, a versus \
+                 game, a loaded board, or use --mask, --seed, --opening or \
+                 --safe-start.",
+            ),
+            (
+                "versus gauntlet",
+                "a versus game can't be a gauntlet, a campaign or a daily challenge, \
+                 or be broadcast or use --time-attack or --endless.",
+            ),
+            (
+                "expert --casual --accessible",
+                "--accessible plays a single game, without lives, undo or a broadcast.",
+            ),
+        ];
+
+        for (line, message) in clashes {
+            assert_eq!(error(line), message, "{}", line);
+        }
+    }
+}
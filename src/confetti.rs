@@ -0,0 +1,104 @@
+use rand::Rng;
+
+/// The characters that pieces of confetti are drawn with.
+const PIECES: [char; 4] = ['*', '+', 'o', '~'];
+
+/// How many pieces fall for every column of the area.
+const PIECES_PER_COLUMN: usize = 1;
+
+/// A single piece of confetti, falling down its own column.
+#[derive(Debug, Copy, Clone)]
+struct Piece {
+    column: u16,
+    row: f64,
+    speed: f64,
+    symbol: char,
+    color: usize,
+}
+
+/// Confetti falling down a rectangle of the screen, one frame at a time.
+///
+/// Pieces start above the top of the area at different heights, so
+/// they fall in over a few frames rather than all at once.
+pub struct Confetti {
+    height: u16,
+    pieces: Vec<Piece>,
+}
+
+/// A piece of confetti to draw, at a position within the area.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub struct Speck {
+    pub column: u16,
+    pub row: u16,
+    pub symbol: char,
+
+    /// Which colour to draw the piece in, out of however many there are.
+    pub color: usize,
+}
+
+impl Confetti {
+    /// Scatter confetti above an area of the given size, in up to `colors` colours.
+    pub fn new(width: u16, height: u16, colors: usize) -> Self {
+        let mut rng = rand::thread_rng();
+        let amount = width as usize * PIECES_PER_COLUMN;
+
+        let pieces = (0..amount)
+            .map(|_| Piece {
+                column: rng.gen_range(0, width.max(1)),
+                row: -rng.gen_range(0.0, height.max(1) as f64),
+                speed: rng.gen_range(0.5, 1.5),
+                symbol: PIECES[rng.gen_range(0, PIECES.len())],
+                color: rng.gen_range(0, colors.max(1)),
+            })
+            .collect();
+
+        Self { height, pieces }
+    }
+
+    /// Move every piece down by a frame, returning whether
+    /// any of them are still falling through the area.
+    pub fn step(&mut self) -> bool {
+        for piece in self.pieces.iter_mut() {
+            piece.row += piece.speed;
+        }
+
+        let height = self.height as f64;
+        self.pieces.iter().any(|piece| piece.row < height)
+    }
+
+    /// The pieces that are inside the area right now.
+    pub fn specks(&self) -> Vec<Speck> {
+        self.pieces
+            .iter()
+            .filter(|piece| piece.row >= 0.0 && piece.row < self.height as f64)
+            .map(|piece| Speck {
+                column: piece.column,
+                row: piece.row as u16,
+                symbol: piece.symbol,
+                color: piece.color,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn confetti_falls_through_and_stops() {
+        let mut confetti = Confetti::new(8, 5, 3);
+        let mut frames = 0;
+
+        while confetti.step() {
+            frames += 1;
+            assert!(frames < 100, "confetti never finished falling");
+
+            for speck in confetti.specks() {
+                assert!(speck.column < 8 && speck.row < 5 && speck.color < 3);
+            }
+        }
+
+        assert!(confetti.specks().is_empty());
+    }
+}
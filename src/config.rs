@@ -0,0 +1,87 @@
+use std::collections::BTreeMap;
+
+use crate::storage;
+
+const CONFIG_FILE: &str = "config";
+
+/// Settings from the config file, written as `key = value` lines.
+///
+/// Blank lines and lines starting with `#` are ignored.
+#[derive(Debug, Default)]
+pub struct Config {
+    values: BTreeMap<String, String>,
+}
+
+impl Config {
+    /// Read the config file, or use no settings at all if it can't be read.
+    pub fn load() -> Self {
+        Self::parse(&storage::read_config_file(CONFIG_FILE).unwrap_or_default())
+    }
+
+    /// Read settings from the text of a config file.
+    ///
+    /// Lines that aren't settings are skipped, and a setting
+    /// given twice keeps its last value.
+    pub fn parse(text: &str) -> Self {
+        let mut values = BTreeMap::new();
+
+        for line in text.lines().map(str::trim) {
+            if line.starts_with('#') {
+                continue;
+            }
+
+            if let Some((key, value)) = line.split_once('=') {
+                values.insert(String::from(key.trim()), String::from(value.trim()));
+            }
+        }
+
+        Self { values }
+    }
+
+    /// Every setting whose key starts with a prefix, with the prefix taken off.
+    pub fn with_prefix<'a>(&'a self, prefix: &'a str) -> impl Iterator<Item = (&'a str, &'a str)> {
+        self.values
+            .iter()
+            .filter(|(_, value)| !value.is_empty())
+            .filter_map(move |(key, value)| Some((key.strip_prefix(prefix)?, value.as_str())))
+    }
+
+    /// Check if a setting is switched on, with a value like `true` or `yes`.
+    pub fn is_enabled(&self, key: &str) -> bool {
+        matches!(self.get(key), Some("true" | "yes" | "on" | "1"))
+    }
+
+    /// Look up a setting.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.values
+            .get(key)
+            .map(String::as_str)
+            .filter(|value| !value.is_empty())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_settings() {
+        let config = Config::parse(
+            "# run when a game is won\n\
+             hook.win = notify-send 'won in' $MINESWEEPER_SECONDS\n\
+             \n\
+             not a setting\n\
+             hook.loss =\n\
+             hook.start=a\n\
+             hook.start = b = c\n",
+        );
+
+        assert_eq!(
+            config.get("hook.win"),
+            Some("notify-send 'won in' $MINESWEEPER_SECONDS")
+        );
+        assert_eq!(config.get("hook.loss"), None);
+        assert_eq!(config.get("hook.start"), Some("b = c"));
+        assert_eq!(config.get("not a setting"), None);
+    }
+}
@@ -0,0 +1,196 @@
+use std::io::{self, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::mpsc::{Receiver, RecvTimeoutError, TryRecvError};
+use std::time::Duration;
+
+use crate::game;
+use crate::minefield::MineField;
+use crate::motion::MotionKeys;
+use crate::position::Position;
+use crate::protocol::{Cover, Feed, GameState, Move, Tile, Update};
+use crate::spectate;
+use crate::terminal::{self, clear, color, cursor, style, Guard, Key};
+
+/// How often the guest checks for keys between updates.
+const TICK_RATE: Duration = Duration::from_millis(50);
+
+/// How the other player's cursor is shown, to tell it apart from your own.
+pub fn partner_highlight() -> String {
+    format!("{}{}", color::Bg(color::Magenta), color::Fg(color::White))
+}
+
+/// Send a move to the host, as a line of JSON.
+fn send_move(stream: &mut TcpStream, tile_move: Move) -> io::Result<()> {
+    let line = serde_json::to_string(&tile_move).unwrap();
+    writeln!(stream, "{}", line)
+}
+
+/// The guest of a co-op game, as seen by the host.
+///
+/// The host's field is the only one. The guest is sent every change to
+/// it, and sends back their moves, which the host makes in the order
+/// they arrive.
+pub struct Partner {
+    stream: TcpStream,
+    moves: Receiver<Move>,
+    feed: Feed,
+    cursor: Option<Position>,
+    is_connected: bool,
+}
+
+impl Partner {
+    /// Wait for a guest to join a co-op game on a port.
+    pub fn host(port: u16) -> io::Result<Self> {
+        let listener = TcpListener::bind(("0.0.0.0", port))?;
+        let (stream, _) = listener.accept()?;
+
+        Ok(Self {
+            moves: spectate::spawn_reader(stream.try_clone()?),
+            stream,
+            feed: Feed::default(),
+            cursor: None,
+            is_connected: true,
+        })
+    }
+
+    /// Send the guest any changes to the field and the host's cursor.
+    pub fn update(&mut self, field: &MineField, cursor: Position) {
+        let updates = self.feed.updates(field, cursor);
+
+        // a guest who has gone shows up when their moves stop.
+        spectate::send(&mut self.stream, &updates).ok();
+    }
+
+    /// Take the moves the guest has made since the last check, keeping
+    /// track of their cursor. Returns `None` if nothing has changed.
+    pub fn take_moves(&mut self) -> Option<Vec<Move>> {
+        let mut moves = Vec::new();
+        let mut is_changed = false;
+
+        loop {
+            match self.moves.try_recv() {
+                Ok(Move::Cursor { row, column }) => self.cursor = Some(Position::new(row, column)),
+                Ok(tile_move) => moves.push(tile_move),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    is_changed |= self.is_connected;
+                    self.is_connected = false;
+                    break;
+                }
+            }
+
+            is_changed = true;
+        }
+
+        match is_changed {
+            true => Some(moves),
+            false => None,
+        }
+    }
+
+    /// Where the guest's cursor is, if they're still around.
+    pub fn cursor(&self) -> Option<Position> {
+        self.cursor.filter(|_| self.is_connected)
+    }
+
+    /// Describe how the guest is doing.
+    pub fn describe(&self) -> &'static str {
+        match self.is_connected {
+            true => "co-op: partner connected",
+            false => "co-op: partner gone",
+        }
+    }
+}
+
+/// Join a co-op game hosted at an address, like `localhost:3999`, and
+/// play on the host's field until it ends or 'q' is pressed.
+///
+/// Nothing is worked out here: moves are sent to the host, and the field
+/// is drawn as the host says it is.
+pub fn join(address: &str) -> io::Result<()> {
+    let mut stream = TcpStream::connect(address)?;
+    let updates: Receiver<Update> = spectate::spawn_reader(stream.try_clone()?);
+
+    let keys = game::spawn_key_reader();
+    let mut raw_stdout = Guard::new()?;
+    let mut state: Option<GameState> = None;
+    let mut host_cursor = None;
+    let mut cursor = Position::new(0, 0);
+    let mut motion_keys = MotionKeys::new();
+    let footer = "co-op: space to dig, 'f' to flag, 'd' to chord, 'q' to leave";
+
+    write!(raw_stdout, "{}{}", clear::All, cursor::Hide)?;
+    write!(
+        raw_stdout,
+        "{}waiting for the game to start...",
+        cursor::Goto(1, 1)
+    )?;
+    raw_stdout.flush()?;
+
+    loop {
+        let mut is_changed = match updates.recv_timeout(TICK_RATE) {
+            Ok(update) => {
+                spectate::follow(update, &mut state, &mut host_cursor, &mut *raw_stdout)?;
+                true
+            }
+            Err(RecvTimeoutError::Timeout) => false,
+            Err(RecvTimeoutError::Disconnected) => break,
+        };
+
+        // keys are only any use once there's a field to use them on.
+        let (height, width, tiles) = match &state {
+            Some(state) => (state.height, state.width, &state.tiles),
+            None => continue,
+        };
+
+        while let Ok(key) = keys.try_recv() {
+            let Position { row, column } = cursor;
+
+            // the cursor moves just like in a game of your own.
+            if let Some(motion) = motion_keys.feed(key) {
+                let is_hidden = |tile: Position| {
+                    tiles[tile.row][tile.column] == Some(Tile::Covered(Cover::Hidden))
+                };
+
+                cursor = motion.apply(cursor, height, width, is_hidden);
+                is_changed = true;
+
+                let Position { row, column } = cursor;
+                send_move(&mut stream, Move::Cursor { row, column }).ok();
+                continue;
+            } else if motion_keys.is_pending() {
+                continue;
+            }
+
+            let tile_move = match key {
+                Key::Char(' ') => Move::Dig { row, column },
+                Key::Char('f') => Move::Flag { row, column },
+                Key::Char('d') => Move::Chord { row, column },
+                Key::Char('q') | terminal::INTERRUPT => return Ok(()),
+                _ => continue,
+            };
+
+            // a lost connection shows up when the updates stop.
+            send_move(&mut stream, tile_move).ok();
+        }
+
+        if let (Some(state), true) = (&state, is_changed) {
+            let mut cursors = vec![(cursor, style::Invert.to_string())];
+            cursors.extend(host_cursor.map(|host| (host, partner_highlight())));
+
+            spectate::draw(&mut *raw_stdout, state, &cursors, footer)?;
+        }
+    }
+
+    // the host has finished, so the field stays as it was left.
+    let footer = "the game has ended, press 'q' to leave";
+
+    match &state {
+        Some(state) => spectate::draw(&mut *raw_stdout, state, &[], footer)?,
+        None => write!(raw_stdout, "{}{}", cursor::Goto(1, 1), footer)?,
+    }
+
+    raw_stdout.flush()?;
+    game::wait_for_key(&keys, Key::Char('q'));
+    Ok(())
+}
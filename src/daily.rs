@@ -0,0 +1,71 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::difficulty::Difficulty;
+
+const SECONDS_PER_DAY: u64 = 60 * 60 * 24;
+
+/// Today, as the amount of days since 1970-01-01 in UTC, so that
+/// everyone's daily challenge changes over at the same moment.
+pub fn today() -> u64 {
+    let since_epoch = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+
+    since_epoch.as_secs() / SECONDS_PER_DAY
+}
+
+/// Write out a day as a date like `2026-10-16`.
+pub fn date(day: u64) -> String {
+    // this is Howard Hinnant's `civil_from_days`, which counts in eras
+    // of 400 years starting from 0000-03-01 so leap days come last.
+    let days = day + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days % 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+
+    let day_of_month = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!("{:04}-{:02}-{:02}", year, month, day_of_month)
+}
+
+/// The seed for a day's challenge on a difficulty.
+///
+/// This is worked out with FNV-1a rather than the standard library's
+/// hasher, which is free to change between versions of Rust and would
+/// hand out different boards to players on different builds.
+pub fn seed(day: u64, difficulty: Difficulty) -> u64 {
+    let (width, height, mines) = difficulty.dimensions();
+    let text = format!("{} {}x{}_{}", date(day), width, height, mines);
+
+    text.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn days_have_dates_and_seeds() {
+        assert_eq!(date(0), "1970-01-01");
+        assert_eq!(date(11016), "2000-02-29");
+        assert_eq!(date(20742), "2026-10-16");
+
+        // the same day and difficulty always get the same board.
+        let beginner = seed(20742, Difficulty::Beginner);
+        assert_eq!(beginner, seed(20742, Difficulty::Beginner));
+        assert_eq!(beginner, 0x38cb_b7ed_0eb9_4ff5);
+        assert_ne!(beginner, seed(20743, Difficulty::Beginner));
+        assert_ne!(beginner, seed(20742, Difficulty::Expert));
+    }
+}
@@ -0,0 +1,86 @@
+/// The size and mine count of the field to play on.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum Difficulty {
+    Beginner,
+    Intermediate,
+    Expert,
+    Custom {
+        width: usize,
+        height: usize,
+        mines: usize,
+    },
+}
+
+/// The classic difficulties, from easiest to hardest.
+pub const CLASSIC: [Difficulty; 3] = [
+    Difficulty::Beginner,
+    Difficulty::Intermediate,
+    Difficulty::Expert,
+];
+
+impl Difficulty {
+    /// Find a classic difficulty by its name.
+    pub fn from_name(name: &str) -> Option<Self> {
+        CLASSIC
+            .iter()
+            .copied()
+            .find(|difficulty| difficulty.name() == Some(name))
+    }
+
+    /// Find the classic difficulty with a field of some size, if there
+    /// is one.
+    pub fn from_dimensions(width: usize, height: usize, mines: usize) -> Option<Self> {
+        CLASSIC
+            .iter()
+            .copied()
+            .find(|difficulty| difficulty.dimensions() == (width, height, mines))
+    }
+
+    /// The name of a classic difficulty, or `None` for a custom one.
+    pub fn name(self) -> Option<&'static str> {
+        match self {
+            Difficulty::Beginner => Some("beginner"),
+            Difficulty::Intermediate => Some("intermediate"),
+            Difficulty::Expert => Some("expert"),
+            Difficulty::Custom { .. } => None,
+        }
+    }
+
+    /// The width, height and amount of mines of the field.
+    pub fn dimensions(self) -> (usize, usize, usize) {
+        match self {
+            Difficulty::Beginner => (10, 10, 10),
+            Difficulty::Intermediate => (15, 15, 40),
+            Difficulty::Expert => (30, 16, 99),
+            Difficulty::Custom {
+                width,
+                height,
+                mines,
+            } => (width, height, mines),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classic_difficulties_are_found_by_name_and_size() {
+        for difficulty in CLASSIC {
+            let (width, height, mines) = difficulty.dimensions();
+
+            assert_eq!(
+                Difficulty::from_name(difficulty.name().unwrap()),
+                Some(difficulty)
+            );
+            assert_eq!(
+                Difficulty::from_dimensions(width, height, mines),
+                Some(difficulty)
+            );
+        }
+
+        assert_eq!(Difficulty::from_name("custom"), None);
+        assert_eq!(Difficulty::from_dimensions(15, 15, 41), None);
+    }
+}
@@ -0,0 +1,486 @@
+use std::time::Duration;
+
+use rand::Rng;
+
+use crate::history::History;
+use crate::minefield::{Edge, MineField, MineFieldError, MineFieldState, Opening};
+use crate::motion::Motion;
+use crate::position::Position;
+use crate::tile::{Note, TileState};
+use crate::timer::Timer;
+
+/// Something a player can do to the game.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum Action {
+    Move(Motion),
+    Dig,
+    Flag,
+    Chord,
+
+    /// Move the note on a hidden tile on to the next one.
+    Note,
+
+    /// Take back the last move, in a game that keeps a history.
+    Undo,
+
+    /// Make the last move that was taken back again.
+    Redo,
+
+    /// Flag every hidden tile that the numbers give away as a mine.
+    AutoFlag,
+
+    /// Flag the last mines, once they're all that's left hidden.
+    Autocomplete,
+
+    /// Pause the game, or carry on if it's already paused.
+    Pause,
+}
+
+/// Something that happened because of an action.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum Event {
+    CursorMoved(Position),
+
+    /// Tiles were opened, in waves spreading out from where they were
+    /// dug: the dug tile first, then those a step away, and so on.
+    TilesRevealed(Vec<Vec<Position>>),
+    FlagToggled {
+        position: Position,
+        is_flagged: bool,
+    },
+
+    /// The note on a hidden tile changed, or was taken off with `None`.
+    NoteChanged {
+        position: Position,
+        note: Option<Note>,
+    },
+
+    /// A flag couldn't be placed, since there are as many as mines.
+    NoFlagsLeft,
+
+    /// A flag couldn't be placed, since it's a flagless game.
+    FlagsDisabled,
+
+    /// Flags were placed for the player, on mines that were given away
+    /// or on the last ones left.
+    MinesFlagged(Vec<Position>),
+
+    /// A mine was dug, but there was a life to spare for it, so it was
+    /// flagged and play goes on.
+    LifeLost {
+        lives_left: usize,
+    },
+    MoveUndone,
+    MoveRedone,
+
+    /// The action didn't change anything, like digging an open tile.
+    Ignored,
+    Paused,
+    Resumed,
+    GameWon,
+    GameLost,
+}
+
+/// The rules of a game and its state, with nothing about how it's
+/// shown or how the player's input comes in.
+///
+/// Each action is applied to the tile under the cursor, and answered
+/// with the events that it caused. The terminal interface is one way
+/// of driving it, and anything else that can turn input into actions
+/// and draw a `MineField` can be another.
+///
+/// There's no way to change the field in the middle of a round other
+/// than an action, so that whatever's driving the engine hears about
+/// every move in its events. Only setting up a round, growing an
+/// endless field and showing the mines at the end are done without one.
+pub struct GameEngine {
+    field: MineField,
+    cursor: Position,
+    timer: Timer,
+    is_paused: bool,
+    history: Option<History>,
+    auto_flag: bool,
+    lives_left: usize,
+}
+
+impl GameEngine {
+    /// Start a game on a field, with the cursor in the top left.
+    pub fn new(field: MineField) -> Self {
+        Self {
+            field,
+            cursor: Position::new(0, 0),
+            timer: Timer::start(),
+            is_paused: false,
+            history: None,
+            auto_flag: false,
+            lives_left: 0,
+        }
+    }
+
+    /// The field being played.
+    #[inline]
+    pub fn field(&self) -> &MineField {
+        &self.field
+    }
+
+    /// Swap in a new field to play, keeping the cursor on it. The moves
+    /// made on the old one can't be undone anymore.
+    pub fn set_field(&mut self, field: MineField) {
+        self.cursor = Position::new(
+            self.cursor.row.min(field.height().saturating_sub(1)),
+            self.cursor.column.min(field.width().saturating_sub(1)),
+        );
+        self.field = field;
+        self.clear_history();
+    }
+
+    /// Open the first empty area of a new round, as the opening asks,
+    /// returning a tile within it.
+    pub fn clear_first_opening_with(
+        &mut self,
+        opening: Opening,
+        rng: &mut impl Rng,
+    ) -> Option<Position> {
+        self.field.clear_first_opening_with(opening, rng)
+    }
+
+    /// Cover every tile back up, to play the same field again.
+    pub fn cover_all(&mut self) {
+        self.field.cover_all();
+        self.clear_history();
+    }
+
+    /// Grow an endless field at one edge.
+    pub fn grow_with(&mut self, edge: Edge, amount: usize, rng: &mut impl Rng) {
+        self.field.grow_with(edge, amount, rng);
+        self.clear_history();
+    }
+
+    /// Show every mine, once the round is over.
+    pub fn game_over(&mut self) {
+        self.field.game_over();
+    }
+
+    /// Keep a history of the moves, so they can be undone and redone.
+    pub fn enable_undo(&mut self) {
+        self.history = Some(History::new());
+    }
+
+    /// Whether moves can be undone.
+    #[inline]
+    pub fn can_undo(&self) -> bool {
+        self.history.is_some()
+    }
+
+    /// Forget the moves made so far, like at the start of a round.
+    pub fn clear_history(&mut self) {
+        if let Some(history) = &mut self.history {
+            history.clear();
+        }
+    }
+
+    /// Flag the mines that the numbers give away after every dig, as
+    /// part of the same move.
+    pub fn enable_auto_flag(&mut self) {
+        self.auto_flag = true;
+    }
+
+    /// Give the player some lives, each of which lets a dug mine be
+    /// flagged instead of losing the game.
+    pub fn set_lives(&mut self, lives: usize) {
+        self.lives_left = lives;
+    }
+
+    /// How many lives the player has left.
+    #[inline]
+    pub fn lives_left(&self) -> usize {
+        self.lives_left
+    }
+
+    /// Where the cursor is.
+    #[inline]
+    pub fn cursor(&self) -> Position {
+        self.cursor
+    }
+
+    /// Put the cursor somewhere else, like after the field has grown.
+    pub fn set_cursor(&mut self, cursor: Position) {
+        self.cursor = cursor;
+    }
+
+    /// Start timing from zero again.
+    pub fn restart_timer(&mut self) {
+        self.restart_timer_from(Duration::new(0, 0));
+    }
+
+    /// Start timing again, from some time already played.
+    pub fn restart_timer_from(&mut self, elapsed: Duration) {
+        self.timer = Timer::start_from(elapsed);
+        self.is_paused = false;
+    }
+
+    /// The time spent playing so far, leaving out any pauses.
+    pub fn elapsed(&self) -> Duration {
+        self.timer.elapsed()
+    }
+
+    /// Apply an action, returning what happened because of it.
+    ///
+    /// While the game is paused, only pausing again does anything, and
+    /// once it's won or lost, only the cursor can move, or a fatal dig
+    /// be undone.
+    pub fn apply(&mut self, action: Action) -> Vec<Event> {
+        let is_over = self.field.get_state() != MineFieldState::InProgress;
+        let cursor = self.cursor;
+
+        match action {
+            Action::Pause if self.is_paused => {
+                self.timer.resume();
+                self.is_paused = false;
+                vec![Event::Resumed]
+            }
+            Action::Pause => {
+                self.timer.pause();
+                self.is_paused = true;
+                vec![Event::Paused]
+            }
+            _ if self.is_paused => vec![Event::Ignored],
+            Action::Move(motion) => {
+                let field = &self.field;
+                let is_hidden = |position: Position| {
+                    let tile = field.get_tile(position).unwrap();
+                    tile.state == TileState::Hidden && !tile.is_void
+                };
+
+                self.cursor = motion.apply(self.cursor, field.height(), field.width(), is_hidden);
+                vec![Event::CursorMoved(self.cursor)]
+            }
+            Action::Undo => {
+                let (field, lives_left) = (&mut self.field, &mut self.lives_left);
+
+                match self
+                    .history
+                    .as_mut()
+                    .is_some_and(|history| history.undo(field, lives_left))
+                {
+                    true => vec![Event::MoveUndone],
+                    false => vec![Event::Ignored],
+                }
+            }
+            Action::Redo => {
+                let (field, lives_left) = (&mut self.field, &mut self.lives_left);
+
+                match self
+                    .history
+                    .as_mut()
+                    .is_some_and(|history| history.redo(field, lives_left))
+                {
+                    true => {
+                        let mut events = vec![Event::MoveRedone];
+                        self.push_outcome(&mut events);
+                        events
+                    }
+                    false => vec![Event::Ignored],
+                }
+            }
+
+            // the last mines are flagged once the field is cleared, so
+            // this goes on after the game is over.
+            Action::Autocomplete => match self.field.try_autocomplete() {
+                flagged if flagged.is_empty() => vec![Event::Ignored],
+                flagged => vec![Event::MinesFlagged(flagged)],
+            },
+            _ if is_over => vec![Event::Ignored],
+            Action::AutoFlag => match self.field.flag_obvious_mines() {
+                flagged if flagged.is_empty() => vec![Event::Ignored],
+                flagged => vec![Event::MinesFlagged(flagged)],
+            },
+            Action::Note => match self.field.get_tile_state(cursor).unwrap() {
+                TileState::Hidden => vec![Event::NoteChanged {
+                    position: cursor,
+                    note: self.field.cycle_note(cursor).unwrap(),
+                }],
+                _ => vec![Event::Ignored],
+            },
+            Action::Dig | Action::Flag | Action::Chord => self.make_move(action),
+        }
+    }
+
+    /// Dig, flag or chord on the tile under the cursor, remembering the
+    /// move if there's a history kept.
+    fn make_move(&mut self, action: Action) -> Vec<Event> {
+        let cursor = self.cursor;
+        let before = self.history.as_ref().map(|_| self.field.clone());
+        let lives_before = self.lives_left;
+
+        let mut events = match action {
+            Action::Dig => match self.field.flood_in_waves(cursor).unwrap() {
+                waves if waves.is_empty() => vec![Event::Ignored],
+                waves => vec![Event::TilesRevealed(waves)],
+            },
+            Action::Chord => match self.field.do_chord(cursor).unwrap() {
+                opened if opened.is_empty() => vec![Event::Ignored],
+                opened => vec![Event::TilesRevealed(vec![opened])],
+            },
+            _ => match self.field.toggle_flag(cursor) {
+                Err(MineFieldError::NoFlagsLeft) => vec![Event::NoFlagsLeft],
+                Err(MineFieldError::FlagsDisabled) => vec![Event::FlagsDisabled],
+                Err(_) => vec![Event::Ignored],
+                Ok(()) => match self.field.get_tile_state(cursor).unwrap() {
+                    TileState::Visible => vec![Event::Ignored],
+                    state => vec![Event::FlagToggled {
+                        position: cursor,
+                        is_flagged: state == TileState::Flagged,
+                    }],
+                },
+            },
+        };
+
+        // flags placed for the player are part of the move, so undoing
+        // a dig takes them back too.
+        let is_dig = action != Action::Flag;
+        let in_progress = self.field.get_state() == MineFieldState::InProgress;
+
+        if self.auto_flag && is_dig && in_progress {
+            let flagged = self.field.flag_obvious_mines();
+
+            if !flagged.is_empty() {
+                events.push(Event::MinesFlagged(flagged));
+            }
+        }
+
+        // the move is remembered once lives have been spent on it, so
+        // that the mines flagged for them are undone along with it.
+        self.push_outcome(&mut events);
+
+        if let (Some(history), Some(before)) = (&mut self.history, before) {
+            history.record(&before, &self.field, (lives_before, self.lives_left));
+        }
+
+        events
+    }
+
+    /// Follow up the events of a move with how the game stands after
+    /// it, spending lives on any mines that were dug if there are
+    /// enough of them.
+    fn push_outcome(&mut self, events: &mut Vec<Event>) {
+        let dug = self.field.dug_mines();
+
+        if self.field.get_state() == MineFieldState::Failed && dug < self.lives_left {
+            self.lives_left -= self.field.flag_dug_mines().len();
+            events.push(Event::LifeLost {
+                lives_left: self.lives_left,
+            });
+        } else if self.field.get_state() == MineFieldState::Failed && self.history.is_none() {
+            // a fatal dig in a game with undo can be taken back, so the
+            // lives are only gone for good without one.
+            self.lives_left = 0;
+        }
+
+        match self.field.get_state() {
+            MineFieldState::InProgress => (),
+            MineFieldState::Cleared => events.push(Event::GameWon),
+            MineFieldState::Failed => events.push(Event::GameLost),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn actions_are_answered_with_events() {
+        let field = MineField::from_layout("*..").unwrap();
+        let mut engine = GameEngine::new(field);
+
+        assert_eq!(
+            engine.apply(Action::Flag),
+            [Event::FlagToggled {
+                position: Position::new(0, 0),
+                is_flagged: true
+            }]
+        );
+        assert_eq!(
+            engine.apply(Action::Move(Motion::Right(2))),
+            [Event::CursorMoved(Position::new(0, 2))]
+        );
+
+        assert_eq!(
+            engine.apply(Action::Note),
+            [Event::NoteChanged {
+                position: Position::new(0, 2),
+                note: Some(Note::Mine)
+            }]
+        );
+
+        assert_eq!(engine.apply(Action::Pause), [Event::Paused]);
+        assert_eq!(engine.apply(Action::Dig), [Event::Ignored]);
+        assert_eq!(engine.apply(Action::Pause), [Event::Resumed]);
+
+        let events = engine.apply(Action::Dig);
+        assert!(matches!(events[0], Event::TilesRevealed(ref waves) if waves.len() == 2));
+        assert_eq!(events[1], Event::GameWon);
+        assert_eq!(engine.apply(Action::Dig), [Event::Ignored]);
+    }
+
+    #[test]
+    fn undos_lives_and_flags_for_the_player_come_with_events() {
+        let field = MineField::from_layout("*1.\n11.\n...\n111\n.*.").unwrap();
+        let mut engine = GameEngine::new(field);
+        engine.enable_undo();
+        engine.enable_auto_flag();
+        engine.set_lives(2);
+
+        engine.set_cursor(Position::new(0, 0));
+        let events = engine.apply(Action::Dig);
+        assert_eq!(
+            events[0],
+            Event::TilesRevealed(vec![vec![Position::new(0, 0)]])
+        );
+        assert_eq!(events[1], Event::LifeLost { lives_left: 1 });
+        assert_eq!(engine.field().get_state(), MineFieldState::InProgress);
+
+        assert_eq!(engine.apply(Action::Undo), [Event::MoveUndone]);
+        assert_eq!(engine.field().flags(), 0);
+        assert_eq!(engine.apply(Action::Redo)[0], Event::MoveRedone);
+
+        // the dig opens up enough numbers to give the mine away.
+        engine.apply(Action::Undo);
+        engine.set_cursor(Position::new(2, 2));
+        let events = engine.apply(Action::Dig);
+        assert_eq!(
+            events[1..],
+            [Event::MinesFlagged(vec![Position::new(0, 0)])]
+        );
+
+        assert_eq!(engine.apply(Action::Undo), [Event::MoveUndone]);
+        assert_eq!(engine.field().flags(), 0);
+    }
+
+    #[test]
+    fn redoing_a_dig_that_cost_a_life_spends_it_again() {
+        let field = MineField::from_layout("*..\n...\n..*").unwrap();
+        let mut engine = GameEngine::new(field);
+        engine.enable_undo();
+        engine.set_lives(2);
+
+        let events = engine.apply(Action::Dig);
+        assert_eq!(events[1], Event::LifeLost { lives_left: 1 });
+
+        assert_eq!(engine.apply(Action::Undo), [Event::MoveUndone]);
+        assert_eq!(engine.lives_left(), 2);
+        assert_eq!(
+            engine.field().get_tile_state(Position::new(0, 0)).unwrap(),
+            TileState::Hidden
+        );
+
+        assert_eq!(engine.apply(Action::Redo), [Event::MoveRedone]);
+        assert_eq!(engine.lives_left(), 1);
+        assert_eq!(engine.field().get_state(), MineFieldState::InProgress);
+        assert_eq!(
+            engine.field().get_tile_state(Position::new(0, 0)).unwrap(),
+            TileState::Flagged
+        );
+    }
+}
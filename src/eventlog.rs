@@ -0,0 +1,136 @@
+use std::collections::VecDeque;
+
+use crate::engine::{Action, Event};
+use crate::position::Position;
+
+/// How many lines are kept, however few of them fit on the screen.
+const CAPACITY: usize = 100;
+
+/// The things that have happened in a game, as short lines of text
+/// for the log beside the field, like `flag placed at C7`.
+///
+/// New lines go on the end, and the oldest are dropped once it's full.
+#[derive(Debug, Default)]
+pub struct EventLog {
+    lines: VecDeque<String>,
+}
+
+impl EventLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a line to the end of the log.
+    pub fn push(&mut self, line: String) {
+        if self.lines.len() == CAPACITY {
+            self.lines.pop_front();
+        }
+
+        self.lines.push_back(line);
+    }
+
+    /// Add a line for an event caused by an action at the cursor, if
+    /// it's worth one. Moving the cursor and ignored keys aren't.
+    pub fn record(&mut self, action: Action, cursor: Position, event: &Event) {
+        if let Some(line) = describe(action, cursor, event) {
+            self.push(line);
+        }
+    }
+
+    /// The newest lines that fit in some amount of space, oldest first,
+    /// so that older lines scroll up and off as new ones come in.
+    pub fn recent(&self, space: usize) -> impl Iterator<Item = &str> {
+        self.lines
+            .iter()
+            .skip(self.lines.len().saturating_sub(space))
+            .map(String::as_str)
+    }
+
+    /// Forget everything, like when a new board is started.
+    pub fn clear(&mut self) {
+        self.lines.clear();
+    }
+}
+
+/// Describe an event in a few words, with tiles named in A1 notation.
+fn describe(action: Action, cursor: Position, event: &Event) -> Option<String> {
+    Some(match event {
+        Event::TilesRevealed(waves) => {
+            let tiles: usize = waves.iter().map(Vec::len).sum();
+
+            match (action, tiles) {
+                (Action::Chord, 1) => format!("chord at {}: 1 tile", cursor.to_a1()),
+                (Action::Chord, _) => format!("chord at {}: {} tiles", cursor.to_a1(), tiles),
+                (_, 1) => format!("revealed {}", cursor.to_a1()),
+                _ => format!("revealed {} tiles at {}", tiles, cursor.to_a1()),
+            }
+        }
+        Event::FlagToggled {
+            position,
+            is_flagged: true,
+        } => format!("flag placed at {}", position.to_a1()),
+        Event::FlagToggled { position, .. } => format!("flag taken off {}", position.to_a1()),
+        Event::NoteChanged {
+            position,
+            note: Some(note),
+        } => format!("{} noted as {}", position.to_a1(), note.describe()),
+        Event::NoteChanged { position, .. } => format!("note taken off {}", position.to_a1()),
+        Event::MinesFlagged(flagged) if flagged.len() == 1 => {
+            format!("flagged the mine at {}", flagged[0].to_a1())
+        }
+        Event::MinesFlagged(flagged) => format!("flagged {} mines", flagged.len()),
+        Event::LifeLost { .. } => format!("lost a life at {}", cursor.to_a1()),
+        Event::MoveUndone => String::from("undid a move"),
+        Event::MoveRedone => String::from("redid a move"),
+        Event::GameWon => String::from("cleared the field"),
+        Event::GameLost => format!("hit a mine at {}", cursor.to_a1()),
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tiles_are_named_like_spreadsheet_cells() {
+        assert_eq!(Position::new(0, 0).to_a1(), "A1");
+        assert_eq!(Position::new(6, 2).to_a1(), "C7");
+        assert_eq!(Position::new(0, 25).to_a1(), "Z1");
+        assert_eq!(Position::new(9, 26).to_a1(), "AA10");
+        assert_eq!(Position::new(15, 29).to_a1(), "AD16");
+    }
+
+    #[test]
+    fn the_log_keeps_the_newest_events() {
+        let mut log = EventLog::new();
+        let cursor = Position::new(3, 4);
+
+        log.record(Action::Dig, cursor, &Event::CursorMoved(cursor));
+        log.record(
+            Action::Dig,
+            cursor,
+            &Event::TilesRevealed(vec![vec![cursor], vec![Position::new(3, 5); 13]]),
+        );
+        log.record(
+            Action::Flag,
+            cursor,
+            &Event::FlagToggled {
+                position: Position::new(6, 2),
+                is_flagged: true,
+            },
+        );
+        log.record(Action::Chord, cursor, &Event::Ignored);
+
+        let lines: Vec<&str> = log.recent(5).collect();
+        assert_eq!(lines, ["revealed 14 tiles at E4", "flag placed at C7"]);
+        assert_eq!(log.recent(1).collect::<Vec<_>>(), ["flag placed at C7"]);
+
+        for _ in 0..CAPACITY {
+            log.push(String::from("undid a move"));
+        }
+
+        assert_eq!(log.lines.len(), CAPACITY);
+        assert!(log.recent(CAPACITY).all(|line| line == "undid a move"));
+    }
+}
@@ -0,0 +1,238 @@
+use std::fs;
+use std::path::Path;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use crate::difficulty::Difficulty;
+use crate::formats;
+use crate::minefield::MineField;
+use crate::position::Position;
+
+/// A way of writing out a field's full layout, mines included.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum Format {
+    /// One line per row, with `*` for mines and the count of mines
+    /// around every other tile.
+    Ascii,
+
+    /// A JSON object with the size of the field and its rows, written
+    /// the same way as the ASCII format.
+    Json,
+
+    /// The binary Minesweeper Board Format, as written by `formats`.
+    Mbf,
+
+    /// The layout format that boards are loaded from, with every
+    /// tile still hidden.
+    Layout,
+}
+
+impl Format {
+    /// Parse the name of a format, as given on the command line.
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "ascii" => Some(Format::Ascii),
+            "json" => Some(Format::Json),
+            "mbf" => Some(Format::Mbf),
+            "layout" => Some(Format::Layout),
+            _ => None,
+        }
+    }
+
+    /// The extension of a file written in the format.
+    pub fn extension(self) -> &'static str {
+        match self {
+            Format::Ascii => "txt",
+            Format::Json => "json",
+            Format::Mbf => "mbf",
+            Format::Layout => "layout",
+        }
+    }
+}
+
+/// A board that's been generated and written out.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Board {
+    pub seed: u64,
+    pub three_bv: usize,
+    pub bytes: Vec<u8>,
+}
+
+/// Draw every row of the field with its mines showing.
+fn layout_rows(field: &MineField) -> Vec<String> {
+    (0..field.height())
+        .map(|row| {
+            (0..field.width())
+                .map(|column| {
+                    let position = Position::new(row, column);
+                    let tile = field.get_tile(position).unwrap();
+
+                    if tile.is_void {
+                        ' '
+                    } else if tile.has_mine {
+                        '*'
+                    } else {
+                        let count = field.count_mines_near(position).unwrap();
+                        std::char::from_digit(count as u32, 10).unwrap()
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Write the field in the JSON format, with the seed it came from if
+/// there is one, and its 3BV.
+fn to_json(field: &MineField, seed: Option<u64>) -> String {
+    let rows: Vec<String> = layout_rows(field)
+        .iter()
+        .map(|row| format!("\"{}\"", row))
+        .collect();
+
+    let seed = match seed {
+        Some(seed) => seed.to_string(),
+        None => String::from("null"),
+    };
+
+    format!(
+        "{{\"width\": {}, \"height\": {}, \"mines\": {}, \"seed\": {}, \"3bv\": {}, \"rows\": [{}]}}\n",
+        field.width(),
+        field.height(),
+        field.mines(),
+        seed,
+        field.three_bv(),
+        rows.join(", ")
+    )
+}
+
+/// Write out a field's layout in a format, ready to go to a file or stdout.
+pub fn export(
+    field: &MineField,
+    format: Format,
+    seed: Option<u64>,
+) -> Result<Vec<u8>, &'static str> {
+    match format {
+        Format::Ascii => {
+            let mut text = layout_rows(field).join("\n");
+            text.push('\n');
+            Ok(text.into_bytes())
+        }
+        Format::Json => Ok(to_json(field, seed).into_bytes()),
+        Format::Mbf => formats::to_mbf(field),
+        Format::Layout => Ok(field.to_string_layout().into_bytes()),
+    }
+}
+
+/// Generate a field from a seed and write out its layout.
+pub fn generate(difficulty: Difficulty, seed: u64, format: Format) -> Result<Board, String> {
+    let (width, height, mines) = difficulty.dimensions();
+
+    let mut field = MineField::empty(width, height);
+    field
+        .populate_with(mines, &mut StdRng::seed_from_u64(seed))
+        .map_err(|e| e.to_string())?;
+
+    Ok(Board {
+        seed,
+        three_bv: field.three_bv(),
+        bytes: export(&field, format, Some(seed)).map_err(String::from)?,
+    })
+}
+
+/// Generate some boards on seeds counting up from the first one,
+/// picking it at random if there isn't one.
+pub fn generate_many(
+    difficulty: Difficulty,
+    count: usize,
+    seed: Option<u64>,
+    format: Format,
+) -> Result<Vec<Board>, String> {
+    let first_seed: u64 = seed.unwrap_or_else(rand::random);
+
+    (0..count as u64)
+        .map(|index| generate(difficulty, first_seed.wrapping_add(index), format))
+        .collect()
+}
+
+/// Write boards into a directory, one file each, along with an index
+/// giving the seed and 3BV of every file. Returns the path of the index.
+pub fn write_boards(boards: &[Board], format: Format, dir: &Path) -> Result<String, String> {
+    let describe = |e: std::io::Error| format!("couldn't write to '{}': {}", dir.display(), e);
+    let digits = boards.len().to_string().len();
+    let mut index = vec![String::from("file\tseed\t3bv")];
+
+    fs::create_dir_all(dir).map_err(describe)?;
+
+    for (number, board) in boards.iter().enumerate() {
+        let name = format!(
+            "board-{:0digits$}.{}",
+            number + 1,
+            format.extension(),
+            digits = digits
+        );
+
+        fs::write(dir.join(&name), &board.bytes).map_err(describe)?;
+        index.push(format!("{}\t{}\t{}", name, board.seed, board.three_bv));
+    }
+
+    let index_path = dir.join("index.tsv");
+    fs::write(&index_path, index.join("\n") + "\n").map_err(describe)?;
+
+    Ok(index_path.display().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_format_holds_the_whole_layout() {
+        let mut field = MineField::empty(4, 3);
+        field
+            .populate_with(2, &mut StdRng::seed_from_u64(7))
+            .unwrap();
+
+        let ascii = String::from_utf8(export(&field, Format::Ascii, None).unwrap()).unwrap();
+        assert_eq!(ascii.lines().count(), 3);
+        assert_eq!(ascii.matches('*').count(), 2);
+
+        let json = String::from_utf8(export(&field, Format::Json, Some(7)).unwrap()).unwrap();
+        assert!(json.contains("\"seed\": 7"));
+        assert!(json.contains(&format!("\"{}\"", ascii.lines().next().unwrap())));
+
+        let mbf = export(&field, Format::Mbf, None).unwrap();
+        assert_eq!(mbf[..4], [4, 3, 0, 2]);
+        assert_eq!(mbf.len(), 8);
+
+        for mine in mbf[4..].chunks(2) {
+            let (column, row) = (mine[0] as usize, mine[1] as usize);
+            assert!(field.has_mine_at(Position::new(row, column)).unwrap());
+        }
+
+        assert!(export(&MineField::empty(300, 1), Format::Mbf, None).is_err());
+    }
+
+    #[test]
+    fn batches_are_indexed_by_seed_and_3bv() {
+        let difficulty = Difficulty::Custom {
+            width: 6,
+            height: 5,
+            mines: 4,
+        };
+        let boards = generate_many(difficulty, 3, Some(41), Format::Json).unwrap();
+        let seeds: Vec<u64> = boards.iter().map(|board| board.seed).collect();
+        assert_eq!(seeds, [41, 42, 43]);
+        assert_eq!(boards[1], generate(difficulty, 42, Format::Json).unwrap());
+
+        let dir = std::env::temp_dir().join(format!("minesweeper-batch-{}", std::process::id()));
+        let index = write_boards(&boards, Format::Json, &dir).unwrap();
+        let index = fs::read_to_string(index).unwrap();
+        let row = format!("board-2.json\t42\t{}", boards[1].three_bv);
+        assert_eq!(index.lines().nth(2), Some(row.as_str()));
+
+        let json = fs::read(dir.join("board-2.json")).unwrap();
+        assert_eq!(json, boards[1].bytes);
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,98 @@
+use crate::minefield::MineField;
+use crate::position::Position;
+
+/// How many bytes come before the mines in an MBF file: the width and
+/// height, then the amount of mines.
+const MBF_HEADER: usize = 4;
+
+/// Write a field in the Minesweeper Board Format, used by the
+/// competitive community to share boards: the width and height as a
+/// byte each, the amount of mines as a big-endian `u16`, then the
+/// column and row of each mine as a byte each.
+pub fn to_mbf(field: &MineField) -> Result<Vec<u8>, &'static str> {
+    if field.width() > 255 || field.height() > 255 || field.mines() > u16::MAX as usize {
+        return Err("MBF can only hold fields up to 255x255 with 65535 mines.");
+    }
+
+    if field.iter_tiles().any(|tile| tile.is_void) {
+        return Err("MBF can't hold fields with gaps in them.");
+    }
+
+    let mut bytes = vec![field.width() as u8, field.height() as u8];
+    bytes.extend_from_slice(&(field.mines() as u16).to_be_bytes());
+
+    for (position, tile) in field.iter_positions().zip(field.iter_tiles()) {
+        if tile.has_mine {
+            bytes.push(position.column as u8);
+            bytes.push(position.row as u8);
+        }
+    }
+
+    Ok(bytes)
+}
+
+/// Read a field from the Minesweeper Board Format, with every tile
+/// still hidden.
+pub fn from_mbf(bytes: &[u8]) -> Result<MineField, &'static str> {
+    let (header, mines) = match bytes.len() {
+        length if length < MBF_HEADER => return Err("The MBF file is too short to hold a board."),
+        _ => bytes.split_at(MBF_HEADER),
+    };
+
+    let (width, height) = (header[0] as usize, header[1] as usize);
+    let count = u16::from_be_bytes([header[2], header[3]]) as usize;
+
+    if width == 0 || height == 0 {
+        return Err("The MBF board doesn't have any tiles in it.");
+    }
+
+    if mines.len() != count * 2 {
+        return Err("The MBF file doesn't hold as many mines as it says.");
+    }
+
+    let positions: Vec<Position> = mines
+        .chunks(2)
+        .map(|mine| Position::new(mine[1] as usize, mine[0] as usize))
+        .collect();
+
+    let field = MineField::with_mines_at(width, height, &positions)
+        .map_err(|_| "The MBF file has a mine that's off the board.")?;
+
+    if field.mines() != count {
+        return Err("The MBF file has the same mine in it twice.");
+    }
+
+    Ok(field)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn mbf_survives_a_round_trip() {
+        let field = MineField::new_with(30, 16, 99, &mut StdRng::seed_from_u64(5)).unwrap();
+        let bytes = to_mbf(&field).unwrap();
+        assert_eq!(bytes[..4], [30, 16, 0, 99]);
+
+        let read = from_mbf(&bytes).unwrap();
+        assert_eq!(read.to_string_layout(), field.to_string_layout());
+        assert_eq!(to_mbf(&read).unwrap(), bytes);
+    }
+
+    #[test]
+    fn broken_mbf_is_refused() {
+        assert!(from_mbf(&[3, 3, 0]).is_err());
+        assert!(from_mbf(&[0, 3, 0, 0]).is_err());
+        assert!(from_mbf(&[3, 3, 0, 2, 1, 1]).is_err());
+        assert!(from_mbf(&[3, 3, 0, 1, 3, 0]).is_err());
+        assert!(from_mbf(&[3, 3, 0, 2, 1, 1, 1, 1]).is_err());
+        assert_eq!(
+            from_mbf(&[3, 2, 0, 1, 2, 1]).unwrap().to_string_layout(),
+            "...\n..*\n"
+        );
+    }
+}
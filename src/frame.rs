@@ -0,0 +1,97 @@
+use std::collections::HashMap;
+
+use crate::terminal::cursor;
+
+/// The part of the board on screen: its top row, left column,
+/// and how many rows and columns it covers.
+type Area = (usize, usize, usize, usize);
+
+/// What the field looks like on screen, cell by cell, so that a new
+/// frame only has to write out the cells that have changed.
+///
+/// Anything else drawn over a cell has to be noted with `forget`, so
+/// that the cell is put back on the next frame.
+#[derive(Debug, Default)]
+pub struct Frame {
+    area: Option<Area>,
+    cells: HashMap<(u16, u16), String>,
+}
+
+impl Frame {
+    /// Start with nothing known about the screen.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Forget everything on screen, so the next frame is drawn in full.
+    pub fn clear(&mut self) {
+        self.area = None;
+        self.cells.clear();
+    }
+
+    /// Forget a single cell, after something else has been drawn over it.
+    pub fn forget(&mut self, x_pos: u16, y_pos: u16) {
+        self.cells.remove(&(x_pos, y_pos));
+    }
+
+    /// Check that the same area of the board is still on screen,
+    /// clearing the frame if not, since every cell will have moved.
+    /// Returns whether it was cleared.
+    pub fn move_to(&mut self, area: Area) -> bool {
+        if self.area == Some(area) {
+            return false;
+        }
+
+        self.clear();
+        self.area = Some(area);
+        true
+    }
+
+    /// Write out the cells of a new frame, skipping any that are already
+    /// on screen, and remember them for the next one.
+    pub fn update(&mut self, cells: impl IntoIterator<Item = ((u16, u16), String)>) -> String {
+        let mut output = String::new();
+
+        for (position, text) in cells {
+            if self.cells.get(&position) == Some(&text) {
+                continue;
+            }
+
+            output.push_str(&format!("{}{}", cursor::Goto(position.0, position.1), text));
+            self.cells.insert(position, text);
+        }
+
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_changed_cells_are_written() {
+        let cells = |middle: &str| {
+            vec![
+                ((1, 1), String::from("#")),
+                ((3, 1), String::from(middle)),
+                ((5, 1), String::from("#")),
+            ]
+        };
+
+        let mut frame = Frame::new();
+        assert!(frame.move_to((0, 0, 1, 3)));
+        assert_eq!(frame.update(cells("#")).matches('\x1b').count(), 3);
+
+        assert!(!frame.move_to((0, 0, 1, 3)));
+        assert_eq!(frame.update(cells("1")), format!("{}1", cursor::Goto(3, 1)));
+        assert_eq!(frame.update(cells("1")), "");
+
+        frame.forget(5, 1);
+        assert_eq!(frame.update(cells("1")), format!("{}#", cursor::Goto(5, 1)));
+
+        // scrolling moves every cell, so they're all drawn again.
+        assert!(frame.move_to((0, 1, 1, 3)));
+        assert_eq!(frame.update(cells("1")).matches('\x1b').count(), 3);
+    }
+}
@@ -1,242 +1,2752 @@
-use std::io::{stdin, stdout, Stdout, Write};
-use std::time::{Duration, SystemTime};
-use termion::event::Key;
-use termion::input::TermRead;
-use termion::raw::{IntoRawMode, RawTerminal};
+use std::collections::HashSet;
+use std::io::Write;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
 
-use crate::minefield::{MineField, MineFieldState};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use crate::animation::{self, FloodSpeed};
+use crate::autosave::Autosave;
+use crate::campaign::Campaign;
+use crate::confetti::Confetti;
+use crate::coop::{self, Partner};
+use crate::daily;
+use crate::engine::{self, Action, GameEngine};
+use crate::eventlog::EventLog;
+use crate::frame::Frame;
+use crate::gauntlet::{self, Gauntlet};
+use crate::hooks::{Event, Hooks};
+use crate::layout::Layout;
+use crate::leaderboard::{Leaderboard, MAX_INITIALS};
+use crate::minefield::{Edge, MineField, MineFieldState, Opening};
+use crate::mode::Mode;
+use crate::motion::MotionKeys;
+use crate::net::{Message, Progress, Rival, Status};
+use crate::pace::{self, Records, Reference};
+use crate::position::Position;
+use crate::protocol::Move;
+use crate::review::{Mark, Moves, PlayedMove, Review};
+use crate::scores::{self, Scores};
+use crate::solver::{self, Deduction, Probability, Verdict};
+use crate::sound::{Cue, Sounds};
+use crate::spectate::Broadcaster;
+use crate::stats::Stats;
+use crate::terminal::{self, color, style, Backend, Guard, Key, RawOutput, Terminal};
+use crate::tile::TileState;
+use crate::tips::{Tip, Tips};
+use crate::versus::Outcome;
+use crate::viewport::Viewport;
+
+/// The line of the side text that the timer is shown on.
+const TIMER_LINE: u16 = 2;
+
+/// The line of the side text that the progress bar is shown on.
+const PROGRESS_LINE: u16 = 3;
+
+/// How many characters wide the progress bar is, between its brackets.
+const PROGRESS_BAR_WIDTH: usize = 20;
+
+/// The fewest rows and columns of tiles worth showing. A terminal too
+/// small to fit them beside the side text can't show the game at all.
+const MIN_VISIBLE_TILES: usize = 5;
+
+/// How many session leaderboard places are shown beside the field.
+const LEADERBOARD_PLACES: usize = 3;
+
+/// The longest name of a tile that can be typed to jump to it, like `AB123`.
+const MAX_COORDINATE: usize = 6;
+
+/// How many of the newest events are shown in the log beside the field.
+const LOG_LINES: usize = 8;
+
+/// How close a revealed tile can get to the edge of an endless field
+/// before it grows, and by how many rows or columns it grows.
+const ENDLESS_MARGIN: usize = 2;
+const ENDLESS_GROWTH: usize = 8;
+
+/// How often the timer is refreshed while waiting for input.
+const TICK_RATE: Duration = Duration::from_millis(200);
+
+/// How often an unfinished game is saved, if it's changed since.
+const AUTOSAVE_INTERVAL: Duration = Duration::from_secs(15);
+
+/// How long each frame of a celebration is shown for.
+const FRAME_TIME: Duration = Duration::from_millis(50);
+
+/// Win streaks that are worth celebrating.
+const STREAK_MILESTONES: [usize; 4] = [3, 5, 10, 25];
+
+/// The colours that confetti comes in.
+const CONFETTI_COLORS: [&dyn color::Color; 5] = [
+    &color::LightRed,
+    &color::LightYellow,
+    &color::LightGreen,
+    &color::LightCyan,
+    &color::LightMagenta,
+];
+
+/// The background colours of the probability overlay, from
+/// certainly safe to certainly a mine.
+const OVERLAY_COLORS: [&dyn color::Color; 4] =
+    [&color::Green, &color::Yellow, &color::LightRed, &color::Red];
+
+/// The background colours of a review, for right flags, wrong flags,
+/// the mine that lost the game, and tiles that could be worked out.
+const REVIEW_COLORS: [&dyn color::Color; 4] =
+    [&color::Green, &color::Red, &color::Magenta, &color::Cyan];
+
+/// Something for the game to handle: a key that was pressed, or a
+/// tick that went by without one.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+enum Input {
+    Key(Key),
+    Tick,
+}
+
+/// What to do once a round has finished.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+enum NextRound {
+    Retry,
+    NewBoard,
+    Finish,
+
+    /// Carry straight on to the next board of a gauntlet.
+    NextStage,
+}
 
 pub struct Minesweeper {
-    field: MineField,
+    engine: GameEngine,
+    viewport: Viewport,
+    scores: Option<Scores>,
+    stats: Option<Stats>,
+    leaderboard: Option<Leaderboard>,
+    hooks: Option<Hooks>,
+    sounds: Option<Sounds>,
+    pace: Option<(Reference, Records)>,
+    tips: Option<Tips>,
+    gauntlet: Option<Gauntlet>,
+    campaign: Option<Campaign>,
+
+    /// How the game is played against the clock, and how many boards
+    /// have been cleared so far in a time attack.
+    mode: Mode,
+    boards_cleared: usize,
+
+    /// The field an endless game started as, to start over from.
+    endless_origin: Option<MineField>,
+
+    /// What the opening cleared on each new board has to be like.
+    first_opening: Opening,
+
+    /// The board as it was loaded from a layout, to go back to on a retry.
+    layout: Option<MineField>,
+
+    /// Where new boards and openings come from, and whether it was
+    /// seeded, so that every board it makes can be known in advance.
+    rng: StdRng,
+    is_seeded: bool,
+    streak: usize,
+    lives: Option<usize>,
+    reduced_motion: bool,
+    flood_speed: FloodSpeed,
+    hint: Option<Deduction>,
+    message: Option<String>,
+    show_mine_count: bool,
+    show_overlay: bool,
+
+    /// What's happened so far this round, shown beside the field while
+    /// the log is turned on.
+    log: EventLog,
+    show_log: bool,
+    auto_flag: bool,
+    highlight_cursor: bool,
+
+    /// Whether the columns are lettered and the rows numbered around
+    /// the field, so tiles can be found by their names like `D7`.
+    show_labels: bool,
+
+    /// How long the game can go without a key before it pauses itself,
+    /// and whether it pauses when the terminal is switched away from.
+    auto_pause: Option<Duration>,
+    pause_on_focus_loss: bool,
+
+    /// Where the game is saved as it's played, so it can be carried on
+    /// with later, when it was last saved and whether it's changed since.
+    autosave: Option<Autosave>,
+    last_saved: Instant,
+    is_unsaved: bool,
+
+    /// How long a game carried on from a save had been played, and
+    /// where its cursor was, until its round starts.
+    resumed: Option<(Duration, Position)>,
+
+    /// The digs, flags and chords of the round, to look back on once
+    /// the round is over.
+    moves: Moves,
+    frame: Frame,
+
+    /// The tiles of a flood that are still drawn hidden, while it opens
+    /// up a frame at a time.
+    covered: HashSet<Position>,
+
+    /// The size of the terminal when the field was last drawn.
+    terminal_size: (u16, u16),
+
+    /// The day of the daily challenge being played, until its first
+    /// round is over.
+    daily: Option<u64>,
+
+    /// The player whose turn it is in a versus game, which is a single
+    /// round, and how their turn went once it's over.
+    turn: Option<String>,
+    outcome: Option<Outcome>,
+
+    /// The other player of a race over the network.
+    rival: Option<Rival>,
+    broadcaster: Option<Broadcaster>,
+
+    /// The guest of a co-op game, playing on this field from elsewhere.
+    partner: Option<Partner>,
 }
 
-/// Wait for a specific key to be pressed.
-fn wait_for_key(target_key: Key) {
-    for key in stdin().keys() {
-        if key.unwrap() == target_key {
-            break;
+/// Read keys on their own thread, so the game can
+/// keep updating while it waits for the next one.
+pub fn spawn_key_reader() -> Receiver<Key> {
+    let (sender, receiver) = mpsc::channel();
+
+    thread::spawn(move || {
+        for key in Terminal::keys() {
+            // stop reading once nobody is listening.
+            if sender.send(key).is_err() {
+                break;
+            }
         }
+    });
+
+    receiver
+}
+
+/// Whether it's been long enough since the last key for the game
+/// to pause itself, if it's meant to.
+fn is_idle(auto_pause: Option<Duration>, since_last_key: Duration) -> bool {
+    auto_pause.is_some_and(|idle| since_last_key >= idle)
+}
+
+/// Wait for the next key, but no longer than a tick, so the game can
+/// keep updating in the meantime. Returns `None` once keys can't be
+/// read anymore.
+fn next_input(keys: &Receiver<Key>) -> Option<Input> {
+    match keys.recv_timeout(TICK_RATE) {
+        Ok(key) => Some(Input::Key(key)),
+        Err(RecvTimeoutError::Timeout) => Some(Input::Tick),
+        Err(RecvTimeoutError::Disconnected) => None,
     }
 }
 
+/// Wait for a specific key to be pressed. Returns false if the game
+/// was interrupted instead.
+pub fn wait_for_key(keys: &Receiver<Key>, target_key: Key) -> bool {
+    keys.iter()
+        .find(|&key| key == target_key || key == terminal::INTERRUPT)
+        == Some(target_key)
+}
+
+/// Wait for one of a few keys to be pressed, returning which one it
+/// was. Interrupting the game counts as quitting.
+fn wait_for_choice(keys: &Receiver<Key>, choices: &[Key]) -> Key {
+    keys.iter()
+        .find(|key| choices.contains(key) || *key == terminal::INTERRUPT)
+        .filter(|&key| key != terminal::INTERRUPT)
+        .unwrap_or(Key::Char('q'))
+}
+
 /// Write some text at a specific position on the console.
-fn write_text(raw_stdout: &mut RawTerminal<Stdout>, string: String, x_pos: u16, y_pos: u16) {
+fn write_text(raw_stdout: &mut RawOutput, string: String, x_pos: u16, y_pos: u16) {
     print!(
         "{}{}{}{}",
-        termion::cursor::Save,
-        termion::cursor::Goto(x_pos, y_pos),
+        terminal::cursor::Save,
+        terminal::cursor::Goto(x_pos, y_pos),
         string,
-        termion::cursor::Restore,
+        terminal::cursor::Restore,
     );
 
     raw_stdout.flush().unwrap();
 }
 
+/// Describe a list of amounts compactly, like "3, 5-7".
+fn describe_amounts(amounts: &[usize]) -> String {
+    let mut runs: Vec<(usize, usize)> = Vec::new();
+
+    for &amount in amounts {
+        match runs.last_mut() {
+            Some((_, end)) if *end + 1 == amount => *end = amount,
+            _ => runs.push((amount, amount)),
+        }
+    }
+
+    runs.iter()
+        .map(|&(start, end)| {
+            if start == end {
+                start.to_string()
+            } else {
+                format!("{}-{}", start, end)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Describe an amount of boards, like `3 boards`.
+fn describe_boards(boards: usize) -> String {
+    match boards {
+        1 => String::from("1 board"),
+        boards => format!("{} boards", boards),
+    }
+}
+
+/// Describe how efficiently a field was cleared, going by its 3BV.
+fn describe_efficiency(three_bv: usize, clicks: usize, time: Duration) -> String {
+    let per_second = three_bv as f64 / time.as_secs_f64().max(0.001);
+    let efficiency = three_bv as f64 / clicks.max(1) as f64 * 100.0;
+
+    format!(
+        "3BV {}, {:.2} 3BV/s, {:.0}% efficiency",
+        three_bv, per_second, efficiency
+    )
+}
+
+/// Pick the overlay colour for a tile's chance of being a mine.
+fn overlay_color(probability: Probability) -> &'static dyn color::Color {
+    match probability.mine {
+        mine if mine <= 0.0 => OVERLAY_COLORS[0],
+        mine if mine < 0.5 => OVERLAY_COLORS[1],
+        mine if mine < 1.0 => OVERLAY_COLORS[2],
+        _ => OVERLAY_COLORS[3],
+    }
+}
+
+/// Pick the review colour for what was made of a tile.
+fn review_color(mark: Mark) -> &'static dyn color::Color {
+    match mark {
+        Mark::RightFlag => REVIEW_COLORS[0],
+        Mark::WrongFlag => REVIEW_COLORS[1],
+        Mark::Fatal => REVIEW_COLORS[2],
+        Mark::Deducible(_) => REVIEW_COLORS[3],
+    }
+}
+
+/// Find the tip describing what a key does, if there is one.
+fn tip_for_key(key: Key) -> Option<Tip> {
+    match key {
+        Key::Up | Key::Down | Key::Left | Key::Right => Some(Tip::Move),
+        Key::Char('h' | 'j' | 'k' | 'l') => Some(Tip::Move),
+        Key::Char(' ') => Some(Tip::Dig),
+        Key::Char('f') => Some(Tip::Flag),
+        Key::Char('d') => Some(Tip::Chord),
+        _ => None,
+    }
+}
+
 /// Methods for the text-based interface of the game.
 impl Minesweeper {
     /// Set up a game with a pre-defined field.
     pub fn with_field(field: MineField) -> Self {
-        Self { field: field }
+        let viewport = Viewport::new(field.height(), field.width());
+
+        Self {
+            engine: GameEngine::new(field),
+            viewport,
+            scores: None,
+            stats: None,
+            leaderboard: None,
+            hooks: None,
+            sounds: None,
+            pace: None,
+            tips: None,
+            gauntlet: None,
+            campaign: None,
+            mode: Mode::Classic,
+            boards_cleared: 0,
+            endless_origin: None,
+            first_opening: Opening::default(),
+            layout: None,
+            rng: StdRng::from_entropy(),
+            is_seeded: false,
+            streak: 0,
+            lives: None,
+            reduced_motion: false,
+            flood_speed: FloodSpeed::default(),
+            hint: None,
+            message: None,
+            show_mine_count: false,
+            show_overlay: false,
+            log: EventLog::new(),
+            show_log: false,
+            auto_flag: false,
+            highlight_cursor: false,
+            show_labels: false,
+            auto_pause: None,
+            pause_on_focus_loss: false,
+            autosave: None,
+            last_saved: Instant::now(),
+            is_unsaved: false,
+            resumed: None,
+            moves: Moves::new(),
+            frame: Frame::new(),
+            covered: HashSet::new(),
+            terminal_size: (0, 0),
+            daily: None,
+            turn: None,
+            outcome: None,
+            rival: None,
+            broadcaster: None,
+            partner: None,
+        }
     }
 
-    /// Set up a fully new, random game.
-    pub fn new(rows: usize, columns: usize, mines: usize) -> Result<Self, &'static str> {
-        let field = MineField::new(rows, columns, mines)?;
-        Ok(Self::with_field(field))
+    /// Set up a game with a field loaded from a layout, played just as
+    /// it was written: no opening is cleared, and retries go back to it.
+    pub fn with_layout(field: MineField) -> Self {
+        Self {
+            layout: Some(field.clone()),
+            ..Self::with_field(field)
+        }
     }
 
-    #[inline]
-    pub fn beginner() -> Self {
-        Self::with_field(MineField::beginner())
+    /// Make new boards and openings with a seeded random number
+    /// generator, so that the game plays out the same way each time.
+    /// Seeded games don't set best times.
+    pub fn use_seeded_rng(&mut self, rng: StdRng) {
+        self.rng = rng;
+        self.is_seeded = true;
     }
 
-    #[inline]
-    pub fn intermediate() -> Self {
-        Self::with_field(MineField::intermediate())
+    /// Play the first round as a day's daily challenge, counting how it
+    /// goes towards the daily streak. This should be on a board seeded
+    /// for the day.
+    pub fn play_daily(&mut self, day: u64) {
+        self.daily = Some(day);
     }
 
-    #[inline]
-    pub fn expert() -> Self {
-        Self::with_field(MineField::expert())
+    /// Stream the field to spectators as it changes.
+    pub fn broadcast(&mut self, broadcaster: Broadcaster) {
+        self.broadcaster = Some(broadcaster);
     }
 
-    /// Write text centred below the field.
-    fn write_text_below(
-        &self,
-        raw_stdout: &mut RawTerminal<Stdout>,
-        string: String,
-        lines_below: u16,
-    ) {
-        let x_offset = self.field.width().saturating_sub(string.len() / 2) as u16;
+    /// Share the field with a guest, who makes moves on it alongside the
+    /// player. Co-op games don't set best times.
+    pub fn cooperate(&mut self, partner: Partner) {
+        self.partner = Some(partner);
+    }
 
-        let y_offset = self.field.height() as u16 + lines_below + 1;
+    /// Record winning times and show the best time for this board.
+    pub fn keep_scores(&mut self, scores: Scores) {
+        self.scores = Some(scores);
+    }
 
-        write_text(raw_stdout, string, x_offset, y_offset)
+    /// Count up the games played on each board, and how they went.
+    pub fn keep_stats(&mut self, stats: Stats) {
+        self.stats = Some(stats);
     }
 
-    /// Write text to the right of the field.
-    fn write_text_beside(&self, raw_stdout: &mut RawTerminal<Stdout>, string: String, line: u16) {
-        write_text(
-            raw_stdout,
-            string,
-            self.field.width() as u16 * 2 + 2,
-            line + 1,
-        );
+    /// Keep a leaderboard of winning times for as long as the program runs.
+    pub fn enable_leaderboard(&mut self) {
+        self.leaderboard = Some(Leaderboard::new());
     }
 
-    /// Display the standard text beside the field.
-    fn display_side_text(&self, mut raw_stdout: &mut RawTerminal<Stdout>) {
-        let game_text = format!(
-            "{}x{} field with {} mines",
-            self.field.width(),
-            self.field.height(),
-            self.field.mines()
-        );
+    /// Play casually, where moves can be undone and redone,
+    /// even a dig that hits a mine. Casual games don't set best times.
+    pub fn enable_casual(&mut self) {
+        self.engine.enable_undo();
+    }
 
-        let flags_text = format!("{} flags used", self.field.flags());
+    /// Flag mines automatically after each dig, once a number shows
+    /// they must be there. These games don't set best times.
+    pub fn enable_auto_flag(&mut self) {
+        self.auto_flag = true;
+        self.engine.enable_auto_flag();
+    }
 
-        self.write_text_beside(&mut raw_stdout, game_text, 0);
-        self.write_text_beside(&mut raw_stdout, flags_text, 1);
+    /// Run shell commands when the game starts, ends or sets a best time.
+    pub fn set_hooks(&mut self, hooks: Hooks) {
+        self.hooks = Some(hooks);
     }
 
-    /// Clear the console and display the field.
-    fn redraw_field(
-        &self,
-        mut raw_stdout: &mut RawTerminal<Stdout>,
-        tile_row: u16,
-        tile_column: u16,
-    ) {
-        // first clear the screen and redraw the field
-        print!(
-            "{}{}{}{}",
-            termion::cursor::Goto(1, 1),
-            termion::clear::All,
-            self.field,
-            termion::cursor::Goto(tile_column * 2 + 1, tile_row + 1),
-        );
+    /// Ring the bell or play sounds when things happen in the game.
+    pub fn set_sounds(&mut self, sounds: Sounds) {
+        self.sounds = Some(sounds);
+    }
 
-        self.display_side_text(&mut raw_stdout);
+    /// Make the sound for something that's happened, if it has one.
+    fn play_cue(&self, cue: Cue) {
+        if let Some(sounds) = &self.sounds {
+            print!("{}", sounds.play(cue));
+        }
     }
 
-    /// Pause the game and keep track of the pause duration.
-    fn pause_game(&self, mut raw_stdout: &mut RawTerminal<Stdout>) -> Duration {
-        self.write_text_below(
-            &mut raw_stdout,
-            String::from("Paused! Press 'p' to unpause."),
-            1,
-        );
+    /// Compare the player's pace against a reference time while playing.
+    pub fn compare_pace(&mut self, reference: Reference, records: Records) {
+        self.pace = Some((reference, records));
+    }
 
-        let paused = SystemTime::now();
-        wait_for_key(Key::Char('p'));
-        let unpaused = SystemTime::now();
+    /// Play a gauntlet of the standard boards instead of a single one,
+    /// keeping the rules and grid of the current field.
+    pub fn start_gauntlet(&mut self) {
+        let gauntlet = Gauntlet::new();
 
-        return unpaused.duration_since(paused).unwrap();
+        if let Some(field) = gauntlet.field(self.engine.field(), &mut self.rng) {
+            self.engine.set_field(field);
+        }
+
+        self.gauntlet = Some(gauntlet);
     }
 
-    /// Play a full round of the game with the interface.
-    pub fn play(&mut self) {
-        // it would be ideal to have this be more detached from the
-        // user interface to some degree, but it should be fine.
+    /// Play through a campaign of puzzles instead of random boards,
+    /// starting on its current puzzle. Each puzzle is played just as
+    /// it was written, like a loaded board.
+    pub fn start_campaign(&mut self, campaign: Campaign) {
+        self.engine.set_field(campaign.field(self.engine.field()));
+        self.layout = Some(self.engine.field().clone());
+        self.campaign = Some(campaign);
+    }
 
-        // set up the first open field before displaying.
-        let (start_row, start_column) = self.field.clear_first_opening().unwrap_or((0, 0));
+    /// Give the player some lives, so that digging a mine flags it and
+    /// costs a life rather than ending the game. These games don't set
+    /// best times.
+    pub fn set_lives(&mut self, lives: usize) {
+        self.lives = Some(lives);
+    }
 
-        let mut tile_row = start_row as u16;
-        let mut tile_column = start_column as u16;
-        let mut check_for_mine = false;
+    /// Clear bigger openings on each new board, or start in the middle.
+    pub fn set_opening(&mut self, opening: Opening) {
+        self.first_opening = opening;
+    }
 
-        let start_time = SystemTime::now();
-        let mut paused_time = Duration::new(0, 0);
+    /// Play against the clock, in a time attack or a countdown.
+    pub fn set_mode(&mut self, mode: Mode) {
+        self.mode = mode;
 
-        let mut raw_stdout = stdout().into_raw_mode().unwrap();
-        self.redraw_field(&mut raw_stdout, tile_row, tile_column);
-        self.display_side_text(&mut raw_stdout);
+        if mode == Mode::Endless {
+            self.endless_origin = Some(self.engine.field().clone());
+        }
+    }
 
-        // this will loop instantly when a key is pressed.
-        for key in stdin().keys() {
-            match key.unwrap() {
-                // cursor controls
-                Key::Up => tile_row = tile_row.saturating_sub(1),
-                Key::Down => tile_row = tile_row.saturating_add(1),
-                Key::Left => tile_column = tile_column.saturating_sub(1),
-                Key::Right => tile_column = tile_column.saturating_add(1),
+    /// Celebrate with a banner alone, rather than falling confetti.
+    pub fn reduce_motion(&mut self) {
+        self.reduced_motion = true;
+    }
 
-                // tile controls. toggles a flag.
-                Key::Char('f') => self
-                    .field
-                    .toggle_flag(tile_row as usize, tile_column as usize)
-                    .unwrap(),
+    /// Change how quickly floods spread out when they're dug, or turn
+    /// that off so they open all at once.
+    pub fn set_flood_speed(&mut self, speed: FloodSpeed) {
+        self.flood_speed = speed;
+    }
 
-                // digs an empty space.
-                Key::Char(' ') => {
-                    self.field
-                        .flood_empty_tiles(tile_row as usize, tile_column as usize)
-                        .unwrap();
+    /// Draw the tile under the cursor inverted, so that it's easy to
+    /// find on big boards.
+    pub fn highlight_cursor(&mut self) {
+        self.highlight_cursor = true;
+    }
 
-                    check_for_mine = true
-                }
+    /// Letter the columns along the top of the field and number the
+    /// rows down its left side.
+    pub fn show_labels(&mut self) {
+        self.show_labels = true;
+    }
+
+    /// Pause the game by itself once no key has been pressed for a while.
+    pub fn set_auto_pause(&mut self, idle: Duration) {
+        self.auto_pause = Some(idle);
+    }
+
+    /// Pause the game by itself when the terminal loses focus, for
+    /// terminals (and backends) that can say so.
+    pub fn pause_on_focus_loss(&mut self) {
+        self.pause_on_focus_loss = true;
+    }
+
+    /// Save each round as it's played, until it's finished.
+    pub fn enable_autosave(&mut self, autosave: Autosave) {
+        self.autosave = Some(autosave);
+    }
+
+    /// Carry on a saved game from how long it had been played, with
+    /// the cursor where it was left.
+    pub fn resume(&mut self, elapsed: Duration, cursor: Position) {
+        self.resumed = Some((elapsed, cursor));
+    }
+
+    /// Save the round as it is now, if it's being saved at all.
+    fn save_round(&mut self) {
+        if let Some(autosave) = &self.autosave {
+            let cursor = self.engine.cursor();
+            autosave
+                .save(self.engine.field(), self.engine.elapsed(), cursor)
+                .ok();
+        }
+
+        self.last_saved = Instant::now();
+        self.is_unsaved = false;
+    }
+
+    /// Leave a round partway through, saving it to carry on with later.
+    fn leave_round(&mut self) -> NextRound {
+        self.save_round();
+        NextRound::Finish
+    }
+
+    /// Put a round away once play stops. One that's quit partway through
+    /// is saved to come back to, returning true, while one that's won or
+    /// lost has its save removed, since it can't be carried on with.
+    fn put_away_round(&mut self, is_out_of_time: bool) -> bool {
+        let is_unfinished =
+            self.engine.field().get_state() == MineFieldState::InProgress && !is_out_of_time;
 
-                // performs a chording move.
-                Key::Char('d') => {
-                    self.field
-                        .do_chord(tile_row as usize, tile_column as usize)
-                        .unwrap();
+        if self.autosave.is_some() && is_unfinished {
+            self.save_round();
+            return true;
+        }
+
+        if let Some(autosave) = &self.autosave {
+            autosave.remove();
+        }
+
+        false
+    }
+
+    /// Show contextual tips for controls the player hasn't used yet.
+    pub fn enable_tips(&mut self, tips: Tips) {
+        self.tips = Some(tips);
+    }
+
+    /// The room taken up by the coordinate labels, if they're shown: the
+    /// columns to the left of the field and the rows above it.
+    fn label_margin(&self) -> (usize, usize) {
+        match self.show_labels {
+            true => (self.engine.field().row_label_width() + 1, 1),
+            false => (0, 0),
+        }
+    }
+
+    /// How many characters across each tile takes up on screen, which is
+    /// more than two when the column labels need the room.
+    fn cell_width(&self) -> usize {
+        match self.show_labels {
+            true => self.engine.field().label_cell_width(),
+            false => 2,
+        }
+    }
+
+    /// The width of the visible part of the field on screen, in characters,
+    /// including its labels.
+    fn field_width(&self) -> usize {
+        self.viewport.columns() * self.viewport.cell_width()
+            + self.engine.field().topology().max_row_offset()
+            + self.label_margin().0
+    }
+
+    /// Work out where everything goes around the field as it is now.
+    fn layout(&self) -> Layout {
+        Layout::new(
+            self.terminal_size,
+            self.field_width(),
+            self.viewport.rows() + self.label_margin().1,
+            self.side_text().len(),
+        )
+    }
+
+    /// Write text centred below the field.
+    fn write_text_below(&self, raw_stdout: &mut RawOutput, string: String, lines_below: u16) {
+        let (x_pos, y_pos) = self.layout().below(string.len(), lines_below);
+        write_text(raw_stdout, string, x_pos, y_pos)
+    }
 
-                    check_for_mine = true
+    /// Write text to the right of the field.
+    fn write_text_beside(&self, raw_stdout: &mut RawOutput, string: String, line: u16) {
+        let (x_pos, y_pos) = self.layout().beside(line);
+        write_text(raw_stdout, string, x_pos, y_pos);
+    }
+
+    /// Build the lines of text shown beside the field.
+    ///
+    /// The timer and progress bar are always on `TIMER_LINE` and
+    /// `PROGRESS_LINE`, so they can be updated alone.
+    fn side_text(&self) -> Vec<String> {
+        let mut lines = vec![
+            format!(
+                "{}x{} {}field with {} mines",
+                self.engine.field().width(),
+                self.engine.field().height(),
+                match self.engine.field().topology().name() {
+                    Some(name) => format!("{} ", name),
+                    None => String::new(),
+                },
+                self.engine.field().mines()
+            ),
+            match self.engine.field().rules() {
+                rules if rules.no_flags => {
+                    format!("{} mines, flagless", self.engine.field().mines())
                 }
+                rules => format!(
+                    "{} mines left, {} flags {}",
+                    self.engine.field().mines_remaining(),
+                    self.engine.field().flags(),
+                    match rules.strict_flags {
+                        true => format!("of {}", self.engine.field().mines()),
+                        false => String::from("used"),
+                    }
+                ),
+            },
+            self.timer_text(),
+            self.progress_text(),
+        ];
 
-                // miscellaneous controls
-                Key::Char('p') => paused_time += self.pause_game(&mut raw_stdout),
-                Key::Char('q') => break,
-                _ => continue,
-            };
+        // an endless field grows as it's cleared, so it's never nearly done.
+        if self.mode != Mode::Endless {
+            let (revealed, total) = self.engine.field().progress();
+
+            lines.push(match total - revealed {
+                1 => String::from("1 safe tile to go"),
+                left => format!("{} safe tiles to go", left),
+            });
+        }
+
+        // the rest of the lines only show up when they're relevant.
+        if let Some(best_time) = self.best_time() {
+            lines.push(format!("best: {} seconds", best_time.as_secs()));
+        }
+
+        if self.streak > 1 {
+            lines.push(format!("streak: {} wins", self.streak));
+        }
+
+        if let Some(day) = self.daily {
+            lines.push(format!("daily challenge {}", daily::date(day)));
+        }
+
+        if let Some(player) = &self.turn {
+            lines.push(format!("versus: {}'s turn", player));
+        }
+
+        if let Some(rival) = &self.rival {
+            lines.push(rival.describe());
+        }
+
+        if let Some(partner) = &self.partner {
+            lines.push(String::from(partner.describe()));
+        }
+
+        if let Some(campaign) = &self.campaign {
+            let (number, puzzles) = campaign.position();
+            let puzzle = campaign.puzzle();
 
-            // ensure that the cursor stays in range.
-            if tile_row >= self.field.height() as u16 {
-                tile_row = self.field.height() as u16 - 1
+            lines.push(format!("puzzle {} of {}: {}", number, puzzles, puzzle.name));
+            lines.push(format!("goal: {}", puzzle.objective.describe()));
+        }
+
+        if let Some(mode) = self.mode.describe() {
+            lines.push(mode);
+        }
+
+        if self.mode.carries_on() {
+            lines.push(format!("boards cleared: {}", self.boards_cleared));
+        }
+
+        if let Some(gauntlet) = &self.gauntlet {
+            let stage = (gauntlet.stage() + 1).min(gauntlet.stages());
+            lines.push(format!(
+                "gauntlet: board {} of {}",
+                stage,
+                gauntlet.stages()
+            ));
+
+            for (name, time) in gauntlet.splits() {
+                lines.push(format!("  {} {:.1}s", name, time.as_secs_f64()));
             }
+        }
 
-            if tile_column >= self.field.width() as u16 {
-                tile_column = self.field.width() as u16 - 1
+        if let Some(leaderboard) = &self.leaderboard {
+            let places =
+                leaderboard.top(&scores::board_key(self.engine.field()), LEADERBOARD_PLACES);
+
+            for (place, entry) in places.iter().enumerate() {
+                lines.push(format!(
+                    "{}. {} {} seconds",
+                    place + 1,
+                    entry.initials,
+                    entry.time.as_secs()
+                ));
             }
+        }
 
-            // if a space has been cleared, there may be a mine.
-            if check_for_mine {
-                // check if the game has been finished.
-                if self.field.get_state() != MineFieldState::InProgress {
-                    break;
-                };
+        if self.engine.can_undo() {
+            lines.push(String::from("casual: u undo, ^r redo"));
+        }
 
-                check_for_mine = false;
+        if self.auto_flag {
+            lines.push(String::from("auto-flagging"));
+        }
+
+        if let Some(lives) = self.lives {
+            lines.push(format!("lives: {} of {}", self.engine.lives_left(), lives));
+        }
+
+        if let Some(region) = self.engine.field().target() {
+            lines.push(format!(
+                "clear the {}x{} target",
+                region.columns, region.rows
+            ));
+        }
+
+        if self.show_mine_count {
+            lines.extend(self.mine_count_text());
+        }
+
+        if self.show_log {
+            lines.push(String::from("log:"));
+            lines.extend(self.log.recent(LOG_LINES).map(|line| format!("  {}", line)));
+        }
+
+        // show which way the board continues when it doesn't fit.
+        let indicators = self
+            .viewport
+            .scroll_indicators(self.engine.field().height(), self.engine.field().width());
+
+        if !indicators.is_empty() {
+            lines.push(format!("more: {}", indicators));
+        }
+
+        lines
+    }
+
+    /// The best time to go by, which is for the whole run in a gauntlet.
+    fn best_time(&self) -> Option<Duration> {
+        let key = match self.gauntlet {
+            Some(_) => gauntlet::board_key(self.engine.field()),
+            None => scores::board_key(self.engine.field()),
+        };
+
+        self.scores.as_ref()?.best(&key)
+    }
+
+    /// Describe how the unflagged mines could be split between the
+    /// frontier and the tiles away from it, for endgame counting.
+    fn mine_count_text(&self) -> Vec<String> {
+        let count = match solver::mine_count(self.engine.field()) {
+            Some(count) => count,
+            None => return vec![String::from("count: check your flags")],
+        };
+
+        let mut lines = vec![format!(
+            "edge: {} tiles, {} mines",
+            count.frontier_tiles,
+            describe_amounts(&count.frontier_mines)
+        )];
+
+        if let Some(remaining) = count.remaining {
+            let mut interior_mines: Vec<usize> = count
+                .frontier_mines
+                .iter()
+                .map(|&mines| remaining - mines)
+                .collect();
+
+            interior_mines.reverse();
+
+            lines.insert(0, format!("mines left: {}", remaining));
+            lines.push(format!(
+                "inside: {} tiles, {} mines",
+                count.interior_tiles,
+                describe_amounts(&interior_mines)
+            ));
+        }
+
+        lines
+    }
+
+    /// Display the standard text beside the field.
+    fn display_side_text(&self, raw_stdout: &mut RawOutput) {
+        if self.is_too_small() {
+            return;
+        }
+
+        let lines = self.side_text();
+        let count = lines.len();
+
+        // the field isn't cleared between frames, so wipe out whatever
+        // was there before, including any lines that have gone away.
+        for (line, text) in lines.into_iter().enumerate() {
+            let text = format!("{}{}", text, terminal::clear::UntilNewline);
+            self.write_text_beside(raw_stdout, text, line as u16);
+        }
+
+        for line in count..self.viewport.rows() {
+            let text = terminal::clear::UntilNewline.to_string();
+            self.write_text_beside(raw_stdout, text, line as u16);
+        }
+    }
+
+    /// Describe the time taken so far, with the pace if it's being compared.
+    fn timer_text(&self) -> String {
+        let elapsed = self.engine.elapsed();
+
+        // against the clock, it's the time left that matters.
+        if let Some(time_left) = self.mode.time_left(elapsed) {
+            return format!("{:.0} seconds left", time_left.as_secs_f64().ceil());
+        }
+
+        match self.pace_text(elapsed) {
+            Some(pace) => format!("{} seconds ({})", elapsed.as_secs(), pace),
+            None => format!("{} seconds", elapsed.as_secs()),
+        }
+    }
+
+    /// The fraction of the safe tiles that have been revealed.
+    fn progress_fraction(&self) -> f64 {
+        let (revealed, total) = self.engine.field().progress();
+
+        if total == 0 {
+            1.0
+        } else {
+            revealed as f64 / total as f64
+        }
+    }
+
+    /// Describe how far ahead of or behind the reference time the player
+    /// is, if a pace is being compared and the board has a reference time.
+    fn pace_text(&self, elapsed: Duration) -> Option<String> {
+        let (reference, records) = self.pace.as_ref()?;
+        let key = scores::board_key(self.engine.field());
+
+        let reference_time = match reference {
+            Reference::Record => records.get(&key)?,
+            Reference::Best => self.scores.as_ref()?.best(&key)?,
+            Reference::Time(time) => *time,
+        };
+
+        let delta = pace::delta(elapsed, self.progress_fraction(), reference_time);
+        Some(format!(
+            "{} {}",
+            pace::describe_delta(delta),
+            reference.describe()
+        ))
+    }
+
+    /// Draw a bar of how many safe tiles have been revealed.
+    ///
+    /// With a best time to go by, the bar is green while the player is
+    /// ahead of that pace and red while they're behind it.
+    fn progress_text(&self) -> String {
+        // an endless field can't be cleared, so only the tiles count.
+        if self.mode == Mode::Endless {
+            return format!("{} tiles cleared", self.engine.field().progress().0);
+        }
+
+        let fraction = self.progress_fraction();
+
+        let filled = (fraction * PROGRESS_BAR_WIDTH as f64) as usize;
+
+        // a gauntlet's best time covers every board, so it says nothing
+        // about the pace on this one.
+        let best_time = match self.gauntlet {
+            Some(_) => None,
+            None => self.best_time(),
+        };
+
+        let pace_color: &dyn color::Color = match best_time {
+            Some(best) if best.as_secs_f64() > 0.0 => {
+                let expected = self.engine.elapsed().as_secs_f64() / best.as_secs_f64();
+
+                if fraction >= expected {
+                    &color::Green
+                } else {
+                    &color::Red
+                }
             }
+            _ => &color::Reset,
+        };
 
-            // redraw the field after every key event.
-            self.redraw_field(&mut raw_stdout, tile_row, tile_column);
-            self.display_side_text(&mut raw_stdout);
+        format!(
+            "[{}{}] {:>3}%",
+            self.engine
+                .field()
+                .theme()
+                .paint(&"#".repeat(filled), pace_color),
+            "-".repeat(PROGRESS_BAR_WIDTH - filled),
+            (fraction * 100.0) as usize
+        )
+    }
+
+    /// Display the time taken so far beside the field, along with
+    /// the progress bar, whose pace changes as time goes on.
+    fn display_timer(&self, raw_stdout: &mut RawOutput) {
+        if self.is_too_small() {
+            return;
         }
 
-        self.field.game_over();
-        self.redraw_field(&mut raw_stdout, tile_row, tile_column);
-        self.display_side_text(&mut raw_stdout);
+        // the pace can get shorter as well as longer, so clear what was there.
+        let timer_text = format!("{}{}", self.timer_text(), terminal::clear::UntilNewline);
 
-        let time_taken = SystemTime::now().duration_since(start_time).unwrap() - paused_time;
-        let time_text = format!("You took {} seconds", time_taken.as_secs());
+        self.write_text_beside(raw_stdout, timer_text, TIMER_LINE);
+        self.write_text_beside(raw_stdout, self.progress_text(), PROGRESS_LINE);
+    }
 
-        // hide the cursor and wait for a keypress to finish.
-        print!("{}", termion::cursor::Hide);
+    /// Let the player know a dug mine cost them a life, and how many
+    /// they have left.
+    fn lose_life(&mut self, lives_left: usize) {
+        self.play_cue(Cue::Mine);
+        self.message = Some(match lives_left {
+            1 => String::from("Boom! 1 life left"),
+            lives => format!("Boom! {} lives left", lives),
+        });
+    }
 
-        self.write_text_below(&mut raw_stdout, time_text, 1);
-        self.write_text_below(&mut raw_stdout, String::from("Press 'q' to finish"), 2);
-        wait_for_key(Key::Char('q'));
+    /// Ask the solver for a tile that can be worked out from the board.
+    fn find_hint(&mut self, cursor: Position) {
+        self.hint = solver::hint(self.engine.field(), cursor);
 
-        // clear the screen upon completion.
-        print!(
-            "{}{}{}",
-            termion::cursor::Show,
-            termion::clear::All,
-            termion::cursor::Goto(1, 1)
-        );
+        self.message = Some(match self.hint {
+            Some(Deduction {
+                position: Position { row, column },
+                verdict,
+            }) => format!(
+                "Hint: row {}, column {} is {}",
+                row + 1,
+                column + 1,
+                match verdict {
+                    Verdict::Safe => "safe",
+                    Verdict::Mine => "a mine",
+                }
+            ),
+            None => {
+                // with nothing certain, point out the safest guess instead.
+                let safest = solver::probabilities(self.engine.field())
+                    .into_iter()
+                    .min_by(|a, b| a.mine.partial_cmp(&b.mine).unwrap());
 
-        raw_stdout.flush().unwrap();
+                match safest {
+                    Some(guess) => format!(
+                        "Hint: time to guess! Row {}, column {} is {:.0}% likely a mine",
+                        guess.position.row + 1,
+                        guess.position.column + 1,
+                        guess.mine * 100.0
+                    ),
+                    None => String::from("Hint: nothing can be deduced, check your flags"),
+                }
+            }
+        });
+    }
+
+    /// Highlight the hinted tile, if there is one on screen.
+    fn display_hint(&mut self, raw_stdout: &mut RawOutput) {
+        let hint = match self.hint {
+            Some(hint) if self.viewport.contains(hint.position) => hint,
+            _ => return,
+        };
+
+        let tile_text = self.engine.field().char_for_tile(hint.position).unwrap();
+        let (x_pos, y_pos) = self.screen_position(hint.position);
+
+        // without colour, the message below says which way the hint goes.
+        let highlighted = match hint.verdict {
+            _ if !self.engine.field().theme().has_color() => {
+                format!("{}{}{}", style::Invert, tile_text, style::Reset)
+            }
+            Verdict::Safe => format!(
+                "{}{}{}",
+                color::Bg(color::Green),
+                tile_text,
+                color::Bg(color::Reset)
+            ),
+            Verdict::Mine => format!(
+                "{}{}{}",
+                color::Bg(color::Red),
+                tile_text,
+                color::Bg(color::Reset)
+            ),
+        };
+
+        write_text(raw_stdout, highlighted, x_pos, y_pos);
+        self.frame.forget(x_pos, y_pos);
+    }
+
+    /// Colour every hidden tile on screen by its chance of being a mine,
+    /// if the overlay is showing, and give the exact chance for the tile
+    /// under the cursor when there isn't another message.
+    fn display_overlay(&mut self, raw_stdout: &mut RawOutput, cursor: Position) {
+        if !self.show_overlay {
+            return;
+        }
+
+        let probabilities = solver::probabilities(self.engine.field());
+
+        // without colour, only the chance under the cursor can be shown.
+        let tiles = if self.engine.field().theme().has_color() {
+            probabilities.as_slice()
+        } else {
+            &[]
+        };
+
+        for &probability in tiles {
+            let position = probability.position;
+
+            if !self.viewport.contains(position) {
+                continue;
+            }
+
+            let tile_text = self.engine.field().char_for_tile(position).unwrap();
+            let (x_pos, y_pos) = self.screen_position(position);
+
+            write_text(
+                raw_stdout,
+                format!(
+                    "{}{}{}",
+                    color::Bg(overlay_color(probability)),
+                    tile_text,
+                    color::Bg(color::Reset)
+                ),
+                x_pos,
+                y_pos,
+            );
+            self.frame.forget(x_pos, y_pos);
+        }
+
+        let under_cursor = probabilities
+            .iter()
+            .find(|probability| probability.position == cursor);
+
+        if let (None, Some(probability)) = (&self.message, under_cursor) {
+            self.message = Some(format!(
+                "Row {}, column {} is {:.0}% likely a mine",
+                cursor.row + 1,
+                cursor.column + 1,
+                probability.mine * 100.0
+            ));
+        }
+    }
+
+    /// Colour every tile on screen by what the review makes of it, with
+    /// the tile under the cursor described below the field.
+    fn display_review(&mut self, raw_stdout: &mut RawOutput, review: &Review, cursor: Position) {
+        // without colour, the description under the cursor has to say it all.
+        let positions = match self.engine.field().theme().has_color() {
+            true => self.engine.field().iter_positions().collect(),
+            false => vec![],
+        };
+
+        for position in positions {
+            let mark = match review.mark(position) {
+                Some(mark) if self.viewport.contains(position) => mark,
+                _ => continue,
+            };
+
+            let tile_text = self.engine.field().char_for_tile(position).unwrap();
+            let (x_pos, y_pos) = self.screen_position(position);
+
+            write_text(
+                raw_stdout,
+                format!(
+                    "{}{}{}",
+                    color::Bg(review_color(mark)),
+                    tile_text,
+                    color::Bg(color::Reset)
+                ),
+                x_pos,
+                y_pos,
+            );
+            self.frame.forget(x_pos, y_pos);
+        }
+
+        self.write_text_below(raw_stdout, review.describe(cursor), 1);
+        self.write_text_below(raw_stdout, review.summary(), 2);
+        self.write_text_below(
+            raw_stdout,
+            String::from("Reviewing: move around, then 'v' to go back"),
+            3,
+        );
+    }
+
+    /// Draw the tile under the cursor inverted, if the cursor is highlighted.
+    fn display_cursor(&mut self, raw_stdout: &mut RawOutput, cursor: Position) {
+        if !self.highlight_cursor {
+            return;
+        }
+
+        let tile_text = self.engine.field().char_for_tile(cursor).unwrap();
+        let (x_pos, y_pos) = self.screen_position(cursor);
+
+        write_text(
+            raw_stdout,
+            format!("{}{}{}", style::Invert, tile_text, style::Reset),
+            x_pos,
+            y_pos,
+        );
+        self.frame.forget(x_pos, y_pos);
+    }
+
+    /// Display where the guest of a co-op game has their cursor.
+    fn display_partner_cursor(&mut self, raw_stdout: &mut RawOutput) {
+        let position = match self.partner.as_ref().and_then(Partner::cursor) {
+            Some(position) if self.viewport.contains(position) => position,
+            _ => return,
+        };
+
+        let tile_text = self.engine.field().char_for_tile(position).unwrap();
+        let (x_pos, y_pos) = self.screen_position(position);
+
+        write_text(
+            raw_stdout,
+            format!("{}{}{}", coop::partner_highlight(), tile_text, style::Reset),
+            x_pos,
+            y_pos,
+        );
+        self.frame.forget(x_pos, y_pos);
+    }
+
+    /// Make the moves of the guest of a co-op game, in the order they
+    /// came in. Returns whether anything has changed, including their
+    /// cursor or connection.
+    fn make_partner_moves(&mut self) -> bool {
+        let moves = match self.partner.as_mut().and_then(Partner::take_moves) {
+            Some(moves) => moves,
+            None => return false,
+        };
+
+        for tile_move in moves {
+            // moves made after the game is over are too late to count.
+            if self.engine.field().get_state() != MineFieldState::InProgress {
+                break;
+            }
+
+            let (action, position) = match tile_move {
+                Move::Dig { row, column } => (Action::Dig, Position::new(row, column)),
+                Move::Flag { row, column } => (Action::Flag, Position::new(row, column)),
+                Move::Chord { row, column } => (Action::Chord, Position::new(row, column)),
+                Move::Cursor { .. } => continue,
+            };
+
+            // a move that can't be made, like one off the field, is ignored.
+            if !self.engine.field().position_is_valid(position) {
+                continue;
+            }
+
+            // the move is made under the guest's cursor, then the host's
+            // cursor is put back where it was.
+            let cursor = self.engine.cursor();
+            self.engine.set_cursor(position);
+
+            let events = self.engine.apply(action);
+            let played = PlayedMove::new(action, position, &events, self.engine.field());
+            self.moves.record(played);
+
+            for event in events {
+                self.log.record(action, position, &event);
+
+                if let engine::Event::LifeLost { lives_left } = event {
+                    self.lose_life(lives_left);
+                }
+            }
+
+            self.engine.set_cursor(cursor);
+        }
+
+        true
+    }
+
+    /// Display the current message below the field, or
+    /// otherwise a tip for the tile under the cursor.
+    fn display_message(&self, raw_stdout: &mut RawOutput, cursor: Position) {
+        if let Some(message) = &self.message {
+            self.write_text_below(raw_stdout, message.clone(), 1);
+        } else {
+            self.display_tip(raw_stdout, cursor);
+        }
+    }
+
+    /// Display a tip for the tile under the cursor, if one applies.
+    fn display_tip(&self, raw_stdout: &mut RawOutput, cursor: Position) {
+        let tip = self
+            .tips
+            .as_ref()
+            .and_then(|tips| tips.tip_for(self.engine.field(), cursor));
+
+        if let Some(tip) = tip {
+            self.write_text_below(raw_stdout, String::from(tip.message()), 1);
+        }
+    }
+
+    /// Describe the tile under the cursor and the keys that do something
+    /// with it, for the status bar.
+    fn status_text(&self, cursor: Position) -> String {
+        let tile = self.engine.field().get_tile(cursor).unwrap();
+
+        let mut keys = match tile.state {
+            _ if tile.is_void => vec![],
+            TileState::Hidden => vec!["space dig", "f flag", "m note"],
+            TileState::Flagged => vec!["f unflag"],
+            TileState::Visible if self.engine.field().has_mines_near(cursor).unwrap() => {
+                vec!["d chord"]
+            }
+            TileState::Visible => vec![],
+        };
+
+        if self.engine.can_undo() {
+            keys.push("u undo");
+        }
+
+        keys.extend(["p pause", "q quit"]);
+
+        format!(
+            " row {}, column {}: {} | {}",
+            cursor.row + 1,
+            cursor.column + 1,
+            self.engine.field().describe_tile(cursor).unwrap(),
+            keys.join("  ")
+        )
+    }
+
+    /// Display the status bar along the bottom of the terminal.
+    fn display_status_bar(&self, raw_stdout: &mut RawOutput, cursor: Position) {
+        let ((x_pos, y_pos), width) = self.layout().status_bar();
+
+        // the last column is left alone, so the terminal doesn't scroll.
+        let width = width.saturating_sub(1);
+        let text: String = self.status_text(cursor).chars().take(width).collect();
+
+        write_text(
+            raw_stdout,
+            format!(
+                "{}{:width$}{}",
+                style::Invert,
+                text,
+                style::Reset,
+                width = width
+            ),
+            x_pos,
+            y_pos,
+        );
+    }
+
+    /// Get the (x, y) console position of a visible tile,
+    /// allowing for rows that are shifted along and the labels.
+    fn screen_position(&self, position: Position) -> (u16, u16) {
+        let (x, y) = self.viewport.screen_position(position);
+        let offset = self.engine.field().topology().row_offset(position.row);
+        let (left, top) = self.label_margin();
+
+        (x + (offset + left) as u16, y + top as u16)
+    }
+
+    /// The cells of the coordinate labels around the visible tiles, with
+    /// each column's letters above its unshifted tiles.
+    fn label_cells(&self) -> Vec<((u16, u16), String)> {
+        let field = self.engine.field();
+        let (left, top) = self.label_margin();
+        let bottom = (self.viewport.top() + self.viewport.rows()).min(field.height());
+        let right = (self.viewport.left() + self.viewport.columns()).min(field.width());
+
+        let columns = (self.viewport.left()..right).map(|column| {
+            let (x, _) = self.viewport.screen_position(Position::new(0, column));
+            let letters = Position::column_letters(column);
+
+            let cell_width = self.viewport.cell_width();
+            (
+                (x + left as u16, 1),
+                format!("{:<cell_width$}", letters, cell_width = cell_width),
+            )
+        });
+
+        let rows = (self.viewport.top()..bottom).map(|row| {
+            let (_, y) = self.viewport.screen_position(Position::new(row, 0));
+            let number = format!("{:>width$} ", row + 1, width = left - 1);
+
+            ((1, y + top as u16), number)
+        });
+
+        columns.chain(rows).collect()
+    }
+
+    /// Fit the viewport to the terminal and scroll it to the cursor.
+    fn update_viewport(&mut self, cursor: Position) {
+        self.terminal_size = Terminal::size().unwrap_or((80, 24));
+        let (width, rows) = Layout::field_space(self.terminal_size);
+        let (left, top) = self.label_margin();
+        let cell_width = self.cell_width();
+
+        let columns = width.saturating_sub(self.engine.field().topology().max_row_offset() + left)
+            / cell_width;
+        let rows = rows.saturating_sub(top);
+
+        self.viewport.set_cell_width(cell_width);
+
+        self.viewport.resize(
+            rows,
+            columns,
+            self.engine.field().height(),
+            self.engine.field().width(),
+        );
+        self.viewport.follow(cursor);
+    }
+
+    /// The smallest terminal, in columns and rows, that the game can
+    /// be drawn in with a few rows and columns of the field.
+    fn minimum_size(&self) -> (u16, u16) {
+        let rows = self.engine.field().height().min(MIN_VISIBLE_TILES);
+        let columns = self.engine.field().width().min(MIN_VISIBLE_TILES);
+        let (left, top) = self.label_margin();
+
+        Layout::minimum_size(
+            columns * self.cell_width() + self.engine.field().topology().max_row_offset() + left,
+            rows + top,
+        )
+    }
+
+    /// Check if the terminal was too small to draw the game in, when
+    /// the field was last drawn.
+    fn is_too_small(&self) -> bool {
+        let (width, height) = self.minimum_size();
+        self.terminal_size.0 < width || self.terminal_size.1 < height
+    }
+
+    /// Check if the terminal has changed size since the field was drawn.
+    fn is_resized(&self) -> bool {
+        Terminal::size().is_ok_and(|size| size != self.terminal_size)
+    }
+
+    /// Check if the game is being made easier, with undos, lives or
+    /// automatic flags, in which case it can't set best times.
+    fn is_assisted(&self) -> bool {
+        self.engine.can_undo() || self.lives.is_some() || self.auto_flag
+    }
+
+    /// Render the part of the field inside the viewport, with its labels.
+    fn visible_field(&self) -> String {
+        let area = (
+            self.viewport.top(),
+            self.viewport.left(),
+            self.viewport.rows(),
+            self.viewport.columns(),
+        );
+
+        match self.show_labels {
+            true => self
+                .engine
+                .field()
+                .render_labelled_area(area.0, area.1, area.2, area.3),
+            false => self
+                .engine
+                .field()
+                .render_area(area.0, area.1, area.2, area.3),
+        }
+    }
+
+    /// Drop confetti down over the field, unless motion is turned down.
+    ///
+    /// Each frame draws the field again without clearing the screen
+    /// first, which wipes out the last frame's confetti without flicker.
+    fn celebrate(&self, raw_stdout: &mut RawOutput) {
+        if self.reduced_motion {
+            return;
+        }
+
+        let width = self.field_width();
+        let height = self.viewport.rows();
+        let mut confetti = Confetti::new(width as u16, height as u16, CONFETTI_COLORS.len());
+
+        while confetti.step() {
+            print!("{}{}", terminal::cursor::Goto(1, 1), self.visible_field());
+
+            for speck in confetti.specks() {
+                print!(
+                    "{}{}",
+                    terminal::cursor::Goto(speck.column + 1, speck.row + 1),
+                    self.engine
+                        .field()
+                        .theme()
+                        .paint(&speck.symbol.to_string(), CONFETTI_COLORS[speck.color]),
+                );
+            }
+
+            raw_stdout.flush().unwrap();
+            thread::sleep(FRAME_TIME);
+        }
+
+        print!("{}{}", terminal::cursor::Goto(1, 1), self.visible_field());
+        raw_stdout.flush().unwrap();
+    }
+
+    /// Show a flood that has just been dug opening up a frame at a time,
+    /// spreading out from the dug tile, unless motion is turned down.
+    ///
+    /// The flood is only drawn covered back up, so the field itself is
+    /// left just as it was.
+    fn animate_flood(
+        &mut self,
+        raw_stdout: &mut RawOutput,
+        cursor: Position,
+        waves: &[Vec<Position>],
+    ) {
+        let frame_time = match self.flood_speed.frame_time() {
+            Some(frame_time) if waves.len() > 1 && !self.reduced_motion => frame_time,
+            _ => return,
+        };
+
+        self.covered = waves.iter().flatten().copied().collect();
+
+        for frame in animation::flood_frames(waves) {
+            for position in frame {
+                self.covered.remove(&position);
+            }
+
+            self.redraw_field(raw_stdout, cursor);
+            raw_stdout.flush().unwrap();
+            thread::sleep(frame_time);
+        }
+    }
+
+    /// Draw the field and everything around and over it.
+    fn draw(&mut self, raw_stdout: &mut RawOutput, cursor: Position) {
+        self.redraw_field(raw_stdout, cursor);
+
+        if self.is_too_small() {
+            raw_stdout.flush().unwrap();
+            return;
+        }
+
+        self.display_side_text(raw_stdout);
+        self.display_hint(raw_stdout);
+        self.display_overlay(raw_stdout, cursor);
+        self.display_cursor(raw_stdout, cursor);
+        self.display_partner_cursor(raw_stdout);
+        self.display_message(raw_stdout, cursor);
+        self.display_status_bar(raw_stdout, cursor);
+    }
+
+    /// Display the visible part of the field.
+    ///
+    /// Only the tiles that changed since the last frame are drawn, unless
+    /// the field has scrolled, when the screen is cleared and drawn afresh.
+    fn redraw_field(&mut self, raw_stdout: &mut RawOutput, cursor: Position) {
+        // spectators see the whole field, whatever fits on this screen.
+        if let Some(broadcaster) = &self.broadcaster {
+            broadcaster.update(self.engine.field(), cursor);
+        }
+
+        if let Some(partner) = &mut self.partner {
+            partner.update(self.engine.field(), cursor);
+        }
+
+        self.update_viewport(cursor);
+
+        let area = (
+            self.viewport.top(),
+            self.viewport.left(),
+            self.viewport.rows(),
+            self.viewport.columns(),
+        );
+
+        if self.is_too_small() {
+            let (width, height) = self.minimum_size();
+            self.frame.clear();
+
+            print!(
+                "{}{}Terminal too small.{}Need {}x{} to play.",
+                terminal::clear::All,
+                terminal::cursor::Goto(1, 1),
+                terminal::cursor::Goto(1, 2),
+                width,
+                height
+            );
+            return;
+        }
+
+        if self.frame.move_to(area) {
+            print!("{}", terminal::clear::All);
+        } else {
+            // messages below the field only last until the next frame.
+            let below = self.layout().under_field();
+            print!(
+                "{}{}",
+                terminal::cursor::Goto(1, below),
+                terminal::clear::AfterCursor
+            );
+        }
+
+        // wide glyphs fill two characters on their own.
+        let gap = match self.engine.field().skin().is_wide() {
+            true => " ".repeat(self.viewport.cell_width() - 2),
+            false => " ".repeat(self.viewport.cell_width() - 1),
+        };
+        let bottom = (area.0 + area.2).min(self.engine.field().height());
+        let right = (area.1 + area.3).min(self.engine.field().width());
+
+        let mut cells = Vec::new();
+
+        for row in area.0..bottom {
+            for column in area.1..right {
+                let tile = Position::new(row, column);
+                let field = self.engine.field();
+
+                let text = match self.covered.contains(&tile) {
+                    true => {
+                        let in_target = field.target().is_some_and(|region| region.contains(tile));
+                        field.theme().hidden(field.skin(), in_target)
+                    }
+                    false => field.char_for_tile(tile).unwrap(),
+                };
+                let position = self.screen_position(tile);
+
+                cells.push((position, format!("{}{}", text, gap)));
+            }
+        }
+
+        if self.show_labels {
+            cells.extend(self.label_cells());
+        }
+
+        let (cursor_x, cursor_y) = self.screen_position(cursor);
+
+        print!(
+            "{}{}",
+            self.frame.update(cells),
+            terminal::cursor::Goto(cursor_x, cursor_y),
+        );
+
+        self.display_side_text(raw_stdout);
+    }
+
+    /// Grow an endless field at each edge that has been cleared up close
+    /// to, keeping the cursor and the view on the same tiles.
+    ///
+    /// Each edge only grows once a move, since an opening can spill over
+    /// into the new tiles all the way to the next edge.
+    fn expand_field(&mut self, cursor: &mut Position) {
+        for edge in self.engine.field().edges_near_revealed(ENDLESS_MARGIN) {
+            self.engine.grow_with(edge, ENDLESS_GROWTH, &mut self.rng);
+
+            match edge {
+                Edge::Top => {
+                    cursor.row += ENDLESS_GROWTH;
+                    self.viewport.shift(ENDLESS_GROWTH, 0);
+                }
+                Edge::Left => {
+                    cursor.column += ENDLESS_GROWTH;
+                    self.viewport.shift(0, ENDLESS_GROWTH);
+                }
+                Edge::Bottom | Edge::Right => (),
+            }
+
+            self.frame.clear();
+        }
+
+        self.engine.set_cursor(*cursor);
+    }
+
+    /// Pause the game, stopping the timer until it's unpaused. Returns
+    /// false if the game was interrupted instead.
+    fn pause_game(
+        &mut self,
+        raw_stdout: &mut RawOutput,
+        keys: &Receiver<Key>,
+        reason: &str,
+    ) -> bool {
+        let message = format!("{} Press 'p' to unpause.", reason);
+
+        // the board is hidden while the timer is stopped, so it can't be
+        // studied for free. it's all drawn again once play carries on.
+        let display_paused = |game: &mut Self, raw_stdout: &mut RawOutput| {
+            game.update_viewport(game.engine.cursor());
+            game.frame.clear();
+            write!(raw_stdout, "{}", terminal::clear::All).unwrap();
+            game.write_text_below(raw_stdout, message.clone(), 1);
+            raw_stdout.flush().unwrap();
+        };
+
+        display_paused(self, raw_stdout);
+        self.engine.apply(Action::Pause);
+
+        let is_unpaused = loop {
+            match next_input(keys) {
+                Some(Input::Key(Key::Char('p'))) => break true,
+                Some(Input::Key(terminal::INTERRUPT)) | None => break false,
+
+                // the message moves with the field when the terminal is resized.
+                Some(Input::Tick) if self.is_resized() => display_paused(self, raw_stdout),
+                _ => (),
+            }
+        };
+
+        self.engine.apply(Action::Pause);
+
+        is_unpaused
+    }
+
+    /// Keep the game up to date while no key is being pressed: the
+    /// guest's moves, the timer, the size of the terminal and the rival's
+    /// progress. Returns whether the guest's moves have ended the round.
+    fn tick(&mut self, raw_stdout: &mut RawOutput, cursor: Position) -> bool {
+        // the guest of a co-op game can end it with their moves.
+        if self.make_partner_moves() {
+            if self.engine.field().get_state() != MineFieldState::InProgress {
+                return true;
+            }
+
+            self.draw(raw_stdout, cursor);
+        }
+
+        // a resized terminal needs everything drawn again.
+        if self.is_resized() {
+            self.frame.clear();
+            self.draw(raw_stdout, cursor);
+        } else {
+            self.display_timer(raw_stdout);
+        }
+
+        // a rival's progress comes in whenever they make a move.
+        if self.rival.as_mut().is_some_and(Rival::poll) {
+            self.display_side_text(raw_stdout);
+        }
+
+        false
+    }
+
+    /// Ask for the name of a tile to jump to, like `D7`, on a line
+    /// below the field.
+    ///
+    /// Returns `None` if the player thinks better of it.
+    fn read_coordinate(
+        &mut self,
+        raw_stdout: &mut RawOutput,
+        keys: &Receiver<Key>,
+    ) -> Option<String> {
+        let prompt = |name: &str| {
+            format!(
+                "Go to tile: {:_<width$} (esc to cancel)",
+                name,
+                width = MAX_COORDINATE
+            )
+        };
+
+        let mut name = String::new();
+
+        // the prompt is wiped out with everything else below the field
+        // when it's drawn again, so it doesn't need blanking here.
+        loop {
+            self.write_text_below(raw_stdout, prompt(&name), 1);
+
+            let key = match next_input(keys) {
+                // the clock doesn't stop for typing.
+                Some(Input::Tick) => {
+                    self.display_timer(raw_stdout);
+                    continue;
+                }
+                Some(Input::Key(key)) => key,
+                None => Key::Esc,
+            };
+
+            match key {
+                Key::Char('\n') => break,
+                Key::Char(c) if c.is_ascii_alphanumeric() && name.len() < MAX_COORDINATE => {
+                    name.push(c.to_ascii_uppercase())
+                }
+                Key::Backspace => {
+                    name.pop();
+                }
+                Key::Esc | terminal::INTERRUPT => return None,
+                _ => (),
+            }
+        }
+
+        Some(name).filter(|name| !name.is_empty())
+    }
+
+    /// Ask for the player's initials on a line below the field.
+    ///
+    /// Returns `None` if they'd rather not give any.
+    fn read_initials(
+        &self,
+        raw_stdout: &mut RawOutput,
+        keys: &Receiver<Key>,
+        line: u16,
+    ) -> Option<String> {
+        let prompt = |initials: &str| {
+            format!(
+                "Initials for the leaderboard: {:_<width$} (esc to skip)",
+                initials,
+                width = MAX_INITIALS
+            )
+        };
+
+        let mut initials = String::new();
+
+        loop {
+            self.write_text_below(raw_stdout, prompt(&initials), line);
+
+            match keys.recv().unwrap_or(Key::Esc) {
+                Key::Char('\n') if !initials.is_empty() => break,
+                Key::Char(c) if c.is_ascii_alphanumeric() && initials.len() < MAX_INITIALS => {
+                    initials.push(c.to_ascii_uppercase())
+                }
+                Key::Backspace => {
+                    initials.pop();
+                }
+                Key::Esc | terminal::INTERRUPT => {
+                    initials.clear();
+                    break;
+                }
+                _ => (),
+            }
+        }
+
+        // the prompt is always the same length, so blank it out the same way.
+        let blank = " ".repeat(prompt("").len());
+        self.write_text_below(raw_stdout, blank, line);
+
+        Some(initials).filter(|initials| !initials.is_empty())
+    }
+
+    /// Play rounds of the game with the interface until the player is done.
+    pub fn play(&mut self) {
+        self.play_with(&spawn_key_reader());
+    }
+
+    /// Play a single round as a player's turn in a versus game, with keys
+    /// read for the whole game. Returns how the turn went, or `None` if
+    /// the game was interrupted.
+    pub fn play_turn(&mut self, keys: &Receiver<Key>, player: &str) -> Option<Outcome> {
+        self.turn = Some(String::from(player));
+        self.play_with(keys);
+        self.outcome.take()
+    }
+
+    /// Play a single round as a race against another player on the same
+    /// board, with how they're getting on shown beside the field. Returns
+    /// how the race went for both players, or `None` if the game was
+    /// interrupted. The other player's is `None` if they never dug.
+    pub fn play_race(&mut self, rival: Rival) -> Option<(Outcome, Option<Outcome>)> {
+        self.rival = Some(rival);
+        self.play();
+
+        let outcome = self.outcome.take()?;
+        let rival_outcome = self
+            .rival
+            .as_ref()
+            .and_then(Rival::progress)
+            .map(|progress| progress.outcome());
+
+        Some((outcome, rival_outcome))
+    }
+
+    /// Check if only one round is played, for a versus turn or a race.
+    fn is_single_round(&self) -> bool {
+        self.turn.is_some() || self.rival.is_some()
+    }
+
+    /// Check if a finished round can be looked back over. Boards that
+    /// lead on to others, or have grown, are left out.
+    fn is_reviewable(&self) -> bool {
+        !self.is_single_round()
+            && self.gauntlet.is_none()
+            && !self.mode.carries_on()
+            && self.mode != Mode::Endless
+    }
+
+    /// Let the other player of a race know how far this one has got.
+    fn send_progress(&mut self, status: Status, time_taken: Duration) {
+        let (revealed, total) = self.engine.field().progress();
+
+        if let Some(rival) = &mut self.rival {
+            // a lost connection shows up when their messages stop.
+            let progress = Progress {
+                revealed,
+                total,
+                time_taken,
+                status,
+            };
+            rival.send(Message::Progress(progress)).ok();
+        }
+    }
+
+    /// Wait for the other player of a race to finish, showing how they
+    /// get on. Returns false if the game was interrupted instead.
+    fn wait_for_rival(&mut self, raw_stdout: &mut RawOutput, keys: &Receiver<Key>) -> bool {
+        self.write_text_below(
+            raw_stdout,
+            String::from("Waiting for your rival, or press 'q' to stop waiting"),
+            3,
+        );
+
+        while self.rival.as_ref().is_some_and(|rival| !rival.is_done()) {
+            match keys.recv_timeout(TICK_RATE) {
+                Ok(Key::Char('q')) => break,
+                Ok(terminal::INTERRUPT) => return false,
+                Err(RecvTimeoutError::Disconnected) => break,
+                _ => (),
+            }
+
+            if self.rival.as_mut().is_some_and(Rival::poll) {
+                self.display_side_text(raw_stdout);
+            }
+        }
+
+        self.write_text_below(raw_stdout, terminal::clear::CurrentLine.to_string(), 3);
+        true
+    }
+
+    fn play_with(&mut self, keys: &Receiver<Key>) {
+        let mut raw_stdout = Guard::new().unwrap();
+
+        // focus is only reported when something is listening for it.
+        if self.pause_on_focus_loss {
+            Terminal::report_focus(&mut raw_stdout).unwrap();
+        }
+
+        // set up the first open field before displaying.
+        let mut opening = match self.layout {
+            Some(_) => None,
+            None => self
+                .engine
+                .clear_first_opening_with(self.first_opening, &mut self.rng),
+        };
+        let mut is_retry = false;
+
+        loop {
+            let start = match self.resumed {
+                Some((_, cursor)) => cursor,
+                None => opening.unwrap_or(Position::new(0, 0)),
+            };
+
+            match self.play_round(&mut raw_stdout, keys, start, is_retry) {
+                NextRound::Retry => {
+                    // cover the board back up and open the same first tile.
+                    if let Some(layout) = &self.layout {
+                        self.engine.set_field(layout.clone());
+                    } else {
+                        self.engine.cover_all();
+                    }
+
+                    if let Some(opening) = opening {
+                        self.engine.set_cursor(opening);
+                        self.engine.apply(Action::Dig);
+                    }
+
+                    is_retry = true;
+                }
+
+                NextRound::NewBoard => {
+                    // a campaign moves on to its next puzzle, which has
+                    // its own starting reveal rather than an opening.
+                    if let Some(mut campaign) = self.campaign.take() {
+                        campaign.advance();
+                        self.start_campaign(campaign);
+                        opening = None;
+                        is_retry = false;
+                        continue;
+                    }
+
+                    // a new gauntlet or time attack starts over from the first board.
+                    self.boards_cleared = 0;
+
+                    // an endless field goes back to the size it started at.
+                    if self.gauntlet.is_some() {
+                        self.start_gauntlet();
+                    } else {
+                        let origin = self.endless_origin.as_ref().unwrap_or(self.engine.field());
+                        self.engine.set_field(origin.regenerate_with(&mut self.rng));
+                        self.layout = None;
+                    }
+
+                    opening = self
+                        .engine
+                        .clear_first_opening_with(self.first_opening, &mut self.rng);
+                    is_retry = false;
+                }
+
+                NextRound::NextStage => {
+                    // a time attack goes on to a fresh board of the same kind.
+                    match &self.gauntlet {
+                        Some(gauntlet) => {
+                            if let Some(field) = gauntlet.field(self.engine.field(), &mut self.rng)
+                            {
+                                self.engine.set_field(field);
+                            }
+                        }
+                        None => {
+                            self.engine
+                                .set_field(self.engine.field().regenerate_with(&mut self.rng));
+                            self.layout = None;
+                        }
+                    }
+
+                    opening = self
+                        .engine
+                        .clear_first_opening_with(self.first_opening, &mut self.rng);
+                    is_retry = false;
+                }
+
+                NextRound::Finish => break,
+            }
+        }
+
+        // a failure to save the tips or stats shouldn't spoil the game.
+        if let Some(tips) = &self.tips {
+            tips.save().ok();
+        }
+
+        if let Some(stats) = &self.stats {
+            stats.save().ok();
+        }
+
+        // dropping the guard clears the screen and gives the terminal back.
+    }
+
+    /// Play a single round on the current field, starting at the given tile.
+    ///
+    /// A retried board has already been seen, and a casual game can take
+    /// back its mistakes, so neither can set a best time.
+    fn play_round(
+        &mut self,
+        raw_stdout: &mut RawOutput,
+        keys: &Receiver<Key>,
+        start: Position,
+        is_retry: bool,
+    ) -> NextRound {
+        // it would be ideal to have this be more detached from the
+        // user interface to some degree, but it should be fine.
+        let mut cursor = start;
+        let mut check_for_mine = false;
+        self.engine.set_cursor(cursor);
+        self.frame.clear();
+        let mut motion_keys = MotionKeys::new();
+
+        // the opening that's cleared for the player counts as their first
+        // click, but a loaded board's reveal wasn't the player's doing.
+        let mut clicks = match self.engine.field().get_tile_state(start) {
+            Ok(TileState::Visible) if self.layout.is_none() => 1,
+            _ => 0,
+        };
+        let mut flags_placed = 0;
+        let mut is_out_of_time = false;
+
+        // gauntlet and time attack boards aren't timed on their own, and
+        // loaded boards have been seen before, so none can set a best time.
+        let is_ranked = !is_retry
+            && !self.is_assisted()
+            && !self.is_seeded
+            && self.gauntlet.is_none()
+            && self.mode.sets_best_times()
+            && self.layout.is_none()
+            && self.partner.is_none();
+        self.engine.set_lives(self.lives.unwrap_or(0));
+        self.engine.clear_history();
+
+        self.log.clear();
+        self.moves.clear();
+
+        // later stages of a gauntlet or time attack carry on with the same timer.
+        let is_later_stage = match &self.gauntlet {
+            Some(gauntlet) => gauntlet.stage() > 0,
+            None => self.mode.carries_on() && self.boards_cleared > 0,
+        };
+
+        if let Some((elapsed, _)) = self.resumed.take() {
+            self.engine.restart_timer_from(elapsed);
+        } else if !is_later_stage {
+            self.engine.restart_timer();
+        }
+
+        if let Some(hooks) = &self.hooks {
+            hooks.run(Event::Start, self.engine.field(), None);
+        }
+
+        if self.auto_flag {
+            self.engine.apply(Action::AutoFlag);
+        }
+
+        // the opening alone can get close enough to an edge to grow it.
+        if self.mode == Mode::Endless {
+            self.expand_field(&mut cursor);
+        }
+
+        print!("{}", terminal::cursor::Show);
+        self.draw(raw_stdout, cursor);
+        let mut last_key = Instant::now();
+
+        loop {
+            // a game against the clock ends the moment the time runs out.
+            if self.mode.is_out_of_time(self.engine.elapsed()) {
+                is_out_of_time = true;
+                break;
+            }
+
+            // keep the timer ticking over while waiting for a key.
+            let key = match next_input(keys) {
+                Some(Input::Key(Key::FocusLost)) if self.pause_on_focus_loss => {
+                    if !self.pause_game(raw_stdout, keys, "Paused while you were away.") {
+                        return self.leave_round();
+                    }
+
+                    last_key = Instant::now();
+                    self.draw(raw_stdout, cursor);
+                    continue;
+                }
+
+                // switching windows isn't the player doing anything.
+                Some(Input::Key(Key::FocusGained | Key::FocusLost)) => continue,
+                Some(Input::Key(key)) => {
+                    last_key = Instant::now();
+                    key
+                }
+                Some(Input::Tick) => {
+                    if is_idle(self.auto_pause, last_key.elapsed()) {
+                        if !self.pause_game(
+                            raw_stdout,
+                            keys,
+                            "Paused after a while without a move.",
+                        ) {
+                            return self.leave_round();
+                        }
+
+                        last_key = Instant::now();
+                        self.draw(raw_stdout, cursor);
+                        continue;
+                    }
+
+                    // an unfinished game is saved every so often, in case
+                    // the terminal goes away without a word.
+                    if self.is_unsaved && self.last_saved.elapsed() >= AUTOSAVE_INTERVAL {
+                        self.save_round();
+                    }
+
+                    if self.tick(raw_stdout, cursor) {
+                        break;
+                    }
+
+                    continue;
+                }
+                None => break,
+            };
+
+            // hints and messages only last until the next key.
+            self.hint = None;
+            self.message = None;
+
+            // once a control has been used, its tip isn't needed anymore.
+            if let (Some(tips), Some(tip)) = (&mut self.tips, tip_for_key(key)) {
+                tips.mark_used(tip);
+            }
+
+            // cursor controls, with vim-style counts and jumps.
+            if let Some(motion) = motion_keys.feed(key) {
+                self.engine.apply(Action::Move(motion));
+                cursor = self.engine.cursor();
+            } else if motion_keys.is_pending() {
+                // wait for the rest of the count or motion.
+                continue;
+            } else {
+                let is_failed = self.engine.field().get_state() == MineFieldState::Failed;
+                let is_move = matches!(key, Key::Char('f' | ' ' | 'd'));
+
+                match key {
+                    // a casual game waits for a fatal dig to be undone.
+                    _ if is_move && is_failed => {
+                        self.play_cue(Cue::Invalid);
+                        self.message = Some(String::from(
+                            "Press 'u' to undo that dig, or 'q' to give up",
+                        ))
+                    }
+
+                    // tile controls: dig, flag or chord under the cursor.
+                    Key::Char(letter) if is_move => {
+                        let action = match letter {
+                            'f' => Action::Flag,
+                            'd' => Action::Chord,
+                            _ => Action::Dig,
+                        };
+
+                        let events = self.engine.apply(action);
+                        let played = PlayedMove::new(action, cursor, &events, self.engine.field());
+                        self.moves.record(played);
+
+                        for event in events {
+                            self.log.record(action, cursor, &event);
+
+                            match event {
+                                engine::Event::TilesRevealed(waves) => {
+                                    self.animate_flood(raw_stdout, cursor, &waves)
+                                }
+                                engine::Event::FlagToggled {
+                                    is_flagged: true, ..
+                                } => flags_placed += 1,
+                                engine::Event::NoFlagsLeft => {
+                                    self.play_cue(Cue::Invalid);
+                                    self.message = Some(format!(
+                                        "No flags left: there are only {} mines",
+                                        self.engine.field().mines()
+                                    ));
+                                }
+                                engine::Event::FlagsDisabled => {
+                                    self.play_cue(Cue::Invalid);
+                                    self.message = Some(String::from(
+                                        "No flags in a flagless game: chord off the numbers",
+                                    ));
+                                }
+
+                                engine::Event::LifeLost { lives_left } => {
+                                    self.lose_life(lives_left)
+                                }
+
+                                // a key that does nothing, like digging an open tile.
+                                engine::Event::Ignored => self.play_cue(Cue::Invalid),
+                                _ => (),
+                            }
+                        }
+
+                        check_for_mine = action != Action::Flag;
+                    }
+
+                    // leaves a note on a hidden tile, or moves it on to the next.
+                    Key::Char('m') => {
+                        for event in self.engine.apply(Action::Note) {
+                            self.log.record(Action::Note, cursor, &event);
+
+                            if event == engine::Event::Ignored {
+                                self.play_cue(Cue::Invalid);
+                            }
+                        }
+                    }
+
+                    // takes back or remakes a move in a casual game.
+                    Key::Char('u') if self.engine.can_undo() => {
+                        for event in self.engine.apply(Action::Undo) {
+                            self.log.record(Action::Undo, cursor, &event);
+
+                            if event == engine::Event::MoveUndone {
+                                self.moves.undo();
+                            }
+                        }
+                    }
+
+                    Key::Ctrl('r') if self.engine.can_undo() => {
+                        for event in self.engine.apply(Action::Redo) {
+                            self.log.record(Action::Redo, cursor, &event);
+
+                            match event {
+                                engine::Event::MoveRedone => self.moves.redo(),
+                                engine::Event::LifeLost { lives_left } => {
+                                    self.lose_life(lives_left)
+                                }
+                                _ => (),
+                            }
+                        }
+
+                        check_for_mine = true
+                    }
+
+                    // shows or hides the mine counting diagnostics.
+                    Key::Char('c') => self.show_mine_count = !self.show_mine_count,
+
+                    // shows or hides the chance of each tile being a mine.
+                    Key::Char('o') => self.show_overlay = !self.show_overlay,
+
+                    // jumps the cursor to a tile named like a spreadsheet cell.
+                    Key::Char(':') => {
+                        let (height, width) =
+                            (self.engine.field().height(), self.engine.field().width());
+
+                        if let Some(name) = self.read_coordinate(raw_stdout, keys) {
+                            match Position::from_a1(&name) {
+                                Some(position) if position.is_within(height, width) => {
+                                    self.engine.set_cursor(position);
+                                    cursor = position;
+                                }
+                                _ => {
+                                    self.play_cue(Cue::Invalid);
+                                    self.message =
+                                        Some(format!("There's no tile {} on this field", name));
+                                }
+                            }
+                        }
+                    }
+
+                    // shows or hides the log of what's happened so far.
+                    Key::Char('e') => self.show_log = !self.show_log,
+
+                    // asks the solver for a nudge.
+                    Key::Char('?') => self.find_hint(cursor),
+
+                    // miscellaneous controls
+                    Key::Char('p') => {
+                        if !self.pause_game(raw_stdout, keys, "Paused!") {
+                            return self.leave_round();
+                        }
+                    }
+                    Key::Char('q') => break,
+
+                    // an interrupt leaves straight away, without a word.
+                    terminal::INTERRUPT => return self.leave_round(),
+                    _ => continue,
+                }
+
+                if is_move && !is_failed {
+                    clicks += 1;
+                }
+            }
+
+            // if a space has been cleared, there may be a mine.
+            if check_for_mine {
+                // an endless field grows before it can ever be cleared.
+                if self.mode == Mode::Endless && self.engine.field().dug_mines() == 0 {
+                    self.expand_field(&mut cursor);
+                }
+
+                self.send_progress(Status::Playing, self.engine.elapsed());
+
+                if self.engine.field().get_state() == MineFieldState::Failed {
+                    self.play_cue(Cue::Mine);
+                }
+
+                // check if the game has been finished. casual games
+                // carry on after a mine, so the dig can be undone.
+                match self.engine.field().get_state() {
+                    MineFieldState::InProgress => (),
+
+                    MineFieldState::Failed if self.engine.can_undo() => {
+                        self.message = Some(String::from(
+                            "Boom! Press 'u' to undo that dig, or 'q' to give up",
+                        ))
+                    }
+
+                    // flagging the last mines after losing a life can
+                    // finish off the field too.
+                    MineFieldState::Failed | MineFieldState::Cleared => break,
+                }
+
+                check_for_mine = false;
+            }
+
+            // the guest's moves come after this one, having arrived later.
+            if self.make_partner_moves()
+                && self.engine.field().get_state() != MineFieldState::InProgress
+            {
+                break;
+            }
+
+            // redraw the field after every key event.
+            self.draw(raw_stdout, cursor);
+            self.is_unsaved = true;
+        }
+
+        let is_saved = self.put_away_round(is_out_of_time);
+
+        let time_taken = self.engine.elapsed();
+
+        // once only mines are left hidden, they're flagged to finish the field.
+        let is_autocompleted = self.engine.apply(Action::Autocomplete) != [engine::Event::Ignored];
+
+        // the pace is compared before a new best time replaces the old one.
+        let mut time_text = match self.pace_text(time_taken) {
+            Some(pace) if self.engine.field().get_state() == MineFieldState::Cleared => {
+                format!("You took {} seconds ({})", time_taken.as_secs(), pace)
+            }
+            _ => format!("You took {} seconds", time_taken.as_secs()),
+        };
+
+        if self.engine.field().get_state() == MineFieldState::Cleared {
+            let efficiency =
+                describe_efficiency(self.engine.field().three_bv(), clicks, time_taken);
+            time_text.push_str(&format!(": {}", efficiency));
+        }
+
+        // only a cleared field can set a new best time.
+        let is_cleared = self.engine.field().get_state() == MineFieldState::Cleared;
+        let mut is_new_best = false;
+
+        // a versus turn or race is measured before the rest of the board
+        // is shown. leaving a race early counts as losing it.
+        if self.is_single_round() {
+            self.outcome = Some(Outcome {
+                is_cleared,
+                time_taken,
+                progress: self.progress_fraction(),
+            });
+
+            match is_cleared {
+                true => self.send_progress(Status::Won, time_taken),
+                false => self.send_progress(Status::Lost, time_taken),
+            }
+        }
+
+        // ranked wins in a row make a streak, which any other ranked round ends.
+        let mut is_milestone = false;
+
+        if is_ranked && is_cleared {
+            self.streak += 1;
+            is_milestone = STREAK_MILESTONES.contains(&self.streak);
+        } else if is_ranked {
+            self.streak = 0;
+        }
+
+        // running out of time in a countdown is as good as losing.
+        let is_finished =
+            self.engine.field().get_state() != MineFieldState::InProgress || is_out_of_time;
+
+        if let (Some(stats), true, true) = (&mut self.stats, is_finished, is_ranked) {
+            stats.record(
+                &scores::board_key(self.engine.field()),
+                is_cleared,
+                time_taken,
+            );
+        }
+
+        // a campaign's puzzle is only solved if its objective was met.
+        let mut campaign_text = None;
+
+        if let (Some(campaign), true) = (&mut self.campaign, is_cleared) {
+            let objective = campaign.puzzle().objective;
+
+            campaign_text = Some(if objective.is_met(flags_placed, clicks) {
+                campaign.solve();
+
+                match campaign.is_complete() {
+                    true => String::from("Puzzle solved, and that's every one of them!"),
+                    false => String::from("Puzzle solved!"),
+                }
+            } else {
+                format!("Cleared, but the goal was to {}", objective.describe())
+            });
+        }
+
+        // only the first round of a daily challenge is on the day's board.
+        let mut daily_text = None;
+
+        if let Some(day) = self.daily.take() {
+            let is_assisted = self.is_assisted();
+
+            if let (Some(stats), true, false) = (&mut self.stats, is_finished, is_assisted) {
+                daily_text = match stats.record_daily(
+                    &scores::board_key(self.engine.field()),
+                    day,
+                    is_cleared,
+                ) {
+                    Some(0) => Some(String::from("Daily challenge lost, come back tomorrow")),
+                    Some(1) => Some(String::from("Daily challenge won!")),
+                    Some(streak) => Some(format!("Daily challenge won, {} days in a row!", streak)),
+                    None => Some(String::from("Today's challenge has already been counted")),
+                };
+            }
+        }
+
+        if let (Some(scores), true, true) = (&mut self.scores, is_cleared, is_ranked) {
+            is_new_best = scores.record(&scores::board_key(self.engine.field()), time_taken);
+
+            if is_new_best {
+                scores.save().ok();
+            }
+        }
+
+        // a gauntlet carries on until a board is lost or they've all been
+        // cleared, and only a finished run has a time worth keeping.
+        let mut is_next_stage = false;
+        let is_assisted = self.is_assisted();
+
+        let time_text = match &mut self.gauntlet {
+            Some(gauntlet) => {
+                if is_cleared {
+                    gauntlet.clear_stage(time_taken);
+                }
+
+                is_next_stage = is_cleared && !gauntlet.is_finished();
+
+                match &mut self.scores {
+                    Some(scores) if gauntlet.is_finished() && !is_assisted => {
+                        is_new_best =
+                            scores.record(&gauntlet::board_key(self.engine.field()), time_taken);
+
+                        if is_new_best {
+                            scores.save().ok();
+                        }
+                    }
+                    _ => (),
+                }
+
+                match (gauntlet.is_finished(), is_new_best) {
+                    (true, true) => format!(
+                        "Gauntlet cleared in {} seconds, a new best!",
+                        time_taken.as_secs()
+                    ),
+                    (true, false) => {
+                        format!("Gauntlet cleared in {} seconds", time_taken.as_secs())
+                    }
+                    (false, _) => format!(
+                        "Gauntlet over on {} after {} seconds",
+                        gauntlet.stage_name(),
+                        time_taken.as_secs()
+                    ),
+                }
+            }
+            None => time_text,
+        };
+
+        // a time attack is scored on the boards cleared, and carries on
+        // to the next one while there's time left.
+        if self.mode.carries_on() && is_cleared {
+            self.boards_cleared += 1;
+            is_next_stage = true;
+        }
+
+        let time_text = match self.mode {
+            Mode::TimeAttack(_) if is_out_of_time => format!(
+                "Time's up! You cleared {}",
+                describe_boards(self.boards_cleared)
+            ),
+            Mode::TimeAttack(_) => format!(
+                "Run over after clearing {} in {} seconds",
+                describe_boards(self.boards_cleared),
+                time_taken.as_secs()
+            ),
+            Mode::Countdown(limit) if is_out_of_time => {
+                format!("Out of time after {} seconds", limit.as_secs())
+            }
+            Mode::Endless => format!(
+                "You cleared {} tiles in {} seconds",
+                self.engine.field().progress().0,
+                time_taken.as_secs()
+            ),
+            _ => time_text,
+        };
+
+        match self.engine.field().get_state() {
+            MineFieldState::Cleared => self.play_cue(Cue::Win),
+            MineFieldState::Failed => self.play_cue(Cue::Loss),
+            MineFieldState::InProgress if is_out_of_time => self.play_cue(Cue::Loss),
+            MineFieldState::InProgress => (),
+        }
+
+        if let Some(hooks) = &self.hooks {
+            match self.engine.field().get_state() {
+                MineFieldState::Cleared => {
+                    hooks.run(Event::Win, self.engine.field(), Some(time_taken))
+                }
+                MineFieldState::Failed => {
+                    hooks.run(Event::Loss, self.engine.field(), Some(time_taken))
+                }
+                MineFieldState::InProgress => (),
+            }
+
+            if is_new_best {
+                hooks.run(Event::NewBest, self.engine.field(), Some(time_taken));
+            }
+        }
+
+        if is_next_stage {
+            return NextRound::NextStage;
+        }
+
+        // a review goes by the field as it was left, before the mines are shown.
+        let review = self
+            .is_reviewable()
+            .then(|| Review::new(self.engine.field(), self.moves.last()));
+
+        // a saved round's mines would give its board away once it's
+        // carried on with.
+        if !is_autocompleted && !is_saved {
+            self.engine.game_over();
+        }
+
+        self.redraw_field(raw_stdout, cursor);
+        self.display_side_text(raw_stdout);
+
+        // hide the cursor and wait for the player to decide what's next.
+        print!("{}", terminal::cursor::Hide);
+
+        if is_new_best || is_milestone {
+            self.celebrate(raw_stdout);
+        }
+
+        self.write_text_below(raw_stdout, time_text.clone(), 1);
+
+        let banner = match (is_new_best, is_milestone) {
+            (true, true) => Some(format!("New best time, and {} wins in a row!", self.streak)),
+            (true, false) => Some(String::from("New best time!")),
+            (false, true) => Some(format!("{} wins in a row!", self.streak)),
+            (false, false) => daily_text.or(campaign_text),
+        };
+
+        let banner = match &self.gauntlet {
+            Some(gauntlet) => Some(gauntlet.describe_splits()),
+            None => banner,
+        };
+
+        if let Some(banner) = &banner {
+            self.write_text_below(raw_stdout, banner.clone(), 2);
+        }
+
+        // winners can put their name on the session leaderboard.
+        if is_cleared && is_ranked && self.leaderboard.is_some() {
+            if let Some(initials) = self.read_initials(raw_stdout, keys, 3) {
+                let key = scores::board_key(self.engine.field());
+
+                if let Some(leaderboard) = &mut self.leaderboard {
+                    leaderboard.record(&key, &initials, time_taken);
+                }
+
+                self.display_side_text(raw_stdout);
+            }
+        }
+
+        // a race isn't over until both players are done.
+        if self.rival.is_some() && !self.wait_for_rival(raw_stdout, keys) {
+            self.outcome = None;
+            return NextRound::Finish;
+        }
+
+        // a gauntlet or time attack can only be started over, since its
+        // boards share a timer.
+        let (prompt, choices): (&str, &[Key]) = match self.gauntlet {
+            // a versus turn or race is a single round, so there's nothing to choose.
+            _ if self.is_single_round() => ("Press enter to carry on", &[Key::Char('\n')]),
+            Some(_) => (
+                "Press 'n' for a new run or 'q' to finish",
+                &[Key::Char('n'), Key::Char('q')],
+            ),
+            None if self.mode.carries_on() => (
+                "Press 'n' for a new run or 'q' to finish",
+                &[Key::Char('n'), Key::Char('q')],
+            ),
+            // an endless field has grown past where its opening was.
+            None if self.mode == Mode::Endless => (
+                "Press 'n' for a new board or 'q' to finish",
+                &[Key::Char('n'), Key::Char('q')],
+            ),
+            None if self.campaign.is_some() => (
+                "Press 'r' to retry, 'n' for the next puzzle, 'v' to review or 'q' to finish",
+                &[
+                    Key::Char('r'),
+                    Key::Char('n'),
+                    Key::Char('v'),
+                    Key::Char('q'),
+                ],
+            ),
+            None => (
+                "Press 'r' to retry, 'n' for a new board, 'v' to review or 'q' to finish",
+                &[
+                    Key::Char('r'),
+                    Key::Char('n'),
+                    Key::Char('v'),
+                    Key::Char('q'),
+                ],
+            ),
+        };
+
+        loop {
+            self.write_text_below(raw_stdout, String::from(prompt), 3);
+
+            let review = match wait_for_choice(keys, choices) {
+                Key::Char('v') => review.as_ref().unwrap(),
+                Key::Char('r') => return NextRound::Retry,
+                Key::Char('n') => return NextRound::NewBoard,
+                _ => return NextRound::Finish,
+            };
+
+            if !self.review_round(raw_stdout, keys, review, cursor) {
+                return NextRound::Finish;
+            }
+
+            // back to the end of the round, as it was before the review.
+            self.redraw_field(raw_stdout, cursor);
+            self.display_side_text(raw_stdout);
+            print!("{}", terminal::cursor::Hide);
+            self.write_text_below(raw_stdout, time_text.clone(), 1);
+
+            if let Some(banner) = &banner {
+                self.write_text_below(raw_stdout, banner.clone(), 2);
+            }
+        }
+    }
+
+    /// Look back over a finished round, with each tile coloured by what
+    /// the review makes of it and the one under the cursor described,
+    /// until 'v', 'q' or escape is pressed. Returns false if the game
+    /// was interrupted instead.
+    fn review_round(
+        &mut self,
+        raw_stdout: &mut RawOutput,
+        keys: &Receiver<Key>,
+        review: &Review,
+        start: Position,
+    ) -> bool {
+        let mut cursor = start;
+        let mut motion_keys = MotionKeys::new();
+        self.engine.set_cursor(cursor);
+        print!("{}", terminal::cursor::Show);
+
+        loop {
+            self.redraw_field(raw_stdout, cursor);
+            self.display_review(raw_stdout, review, cursor);
+            raw_stdout.flush().unwrap();
+
+            match keys.recv() {
+                Ok(terminal::INTERRUPT) | Err(_) => return false,
+                Ok(Key::Esc | Key::Char('v' | 'q')) => return true,
+                Ok(key) => {
+                    if let Some(motion) = motion_keys.feed(key) {
+                        self.engine.apply(Action::Move(motion));
+                        cursor = self.engine.cursor();
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::autosave;
+
+    #[test]
+    fn a_tick_goes_by_while_no_key_is_pressed() {
+        let (sender, receiver) = mpsc::channel();
+
+        sender.send(Key::Char('f')).unwrap();
+        assert_eq!(next_input(&receiver), Some(Input::Key(Key::Char('f'))));
+        assert_eq!(next_input(&receiver), Some(Input::Tick));
+
+        drop(sender);
+        assert_eq!(next_input(&receiver), None);
+    }
+
+    #[test]
+    fn a_round_quit_partway_through_is_saved_with_its_mines_hidden() {
+        let dir = std::env::temp_dir().join(format!("minesweeper-quit-{}", std::process::id()));
+        let field = MineField::from_layout("*..*").unwrap();
+        let mut game = Minesweeper::with_field(field);
+        fs::create_dir_all(&dir).unwrap();
+        game.enable_autosave(Autosave::in_dir(&dir, Vec::new()).unwrap());
+
+        game.engine.set_cursor(Position::new(0, 1));
+        game.engine.apply(Action::Dig);
+        assert!(game.put_away_round(false));
+
+        let (saved, _) = autosave::latest_in(&dir).unwrap().unwrap();
+        assert_eq!(saved.field.to_string_layout(), "*1.*\n");
+
+        // running out of time ends the round, so there's nothing to keep.
+        assert!(!game.put_away_round(true));
+        assert!(autosave::latest_in(&dir).unwrap().is_none());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn only_pauses_itself_once_idle_for_long_enough() {
+        let minute = Duration::from_secs(60);
+
+        assert!(!is_idle(None, Duration::from_secs(3600)));
+        assert!(!is_idle(Some(minute), Duration::from_secs(59)));
+        assert!(is_idle(Some(minute), minute));
     }
 }
@@ -0,0 +1,126 @@
+use std::time::Duration;
+
+use rand::Rng;
+
+use crate::difficulty::{self, Difficulty};
+use crate::minefield::MineField;
+
+/// The boards in a gauntlet, in the order they're played.
+const STAGES: [Difficulty; 3] = difficulty::CLASSIC;
+
+/// A run through every standard board back to back, on a single timer.
+///
+/// Losing any board ends the run, so only a run that clears all
+/// of them gets a time.
+#[derive(Debug, Default)]
+pub struct Gauntlet {
+    /// The time on the run's timer when each stage was cleared.
+    cleared_at: Vec<Duration>,
+}
+
+impl Gauntlet {
+    /// Start a new run from the first board.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The stage being played, counted from zero.
+    #[inline]
+    pub fn stage(&self) -> usize {
+        self.cleared_at.len()
+    }
+
+    /// The amount of stages in a run.
+    #[inline]
+    pub fn stages(&self) -> usize {
+        STAGES.len()
+    }
+
+    /// Check if every stage has been cleared.
+    #[inline]
+    pub fn is_finished(&self) -> bool {
+        self.stage() == STAGES.len()
+    }
+
+    /// Note that the current stage was cleared, at a time on the run's timer.
+    pub fn clear_stage(&mut self, time: Duration) {
+        if !self.is_finished() {
+            self.cleared_at.push(time);
+        }
+    }
+
+    /// Create the field for the current stage with a random number
+    /// generator, set up like another field, or `None` once the run
+    /// is finished.
+    pub fn field(&self, like: &MineField, rng: &mut impl Rng) -> Option<MineField> {
+        let mut field = MineField::for_difficulty_with(*STAGES.get(self.stage())?, rng).unwrap();
+
+        field.set_rules(like.rules());
+        field.set_topology(like.topology());
+        field.set_theme(like.theme());
+        field.set_skin(like.skin());
+
+        Some(field)
+    }
+
+    /// The time taken on each cleared stage on its own, with its name.
+    pub fn splits(&self) -> Vec<(&'static str, Duration)> {
+        let starts = std::iter::once(Duration::from_secs(0)).chain(self.cleared_at.clone());
+
+        STAGES
+            .iter()
+            .zip(self.cleared_at.iter().zip(starts))
+            .map(|(stage, (&end, start))| (stage.name().unwrap(), end - start))
+            .collect()
+    }
+
+    /// Describe the splits on one line, like "beginner 4.2s, intermediate 31.0s".
+    pub fn describe_splits(&self) -> String {
+        self.splits()
+            .iter()
+            .map(|(name, time)| format!("{} {:.1}s", name, time.as_secs_f64()))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// The name of the stage being played, or the last one once it's finished.
+    pub fn stage_name(&self) -> &'static str {
+        STAGES[self.stage().min(STAGES.len() - 1)].name().unwrap()
+    }
+}
+
+/// Work out the name that gauntlet times are recorded under,
+/// going by a field from the run.
+pub fn board_key(field: &MineField) -> String {
+    match field.topology().name() {
+        Some(name) => format!("gauntlet+{}", name),
+        None => String::from("gauntlet"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_are_the_time_on_each_stage() {
+        let mut rng = rand::thread_rng();
+        let mut gauntlet = Gauntlet::new();
+        let field = gauntlet.field(&MineField::empty(1, 1), &mut rng).unwrap();
+        assert_eq!(field.mines(), 10);
+
+        gauntlet.clear_stage(Duration::from_millis(4_200));
+        gauntlet.clear_stage(Duration::from_millis(35_200));
+        assert_eq!(gauntlet.stage_name(), "expert");
+        assert!(!gauntlet.is_finished());
+
+        gauntlet.clear_stage(Duration::from_millis(130_200));
+        assert!(gauntlet.is_finished());
+        assert!(gauntlet.field(&MineField::empty(1, 1), &mut rng).is_none());
+
+        assert_eq!(
+            gauntlet.describe_splits(),
+            "beginner 4.2s, intermediate 31.0s, expert 95.0s"
+        );
+    }
+}
@@ -0,0 +1,189 @@
+use std::io::{self, BufRead, Write};
+
+use crate::minefield::{MineField, MineFieldState};
+use crate::position::Position;
+use crate::protocol::{self, GameState};
+use crate::tile::TileState;
+
+/// A move read from the input, on the tile at a position.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+enum Move {
+    Dig(Position),
+    Flag(Position),
+    Chord(Position),
+}
+
+impl Move {
+    /// Parse a move like `dig 3 4`, with the row before the column.
+    fn parse(line: &str) -> Result<Self, String> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+
+        let make: fn(Position) -> Move = match words[0] {
+            "dig" => Move::Dig,
+            "flag" => Move::Flag,
+            "chord" => Move::Chord,
+            word => return Err(format!("unknown move '{}'.", word)),
+        };
+
+        match words[1..] {
+            [row, column] => match (row.parse(), column.parse()) {
+                (Ok(row), Ok(column)) => Ok(make(Position::new(row, column))),
+                _ => Err(format!("'{} {}' isn't a row and column.", row, column)),
+            },
+            _ => Err(String::from("a move needs a row and a column.")),
+        }
+    }
+}
+
+/// The name of a field's state, as written in the output.
+fn state_name(state: MineFieldState) -> &'static str {
+    match state {
+        MineFieldState::InProgress => "in-progress",
+        MineFieldState::Cleared => "won",
+        MineFieldState::Failed => "lost",
+    }
+}
+
+/// Write out the state of the field as the player can see it.
+///
+/// The first line is the state of the game, and the second is the
+/// amount of mines and flags. Then there's a line per row, with `#`
+/// for hidden tiles, `F` for flags, `0` to `8` for revealed tiles,
+/// `*` for a dug mine and a space for a void tile. A blank line ends
+/// the board.
+fn write_board(field: &MineField, output: &mut impl Write) -> io::Result<()> {
+    writeln!(output, "state {}", state_name(field.get_state()))?;
+    writeln!(output, "mines {} flags {}", field.mines(), field.flags())?;
+
+    for row in 0..field.height() {
+        let line: String = (0..field.width())
+            .map(|column| {
+                let position = Position::new(row, column);
+                let tile = field.get_tile(position).unwrap();
+
+                match (tile.is_void, tile.state, tile.has_mine) {
+                    (true, _, _) => ' ',
+                    (false, TileState::Hidden, _) => '#',
+                    (false, TileState::Flagged, _) => 'F',
+                    (false, TileState::Visible, true) => '*',
+                    (false, TileState::Visible, false) => {
+                        // counts past 9 go on in letters, like on the screen.
+                        let count = field.count_mines_near(position).unwrap();
+                        std::char::from_digit(count as u32, 36).unwrap_or('+')
+                    }
+                }
+            })
+            .collect();
+
+        writeln!(output, "{}", line)?;
+    }
+
+    writeln!(output)?;
+    output.flush()
+}
+
+/// Write out the state of the field, as text or as JSON.
+fn write_state(field: &MineField, json: bool, output: &mut impl Write) -> io::Result<()> {
+    if !json {
+        return write_board(field, output);
+    }
+
+    let state = serde_json::to_string(&GameState::of(field)).unwrap();
+    writeln!(output, "{}", state)?;
+    output.flush()
+}
+
+/// Write out why a move couldn't be made, as text or as JSON.
+fn write_error(error: String, json: bool, output: &mut impl Write) -> io::Result<()> {
+    if json {
+        let error = serde_json::to_string(&protocol::Error { error }).unwrap();
+        writeln!(output, "{}", error)
+    } else {
+        writeln!(output, "error {}", error)
+    }
+}
+
+/// Play a game with moves read from the input, one per line, writing
+/// the board to the output at the start and after each move. With
+/// `json`, the board is written as a `GameState` instead.
+///
+/// Blank lines are skipped. A move that can't be read or made is
+/// answered with an error, and the game goes on. It
+/// ends when the game is won or lost, or the input runs out.
+pub fn play(
+    mut field: MineField,
+    json: bool,
+    input: impl BufRead,
+    mut output: impl Write,
+) -> io::Result<()> {
+    write_state(&field, json, &mut output)?;
+
+    for line in input.lines() {
+        let line = line?;
+
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let result = Move::parse(&line).and_then(|next| {
+            match next {
+                Move::Dig(position) => field.flood_empty_tiles(position),
+                Move::Flag(position) => field.toggle_flag(position),
+                Move::Chord(position) => field.do_chord(position).map(|_| ()),
+            }
+            .map_err(|e| e.to_string())
+        });
+
+        if let Err(e) = result {
+            write_error(e, json, &mut output)?;
+            continue;
+        }
+
+        let state = field.get_state();
+
+        if state == MineFieldState::Failed {
+            field.game_over();
+        }
+
+        write_state(&field, json, &mut output)?;
+
+        if state != MineFieldState::InProgress {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn moves_are_answered_with_the_board() {
+        let field = MineField::from_layout("*..\n...\n..*").unwrap();
+        let input = "flag 0 0\nprod 1 1\ndig 9 9\n\ndig 0 2\ndig 2 0\n";
+        let mut output = Vec::new();
+
+        play(field, false, input.as_bytes(), &mut output).unwrap();
+
+        let text = String::from_utf8(output).unwrap();
+        let boards: Vec<&str> = text.split("\n\n").collect();
+
+        assert_eq!(
+            boards[0],
+            "state in-progress\nmines 2 flags 0\n###\n###\n###"
+        );
+        assert_eq!(
+            boards[1],
+            "state in-progress\nmines 2 flags 1\nF##\n###\n###"
+        );
+        assert_eq!(
+            boards[2],
+            "error unknown move 'prod'.\n\
+             error Row 9, column 9 isn't on the field.\n\
+             state in-progress\nmines 2 flags 1\nF10\n#21\n###"
+        );
+        assert_eq!(boards[3], "state won\nmines 2 flags 1\nF10\n121\n01#");
+    }
+}
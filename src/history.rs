@@ -0,0 +1,136 @@
+use crate::minefield::{MineField, TileChange};
+
+/// The moves made on a field, so they can be undone and redone.
+///
+/// Each move only keeps the tiles it changed, so a big flood
+/// costs more to remember than a single flag.
+#[derive(Debug, Default)]
+pub struct History {
+    undo: Vec<Step>,
+    redo: Vec<Step>,
+}
+
+/// A move that was made, as the tiles it changed and the lives the
+/// player had before and after it.
+#[derive(Debug)]
+struct Step {
+    changes: Vec<TileChange>,
+    lives_left: (usize, usize),
+}
+
+impl Step {
+    /// The same step, going the other way.
+    fn reversed(self) -> Self {
+        let (before, after) = self.lives_left;
+
+        Self {
+            changes: self.changes.into_iter().map(TileChange::reversed).collect(),
+            lives_left: (after, before),
+        }
+    }
+}
+
+impl History {
+    /// Create an empty history.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Forget every move, ready for a new round.
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+
+    /// Remember a move by comparing a copy of the field from before it
+    /// with the field as it is now, along with the lives left before and
+    /// after it. Moves that changed nothing are skipped.
+    pub fn record(&mut self, before: &MineField, field: &MineField, lives_left: (usize, usize)) {
+        let changes = before.diff(field);
+
+        if !changes.is_empty() {
+            self.undo.push(Step {
+                changes,
+                lives_left,
+            });
+            self.redo.clear();
+        }
+    }
+
+    /// Take back the last move, along with any lives it spent, returning
+    /// whether there was one.
+    pub fn undo(&mut self, field: &mut MineField, lives_left: &mut usize) -> bool {
+        Self::replay(&mut self.undo, &mut self.redo, field, lives_left)
+    }
+
+    /// Make the last undone move again, returning whether there was one.
+    pub fn redo(&mut self, field: &mut MineField, lives_left: &mut usize) -> bool {
+        Self::replay(&mut self.redo, &mut self.undo, field, lives_left)
+    }
+
+    /// Turn back the latest move on one stack, and keep it on the other
+    /// so it can be turned back again.
+    fn replay(
+        from: &mut Vec<Step>,
+        to: &mut Vec<Step>,
+        field: &mut MineField,
+        lives_left: &mut usize,
+    ) -> bool {
+        let step = match from.pop() {
+            Some(step) => step.reversed(),
+            None => return false,
+        };
+
+        for change in step.changes.iter() {
+            field
+                .set_tile_state(change.position, change.after.state)
+                .unwrap();
+        }
+
+        *lives_left = step.lives_left.1;
+        to.push(step);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::position::Position;
+    use crate::tile::TileState;
+
+    #[test]
+    fn undo_and_redo_a_fatal_dig() {
+        let mut field = MineField::empty(3, 1);
+        field.get_tile_mut(Position::new(0, 2)).unwrap().has_mine = true;
+        let mut history = History::new();
+        let mut lives = 0;
+
+        let before = field.clone();
+        field.toggle_flag(Position::new(0, 0)).unwrap();
+        history.record(&before, &field, (0, 0));
+
+        let before = field.clone();
+        field.dig_tile(Position::new(0, 2)).unwrap();
+        history.record(&before, &field, (0, 0));
+
+        assert!(history.undo(&mut field, &mut lives));
+        assert_eq!(
+            field.get_tile_state(Position::new(0, 2)).unwrap(),
+            TileState::Hidden
+        );
+
+        assert!(history.undo(&mut field, &mut lives));
+        assert_eq!(field.flags(), 0);
+        assert!(!history.undo(&mut field, &mut lives));
+
+        assert!(history.redo(&mut field, &mut lives));
+        assert_eq!(field.flags(), 1);
+        assert!(history.redo(&mut field, &mut lives));
+        assert_eq!(
+            field.get_tile_state(Position::new(0, 2)).unwrap(),
+            TileState::Visible
+        );
+        assert!(!history.redo(&mut field, &mut lives));
+    }
+}
@@ -0,0 +1,122 @@
+use std::collections::BTreeMap;
+use std::env;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::Duration;
+
+use crate::config::Config;
+use crate::minefield::MineField;
+use crate::scores;
+
+/// Environment variables passed through to hooks from the game's own
+/// environment. Everything else is left out, so hooks run the same way
+/// however the game was started.
+const KEPT_VARIABLES: [&str; 4] = ["PATH", "HOME", "USER", "LANG"];
+
+/// Something that happens during a game, which a hook can run on.
+#[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Copy, Clone)]
+pub enum Event {
+    Start,
+    Win,
+    Loss,
+    NewBest,
+}
+
+impl Event {
+    const ALL: [Event; 4] = [Event::Start, Event::Win, Event::Loss, Event::NewBest];
+
+    /// The name of the event in the config file and in `MINESWEEPER_EVENT`.
+    fn name(self) -> &'static str {
+        match self {
+            Event::Start => "start",
+            Event::Win => "win",
+            Event::Loss => "loss",
+            Event::NewBest => "best",
+        }
+    }
+}
+
+/// Set up a shell command to run in the background, with only the
+/// `KEPT_VARIABLES` from the game's environment.
+pub fn shell(command: &str) -> Command {
+    let mut process = Command::new("sh");
+
+    process
+        .arg("-c")
+        .arg(command)
+        .env_clear()
+        .envs(
+            KEPT_VARIABLES
+                .iter()
+                .filter_map(|&name| Some((name, env::var_os(name)?))),
+        )
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+
+    process
+}
+
+/// Start a command without waiting for it, ignoring it if it can't start.
+pub fn spawn(process: &mut Command) {
+    // wait for it on another thread, so it doesn't hold up the game.
+    if let Ok(mut child) = process.spawn() {
+        thread::spawn(move || child.wait());
+    }
+}
+
+/// Shell commands to run on game events, set up in the config
+/// file with lines like `hook.win = notify-send "You won!"`.
+#[derive(Debug, Default)]
+pub struct Hooks {
+    commands: BTreeMap<Event, String>,
+}
+
+impl Hooks {
+    /// Find the hooks set up in the config.
+    pub fn from_config(config: &Config) -> Self {
+        let commands = Event::ALL
+            .iter()
+            .filter_map(|&event| {
+                let command = config.get(&format!("hook.{}", event.name()))?;
+                Some((event, String::from(command)))
+            })
+            .collect();
+
+        Self { commands }
+    }
+
+    /// Check if there are no hooks to run at all.
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    /// Start the hook for an event, if there is one, without waiting for it.
+    ///
+    /// The hook is told about the board through `MINESWEEPER_*`
+    /// variables, along with the time taken when there is one.
+    /// Hooks that fail to start are ignored, so they can't spoil a game.
+    pub fn run(&self, event: Event, field: &MineField, time: Option<Duration>) {
+        let command = match self.commands.get(&event) {
+            Some(command) => command,
+            None => return,
+        };
+
+        let mut process = shell(command);
+
+        process
+            .env("MINESWEEPER_EVENT", event.name())
+            .env("MINESWEEPER_BOARD", scores::board_key(field))
+            .env("MINESWEEPER_WIDTH", field.width().to_string())
+            .env("MINESWEEPER_HEIGHT", field.height().to_string())
+            .env("MINESWEEPER_MINES", field.mines().to_string());
+
+        if let Some(time) = time {
+            process
+                .env("MINESWEEPER_SECONDS", time.as_secs().to_string())
+                .env("MINESWEEPER_MILLIS", time.as_millis().to_string());
+        }
+
+        spawn(&mut process);
+    }
+}
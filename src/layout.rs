@@ -0,0 +1,109 @@
+/// Columns kept free to the right of the field for the side text.
+const SIDE_TEXT_WIDTH: usize = 30;
+
+/// Lines kept free below the field for messages.
+const LINES_BELOW: usize = 4;
+
+/// Lines kept at the very bottom of the terminal for the status bar.
+const STATUS_LINES: usize = 1;
+
+/// Where each part of the game goes on the screen, counted in
+/// characters from 1 like the cursor.
+///
+/// The field is in the top left, with the side text to its right and
+/// messages centred below them both. The status bar always takes the
+/// last line of the terminal, however big the field is.
+#[derive(Debug, Default, Eq, PartialEq, Copy, Clone)]
+pub struct Layout {
+    terminal: (u16, u16),
+    field_width: usize,
+    field_height: usize,
+    side_lines: usize,
+}
+
+impl Layout {
+    /// Lay out a field taking up some characters across and rows down,
+    /// with some lines of side text, on a terminal of a size.
+    pub fn new(
+        terminal: (u16, u16),
+        field_width: usize,
+        field_height: usize,
+        side_lines: usize,
+    ) -> Self {
+        Self {
+            terminal,
+            field_width,
+            field_height,
+            side_lines,
+        }
+    }
+
+    /// How many characters across and rows down the field can take up
+    /// on a terminal of a size, leaving room for everything else.
+    pub fn field_space(terminal: (u16, u16)) -> (usize, usize) {
+        let (width, height) = terminal;
+
+        (
+            (width as usize).saturating_sub(SIDE_TEXT_WIDTH),
+            (height as usize).saturating_sub(LINES_BELOW + STATUS_LINES),
+        )
+    }
+
+    /// The smallest terminal that fits a field taking up some characters
+    /// across and rows down, with everything else around it.
+    pub fn minimum_size(field_width: usize, field_height: usize) -> (u16, u16) {
+        (
+            (field_width + SIDE_TEXT_WIDTH) as u16,
+            (field_height + LINES_BELOW + STATUS_LINES) as u16,
+        )
+    }
+
+    /// Where a line of the side text starts.
+    pub fn beside(&self, line: u16) -> (u16, u16) {
+        (self.field_width as u16 + 2, line + 1)
+    }
+
+    /// Where some text of a width starts to be centred under the field,
+    /// on a line below it.
+    ///
+    /// The side text can run below very short fields, so it keeps clear
+    /// of that too.
+    pub fn below(&self, text_width: usize, lines_below: u16) -> (u16, u16) {
+        let x_pos = (self.field_width / 2).saturating_sub(text_width / 2).max(1);
+        let y_pos = self.field_height.max(self.side_lines) as u16 + lines_below + 1;
+
+        (x_pos as u16, y_pos)
+    }
+
+    /// The first line under the field, where everything below it starts.
+    pub fn under_field(&self) -> u16 {
+        self.field_height as u16 + 1
+    }
+
+    /// Where the status bar starts, and how wide it is.
+    pub fn status_bar(&self) -> ((u16, u16), usize) {
+        let (width, height) = self.terminal;
+        ((1, height.max(1)), width as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn everything_fits_around_the_field() {
+        let (width, height) = Layout::field_space((80, 24));
+        assert_eq!((width, height), (50, 19));
+        assert_eq!(Layout::minimum_size(width, height), (80, 24));
+
+        let layout = Layout::new((80, 24), 20, 10, 6);
+        assert_eq!(layout.beside(2), (22, 3));
+        assert_eq!(layout.below(8, 1), (6, 12));
+        assert_eq!(layout.status_bar(), ((1, 24), 80));
+
+        // long side text pushes messages down, clear of it.
+        let short = Layout::new((80, 24), 20, 3, 6);
+        assert_eq!(short.below(8, 1).1, 8);
+    }
+}
@@ -0,0 +1,116 @@
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+/// The most letters that can be entered as initials.
+pub const MAX_INITIALS: usize = 3;
+
+/// The most times kept for each board. Slower ones fall off the end.
+const MAX_ENTRIES: usize = 10;
+
+/// One winning time on the session leaderboard.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Entry {
+    pub initials: String,
+    pub time: Duration,
+}
+
+/// Winning times from this run of the program, keyed by board.
+///
+/// Nothing here is saved, so it suits a few people
+/// taking turns on one machine.
+#[derive(Debug, Default)]
+pub struct Leaderboard {
+    entries: BTreeMap<String, Vec<Entry>>,
+}
+
+impl Leaderboard {
+    /// Create an empty leaderboard.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a winning time, returning its place on the board from 1.
+    ///
+    /// Equal times are placed after the ones already recorded, and a
+    /// time too slow to be kept still gets the place it would have had.
+    pub fn record(&mut self, board_key: &str, initials: &str, time: Duration) -> usize {
+        let entries = self.entries.entry(String::from(board_key)).or_default();
+        let index = entries.partition_point(|entry| entry.time <= time);
+
+        entries.insert(
+            index,
+            Entry {
+                initials: String::from(initials),
+                time,
+            },
+        );
+        entries.truncate(MAX_ENTRIES);
+
+        index + 1
+    }
+
+    /// The fastest times recorded on a board, fastest first.
+    pub fn top(&self, board_key: &str, amount: usize) -> &[Entry] {
+        match self.entries.get(board_key) {
+            Some(entries) => &entries[..amount.min(entries.len())],
+            None => &[],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn seconds(secs: u64) -> Duration {
+        Duration::from_secs(secs)
+    }
+
+    #[test]
+    fn times_are_kept_fastest_first() {
+        let mut leaderboard = Leaderboard::new();
+
+        assert_eq!(leaderboard.record("expert", "AAA", seconds(90)), 1);
+        assert_eq!(leaderboard.record("expert", "BOB", seconds(60)), 1);
+        assert_eq!(leaderboard.record("expert", "CAT", seconds(75)), 2);
+
+        let initials: Vec<&str> = leaderboard
+            .top("expert", 3)
+            .iter()
+            .map(|entry| entry.initials.as_str())
+            .collect();
+        assert_eq!(initials, ["BOB", "CAT", "AAA"]);
+
+        // asking for more places than there are times gives all of them.
+        assert_eq!(leaderboard.top("expert", 10).len(), 3);
+        assert_eq!(leaderboard.top("expert", 1)[0].time, seconds(60));
+    }
+
+    #[test]
+    fn equal_times_go_after_the_earlier_ones() {
+        let mut leaderboard = Leaderboard::new();
+        leaderboard.record("beginner", "ONE", seconds(10));
+
+        assert_eq!(leaderboard.record("beginner", "TWO", seconds(10)), 2);
+        assert_eq!(leaderboard.top("beginner", 2)[0].initials, "ONE");
+    }
+
+    #[test]
+    fn each_board_keeps_its_own_times() {
+        let mut leaderboard = Leaderboard::new();
+
+        for secs in 1..=MAX_ENTRIES as u64 + 2 {
+            leaderboard.record("beginner", "ZED", seconds(secs));
+        }
+
+        assert_eq!(leaderboard.top("beginner", 100).len(), MAX_ENTRIES);
+        assert_eq!(
+            leaderboard.record("beginner", "SLO", seconds(100)),
+            MAX_ENTRIES + 1
+        );
+        assert_eq!(leaderboard.top("beginner", 100).len(), MAX_ENTRIES);
+
+        assert!(leaderboard.top("expert", 3).is_empty());
+        assert_eq!(leaderboard.record("expert", "NEW", seconds(500)), 1);
+    }
+}
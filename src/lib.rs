@@ -0,0 +1,41 @@
+//! The rules of the game, for the terminal game in `main.rs` and the
+//! other frontends to build on. With the `wasm` feature, they're exposed
+//! to JavaScript through `wasm_bindgen`, so a browser can play on the
+//! same engine as the terminal game.
+//!
+//! Build it for the browser with:
+//!
+//! ```sh
+//! cargo build --lib --release --target wasm32-unknown-unknown \
+//!     --no-default-features --features wasm
+//! ```
+
+pub mod difficulty;
+pub mod engine;
+pub mod history;
+pub mod mask;
+pub mod minefield;
+pub mod motion;
+pub mod position;
+pub mod protocol;
+pub mod rules;
+pub mod theme;
+pub mod tile;
+pub mod timer;
+pub mod topology;
+
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+#[cfg(any(feature = "termion", feature = "crossterm"))]
+pub mod terminal;
+
+/// Without a terminal backend, there are only the escape codes, which
+/// a field's theme is drawn with.
+#[cfg(not(any(feature = "termion", feature = "crossterm")))]
+pub mod terminal {
+    #[path = "ansi.rs"]
+    mod ansi;
+
+    pub use ansi::{clear, color, cursor, style};
+}
@@ -1,54 +1,705 @@
 use std::env;
+use std::error::Error;
+use std::fs;
+use std::io::{self, Write};
+use std::process;
+use std::time::Duration;
 
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+mod accessible;
+mod analyze;
+mod animation;
+mod autosave;
+mod benchmark;
+mod campaign;
+mod cli;
+mod confetti;
+mod config;
+mod coop;
+mod daily;
+mod eventlog;
+mod export;
+mod formats;
+mod frame;
 mod game;
-mod minefield;
-mod tile;
+mod gauntlet;
+mod headless;
+mod hooks;
+mod layout;
+mod leaderboard;
+mod menu;
+mod mode;
+mod net;
+mod pace;
+mod partial;
+mod review;
+mod scores;
+mod solver;
+mod sound;
+mod spectate;
+mod stats;
+mod storage;
+mod tips;
+mod tutorial;
+mod versus;
+mod view;
+mod viewport;
+
+use minesweeper::{
+    difficulty, engine, mask, minefield, motion, position, protocol, rules, terminal, theme, tile,
+    timer, topology,
+};
 
+use animation::FloodSpeed;
+use autosave::{Autosave, SavedGame};
+use campaign::Campaign;
+use cli::{Command, Options};
+use config::Config;
+use coop::Partner;
+use difficulty::Difficulty;
+use export::Format;
 use game::Minesweeper;
+use hooks::Hooks;
+use mask::Mask;
+use minefield::{MineField, MineFieldError, Region};
+use mode::Mode;
+use net::{Message, Rival};
+use pace::Records;
+use scores::Scores;
+use sound::Sounds;
+use spectate::Broadcaster;
+use stats::Stats;
+use theme::{Skin, Theme};
+use tips::Tips;
+use topology::Adjacency;
+
+/// Exit code for a game that couldn't be set up.
+const EXIT_FAILURE: i32 = 1;
+
+/// Exit code for bad command line arguments.
+const EXIT_USAGE: i32 = 2;
+
+/// Which end of a race over the network this player is on.
+enum Race {
+    Host(u16),
+    Join(String),
+}
+
+/// A game played with someone else over the network.
+enum Remote {
+    Race(Race),
+
+    /// Host a co-op game on a port.
+    Coop(u16),
+}
+
+/// Create the random number generator for a game, seeded if the
+/// command line asks for it.
+fn create_rng(options: &Options) -> StdRng {
+    match options.seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    }
+}
+
+/// Create the field asked for on the command line, or ask for a
+/// difficulty if there isn't one. Returns `None` if the player backs out.
+fn create_field(options: &Options, rng: &mut StdRng) -> Result<Option<MineField>, Box<dyn Error>> {
+    if let Some(shape) = &options.shape {
+        let text = fs::read_to_string(&shape.mask)
+            .map_err(|e| format!("couldn't read '{}': {}", shape.mask, e))?;
+        let mask = Mask::parse(&text)?;
+
+        return Ok(Some(MineField::with_mask_with(&mask, shape.mines, rng)?));
+    }
+
+    // boards in the Minesweeper Board Format are binary, and known by
+    // their extension. anything else is read as a layout.
+    if let Some(path) = &options.layout {
+        if path.to_lowercase().ends_with(".mbf") {
+            let bytes = fs::read(path).map_err(|e| format!("couldn't read '{}': {}", path, e))?;
+            return Ok(Some(formats::from_mbf(&bytes)?));
+        }
+
+        let text =
+            fs::read_to_string(path).map_err(|e| format!("couldn't read '{}': {}", path, e))?;
+
+        return Ok(Some(MineField::from_layout(&text)?));
+    }
+
+    // without a difficulty on the command line, ask for one.
+    let difficulty = match options.difficulty.or_else(menu::choose_difficulty) {
+        Some(difficulty) => difficulty,
+        None => return Ok(None),
+    };
+
+    Ok(Some(MineField::for_difficulty_with(difficulty, rng)?))
+}
+
+/// Apply the options that change how the field plays and looks.
+fn set_up_field(mut field: MineField, options: &Options) -> Result<MineField, MineFieldError> {
+    field.set_rules(options.rules);
+
+    if options.hex {
+        field.set_topology(&topology::HEX);
+    } else if let Some(adjacency) = &options.adjacency {
+        field.set_topology(adjacency.topology());
+    }
+
+    if let Some(theme) = options.theme {
+        field.set_theme(theme);
+    }
+
+    if let Some(skin) = options.skin {
+        field.set_skin(skin);
+    }
+
+    if let Some((width, height)) = options.target {
+        field.set_target(Region::centred(
+            height,
+            width,
+            field.height(),
+            field.width(),
+        ))?;
+    }
+
+    Ok(field)
+}
+
+/// Start a game on a field, played as it is if it was loaded rather
+/// than having an opening cleared.
+fn start_game(
+    field: MineField,
+    options: &Options,
+    is_loaded: bool,
+) -> Result<Minesweeper, MineFieldError> {
+    let field = set_up_field(field, options)?;
+
+    Ok(match is_loaded {
+        true => Minesweeper::with_layout(field),
+        false => Minesweeper::with_field(field),
+    })
+}
+
+/// Check if a game is plain enough to be saved and carried on with
+/// later. Gauntlets, campaigns and the like have more to them than
+/// the board, which a save doesn't keep.
+fn can_autosave(options: &Options) -> bool {
+    options.mode == Mode::Classic
+        && !options.gauntlet
+        && !options.daily
+        && !options.versus
+        && options.campaign.is_none()
+}
+
+/// Create the field for a headless or accessible game, with its first
+/// opening cleared unless it was loaded. There's no menu to ask for a
+/// difficulty, so a game without one is played on beginner.
+fn create_headless_field(options: &Options) -> Result<MineField, Box<dyn Error>> {
+    let difficulty = options.difficulty.unwrap_or(Difficulty::Beginner);
+    let options = Options {
+        difficulty: Some(difficulty),
+        seed: match options.daily {
+            true => Some(daily::seed(daily::today(), difficulty)),
+            false => options.seed,
+        },
+        ..options.clone()
+    };
+
+    let mut rng = create_rng(&options);
+    let field = create_field(&options, &mut rng)?.expect("a difficulty is always given");
+    let mut field = set_up_field(field, &options)?;
+
+    if options.layout.is_none() {
+        field.clear_first_opening_with(options.opening, &mut rng);
+    }
+
+    Ok(field)
+}
+
+/// Set up a game as the options and config ask, exiting if it can't be,
+/// or carry on with a saved one. Returns `None` if the player backs out
+/// of picking a difficulty.
+fn set_up_game(
+    options: &Options,
+    config: &Config,
+    today: u64,
+    saved: Option<&SavedGame>,
+) -> Option<Minesweeper> {
+    let mut rng = create_rng(options);
+
+    let campaign = match &options.campaign {
+        Some(puzzles) => match Campaign::load(puzzles) {
+            Ok(campaign) => Some(campaign),
+            Err(e) => {
+                eprintln!("error: {}", e);
+                process::exit(EXIT_FAILURE);
+            }
+        },
+        None => None,
+    };
+
+    // a campaign brings its own boards, and a saved game the board it
+    // was left on.
+    let field = match (saved, &campaign) {
+        (Some(saved), _) => Ok(Some(saved.field.clone())),
+        (None, Some(campaign)) => Ok(Some(campaign.puzzle().field().clone())),
+        (None, None) => create_field(options, &mut rng),
+    };
+
+    let field = match field {
+        Ok(Some(field)) => field,
+        Ok(None) => return None,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            process::exit(EXIT_FAILURE);
+        }
+    };
+
+    let is_loaded = options.layout.is_some() || saved.is_some();
+
+    let mut minesweeper = match start_game(field, options, is_loaded) {
+        Ok(minesweeper) => minesweeper,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            process::exit(EXIT_FAILURE);
+        }
+    };
+
+    if options.seed.is_some() {
+        minesweeper.use_seeded_rng(rng);
+    }
+
+    if let Some(saved) = saved {
+        minesweeper.resume(saved.elapsed, saved.cursor);
+    }
+
+    if options.daily {
+        minesweeper.play_daily(today);
+    }
+
+    minesweeper.keep_scores(Scores::load());
+    minesweeper.keep_stats(Stats::load());
+
+    let hooks = Hooks::from_config(config);
+
+    if !hooks.is_empty() {
+        minesweeper.set_hooks(hooks);
+    }
+
+    let sounds = Sounds::from_config(config);
+
+    if !sounds.is_empty() {
+        minesweeper.set_sounds(sounds);
+    }
+
+    if config.is_enabled("cursor_highlight") {
+        minesweeper.highlight_cursor();
+    }
+
+    if config.is_enabled("labels") {
+        minesweeper.show_labels();
+    }
+
+    // a game left alone for this many seconds pauses itself.
+    if let Some(seconds) = config.get("auto_pause").and_then(|s| s.parse().ok()) {
+        if seconds > 0 {
+            minesweeper.set_auto_pause(Duration::from_secs(seconds));
+        }
+    }
+
+    if config.is_enabled("pause_on_focus_loss") {
+        minesweeper.pause_on_focus_loss();
+    }
+
+    if config.is_enabled("reduced_motion") {
+        minesweeper.reduce_motion();
+    }
+
+    if let Some(speed) = config.get("flood_animation").and_then(FloodSpeed::parse) {
+        minesweeper.set_flood_speed(speed);
+    }
+
+    if let Some(reference) = options.pace.clone() {
+        minesweeper.compare_pace(reference, Records::load(config));
+    }
 
-fn custom_game(config: String) -> Minesweeper {
-    let first_split: Vec<&str> = config.split('_').collect();
+    if options.gauntlet {
+        minesweeper.start_gauntlet();
+    }
+
+    if let Some(campaign) = campaign {
+        minesweeper.start_campaign(campaign);
+    }
+
+    if options.casual {
+        minesweeper.enable_casual();
+    }
 
-    if first_split.len() != 2 {
-        panic!("Expected format: 'WxH_M'.");
+    if (options.auto_flag || config.is_enabled("auto_flag")) && !options.rules.no_flags {
+        minesweeper.enable_auto_flag();
     }
 
-    let geom = first_split[0];
-    let mines: usize = first_split[1]
-        .parse()
-        .expect("Mines must be a positive integer.");
+    if let Some(lives) = options.lives {
+        minesweeper.set_lives(lives);
+    }
 
-    let geom_split: Vec<&str> = geom.split('x').collect();
+    minesweeper.set_mode(options.mode);
+    minesweeper.set_opening(options.opening);
 
-    if geom_split.len() != 2 {
-        panic!("Expected format: 'WxH_M'.");
+    if options.leaderboard {
+        minesweeper.enable_leaderboard();
     }
 
-    let width: usize = geom_split[0]
-        .parse()
-        .expect("Width must be a positive integer.");
+    if options.tips {
+        minesweeper.enable_tips(Tips::load());
+    }
 
-    let height: usize = geom_split[1]
-        .parse()
-        .expect("Height must be a positive integer.");
+    if let Some(port) = options.broadcast {
+        match Broadcaster::start(port) {
+            Ok(broadcaster) => minesweeper.broadcast(broadcaster),
+            Err(e) => {
+                eprintln!("error: couldn't broadcast on port {}: {}", port, e);
+                process::exit(EXIT_FAILURE);
+            }
+        }
+    }
 
-    Minesweeper::new(width, height, mines).unwrap()
+    Some(minesweeper)
+}
+
+/// Connect to the other player of a race, agree on the board with them
+/// and play it, printing who won at the end.
+fn play_race(
+    race: Race,
+    mut options: Options,
+    config: &Config,
+    today: u64,
+) -> Result<(), Box<dyn Error>> {
+    let (name, rival_name) = match race {
+        Race::Host(_) => ("host", "guest"),
+        Race::Join(_) => ("guest", "host"),
+    };
+
+    let rival = match race {
+        Race::Host(port) => {
+            let difficulty = match options.difficulty.or_else(menu::choose_difficulty) {
+                Some(difficulty) => difficulty,
+                None => return Ok(()),
+            };
+            let seed = options.seed.unwrap_or_else(rand::random);
+
+            println!("waiting for someone to join on port {}...", port);
+
+            let mut rival =
+                Rival::host(port).map_err(|e| format!("couldn't host on port {}: {}", port, e))?;
+            rival.send(Message::Board {
+                seed,
+                difficulty,
+                hex: options.hex,
+            })?;
+
+            options.difficulty = Some(difficulty);
+            options.seed = Some(seed);
+            rival
+        }
+        Race::Join(address) => {
+            let mut rival =
+                Rival::join(&address).map_err(|e| format!("couldn't join '{}': {}", address, e))?;
+            let (seed, difficulty, hex) = rival.board()?;
+
+            options.difficulty = Some(difficulty);
+            options.seed = Some(seed);
+            options.hex = hex;
+            rival
+        }
+    };
+
+    let mut minesweeper =
+        set_up_game(&options, config, today, None).expect("the board is already picked");
+
+    if let Some((outcome, rival_outcome)) = terminal::run_guarded(|| minesweeper.play_race(rival)) {
+        // a rival who never dug didn't get anywhere.
+        let turns = [
+            (String::from(name), outcome),
+            (String::from(rival_name), rival_outcome.unwrap_or_default()),
+        ];
+
+        for line in versus::verdict(&turns) {
+            println!("{}", line);
+        }
+    }
+
+    Ok(())
 }
 
 fn main() {
-    let args: Vec<String> = env::args().collect();
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    let mut resumed = None;
 
-    let mut minesweeper = if args.len() <= 1 {
-        Minesweeper::beginner()
-    } else {
-        match args[1].trim() {
-            "beginner" => Minesweeper::beginner(),
-            "intermediate" => Minesweeper::intermediate(),
-            "expert" => Minesweeper::expert(),
-            "custom" => custom_game(args[2].clone()),
-            _ => panic!("Unknown game difficulty."),
+    let (mut options, remote) = match cli::parse_args(&args) {
+        Ok(Command::Play(options)) => (options, None),
+
+        // a saved game is set up by the arguments it was started with.
+        Ok(Command::Continue) => match autosave::latest() {
+            Ok(Some((saved, autosave))) => match cli::parse_args(&saved.args) {
+                Ok(Command::Play(options)) => {
+                    resumed = Some((saved, autosave));
+                    (options, None)
+                }
+                _ => {
+                    eprintln!("error: the last unfinished game can't be set up anymore.");
+                    process::exit(EXIT_FAILURE);
+                }
+            },
+            Ok(None) => {
+                eprintln!("error: there's no unfinished game to carry on with.");
+                process::exit(EXIT_FAILURE);
+            }
+            Err(e) => {
+                eprintln!("error: couldn't look for a saved game: {}", e);
+                process::exit(EXIT_FAILURE);
+            }
+        },
+        Ok(Command::Host { options, port }) => (options, Some(Remote::Race(Race::Host(port)))),
+        Ok(Command::Join { address, options }) => {
+            (options, Some(Remote::Race(Race::Join(address))))
+        }
+        Ok(Command::Coop { options, port }) => (options, Some(Remote::Coop(port))),
+        Ok(Command::JoinCoop { address }) => match coop::join(&address) {
+            Ok(()) => return,
+            Err(e) => {
+                eprintln!("error: couldn't join '{}': {}", address, e);
+                process::exit(EXIT_FAILURE);
+            }
+        },
+        Ok(Command::Analyze { position, mines }) => match analyze::analyze(&position, mines) {
+            Ok(report) => {
+                println!("{}", report);
+                return;
+            }
+            Err(e) => {
+                eprintln!("error: {}", e);
+                process::exit(EXIT_FAILURE);
+            }
+        },
+        Ok(Command::Generate {
+            difficulty,
+            seed,
+            format,
+            count,
+            out,
+        }) => {
+            let boards = export::generate_many(difficulty, count, seed, format);
+
+            match (boards, out) {
+                (Ok(boards), Some(out)) => {
+                    match export::write_boards(&boards, format, out.as_ref()) {
+                        Ok(index) => {
+                            println!("wrote {} boards, listed in {}", boards.len(), index);
+                            return;
+                        }
+                        Err(e) => {
+                            eprintln!("error: {}", e);
+                            process::exit(EXIT_FAILURE);
+                        }
+                    }
+                }
+                (Ok(boards), None) => {
+                    // a closed pipe isn't worth complaining about, and
+                    // boards drawn as text are kept apart by a blank line.
+                    for (index, board) in boards.iter().enumerate() {
+                        if index > 0 && matches!(format, Format::Ascii | Format::Layout) {
+                            println!();
+                        }
+
+                        io::stdout().write_all(&board.bytes).ok();
+                    }
+
+                    return;
+                }
+                (Err(e), _) => {
+                    eprintln!("error: {}", e);
+                    process::exit(EXIT_FAILURE);
+                }
+            }
+        }
+        Ok(Command::Solve {
+            difficulty,
+            games,
+            seed,
+        }) => match benchmark::run(difficulty, games, seed) {
+            Ok(report) => {
+                println!("{}", report);
+                return;
+            }
+            Err(e) => {
+                eprintln!("error: {}", e);
+                process::exit(EXIT_FAILURE);
+            }
+        },
+        Ok(Command::Headless { options, json }) => match create_headless_field(&options) {
+            Ok(field) => {
+                // the game just ends if whoever's playing hangs up.
+                headless::play(field, json, io::stdin().lock(), io::stdout()).ok();
+                return;
+            }
+            Err(e) => {
+                eprintln!("error: {}", e);
+                process::exit(EXIT_FAILURE);
+            }
+        },
+        Ok(Command::Accessible(options)) => match create_headless_field(&options) {
+            Ok(field) => {
+                accessible::play(field, io::stdin().lock(), io::stdout()).ok();
+                return;
+            }
+            Err(e) => {
+                eprintln!("error: {}", e);
+                process::exit(EXIT_FAILURE);
+            }
+        },
+        Ok(Command::Watch { address }) => match spectate::watch(&address) {
+            Ok(()) => return,
+            Err(e) => {
+                eprintln!("error: couldn't watch '{}': {}", address, e);
+                process::exit(EXIT_FAILURE);
+            }
+        },
+        Ok(Command::Stats) => {
+            println!("{}", Stats::load().summary());
+            return;
+        }
+        Ok(Command::Tutorial) => {
+            let config = Config::load();
+            let theme = config.get("theme").and_then(Theme::parse);
+            let skin = config.get("skin").and_then(Skin::parse);
+
+            match terminal::run_guarded(|| {
+                tutorial::play(theme.unwrap_or_default(), skin.unwrap_or_default())
+            }) {
+                Ok(true) => println!("that's everything! try 'minesweeper beginner' next."),
+                Ok(false) => (),
+                Err(e) => {
+                    eprintln!("error: {}", e);
+                    process::exit(EXIT_FAILURE);
+                }
+            }
+
+            return;
+        }
+        Ok(Command::Help) => {
+            println!("{}", cli::USAGE);
+            return;
+        }
+        Err(e) => {
+            eprintln!("error: {}", e);
+            eprintln!("try 'minesweeper --help' for more information.");
+            process::exit(EXIT_USAGE);
         }
     };
 
-    minesweeper.play();
+    let config = Config::load();
+
+    // a theme or skin on the command line wins over the one in the config.
+    options.theme = options
+        .theme
+        .or_else(|| config.get("theme").and_then(Theme::parse));
+    options.skin = options
+        .skin
+        .or_else(|| config.get("skin").and_then(Skin::parse));
+    options.rules.strict_flags |= config.is_enabled("strict_flags");
+
+    // an adjacency in the config only applies where one could be given.
+    if !options.hex && options.mode != Mode::Endless && options.campaign.is_none() {
+        options.adjacency = options
+            .adjacency
+            .or_else(|| config.get("adjacency").and_then(Adjacency::parse));
+    }
+
+    let today = daily::today();
+
+    // the daily challenge's board is seeded by the date and difficulty,
+    // so the difficulty has to be known before anything else.
+    if options.daily {
+        let difficulty = match options.difficulty.or_else(menu::choose_difficulty) {
+            Some(difficulty) => difficulty,
+            None => return,
+        };
+
+        options.difficulty = Some(difficulty);
+        options.seed = Some(daily::seed(today, difficulty));
+    }
+
+    // both players of a versus game get the same board, so it has to be
+    // picked before either of them plays.
+    if options.versus {
+        if options.difficulty.is_none() && options.layout.is_none() && options.shape.is_none() {
+            match menu::choose_difficulty() {
+                Some(difficulty) => options.difficulty = Some(difficulty),
+                None => return,
+            }
+        }
+
+        options.seed = options.seed.or_else(|| Some(rand::random()));
+
+        let turns = terminal::run_guarded(|| {
+            versus::play(|| {
+                set_up_game(&options, &config, today, None).expect("the board is already picked")
+            })
+        });
+
+        if let Some(turns) = turns {
+            for line in versus::verdict(&turns) {
+                println!("{}", line);
+            }
+
+            println!("(seed {})", options.seed.unwrap());
+        }
+
+        return;
+    }
+
+    if let Some(Remote::Race(race)) = remote {
+        if let Err(e) = play_race(race, options, &config, today) {
+            eprintln!("error: {}", e);
+            process::exit(EXIT_FAILURE);
+        }
+
+        return;
+    }
+
+    let saved = resumed.as_ref().map(|(saved, _)| saved);
+
+    if let Some(mut minesweeper) = set_up_game(&options, &config, today, saved) {
+        // the host of a co-op game has the board picked before the guest arrives.
+        if let Some(Remote::Coop(port)) = remote {
+            println!("waiting for someone to join on port {}...", port);
+
+            match Partner::host(port) {
+                Ok(partner) => minesweeper.cooperate(partner),
+                Err(e) => {
+                    eprintln!("error: couldn't host on port {}: {}", port, e);
+                    process::exit(EXIT_FAILURE);
+                }
+            }
+        }
+
+        // plain games are saved as they're played, to carry on with later.
+        if remote.is_none() && can_autosave(&options) {
+            let autosave = match resumed {
+                Some((_, autosave)) => Some(autosave),
+                None => Autosave::new(args),
+            };
+
+            if let Some(autosave) = autosave {
+                minesweeper.enable_autosave(autosave);
+            }
+        }
+
+        terminal::run_guarded(|| minesweeper.play());
+    }
 }
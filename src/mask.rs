@@ -0,0 +1,90 @@
+/// The shape of a field, saying which tiles are part of it.
+///
+/// Tiles outside of the shape are void: they never hold mines, don't
+/// count as neighbours, and are left blank when the field is drawn.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Mask {
+    width: usize,
+    height: usize,
+    tiles: Vec<bool>,
+}
+
+impl Mask {
+    /// Parse a text mask, one line per row.
+    ///
+    /// Tiles are `#` and void tiles are `.`. Shorter lines are padded
+    /// out with void tiles, so shapes don't need trailing dots.
+    /// Blank lines at the start and end are ignored.
+    pub fn parse(text: &str) -> Result<Self, &'static str> {
+        let lines: Vec<&str> = text.lines().map(str::trim_end).collect();
+
+        let first = lines.iter().position(|line| !line.is_empty());
+        let last = lines.iter().rposition(|line| !line.is_empty());
+
+        let lines = match (first, last) {
+            (Some(first), Some(last)) => &lines[first..=last],
+            _ => return Err("The mask is empty."),
+        };
+
+        let width = lines.iter().map(|line| line.chars().count()).max().unwrap();
+        let mut tiles = Vec::with_capacity(width * lines.len());
+
+        for line in lines {
+            for c in line.chars() {
+                match c {
+                    '#' => tiles.push(true),
+                    '.' => tiles.push(false),
+                    _ => return Err("Masks can only contain '#' and '.'."),
+                }
+            }
+
+            tiles.resize(tiles.len() + width - line.chars().count(), false);
+        }
+
+        if !tiles.contains(&true) {
+            return Err("The mask doesn't have any tiles in it.");
+        }
+
+        Ok(Self {
+            width,
+            height: lines.len(),
+            tiles,
+        })
+    }
+
+    /// The width of the smallest rectangle that holds the shape.
+    #[inline]
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// The height of the smallest rectangle that holds the shape.
+    #[inline]
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Check if a (row, column) is part of the shape.
+    #[inline]
+    pub fn contains(&self, row: usize, column: usize) -> bool {
+        row < self.height && column < self.width && self.tiles[row * self.width + column]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn short_lines_are_padded_with_void() {
+        let mask = Mask::parse("\n.#.\n###\n.#\n\n").unwrap();
+
+        assert_eq!((mask.width(), mask.height()), (3, 3));
+        assert!(mask.contains(1, 0));
+        assert!(!mask.contains(0, 0));
+        assert!(!mask.contains(2, 2));
+
+        assert!(Mask::parse("...\n...").is_err());
+        assert!(Mask::parse("#x#").is_err());
+    }
+}
@@ -0,0 +1,172 @@
+use std::io::Write;
+
+use crate::cli;
+use crate::difficulty::Difficulty;
+use crate::terminal::{self, clear, cursor, style, Backend, Guard, Key, RawOutput, Terminal};
+
+/// Keys read from the terminal while the menu is open.
+type Keys = <Terminal as Backend>::Keys;
+
+/// The difficulties on offer, with a short description of each.
+const CHOICES: [(&str, &str); 6] = [
+    ("beginner", "10x10 field with 10 mines"),
+    ("intermediate", "15x15 field with 40 mines"),
+    ("expert", "30x16 field with 99 mines"),
+    ("relaxed", "30x16 field with 48 mines"),
+    ("evil", "30x16 field with 115 mines"),
+    ("custom", "pick your own size"),
+];
+
+/// The first line of the menu on screen.
+const TOP_LINE: u16 = 3;
+
+/// The most digits that can be typed into a number.
+const MAX_DIGITS: usize = 6;
+
+/// The difficulty picked for each entry in `CHOICES`.
+fn difficulty_for(choice: usize) -> Option<Difficulty> {
+    match choice {
+        0 => Some(Difficulty::Beginner),
+        1 => Some(Difficulty::Intermediate),
+        2 => Some(Difficulty::Expert),
+        3 => cli::preset("relaxed"),
+        4 => cli::preset("evil"),
+        _ => None,
+    }
+}
+
+/// Draw the list of difficulties, with the chosen one highlighted.
+fn draw_choices(raw_stdout: &mut RawOutput, selected: usize) {
+    print!(
+        "{}{}minesweeper{}",
+        clear::All,
+        cursor::Goto(1, 1),
+        cursor::Hide
+    );
+
+    for (index, (name, description)) in CHOICES.iter().enumerate() {
+        let (marker, start, end) = if index == selected {
+            (">", style::Invert.to_string(), style::Reset.to_string())
+        } else {
+            (" ", String::new(), String::new())
+        };
+
+        print!(
+            "{}{} {}{:<12}{} {}",
+            cursor::Goto(1, TOP_LINE + index as u16),
+            marker,
+            start,
+            name,
+            end,
+            description
+        );
+    }
+
+    print!(
+        "{}up/down to choose, enter to start, q to quit",
+        cursor::Goto(1, TOP_LINE + CHOICES.len() as u16 + 1)
+    );
+
+    raw_stdout.flush().unwrap();
+}
+
+/// Read a positive number typed in below the menu.
+///
+/// Returns `None` if escape is pressed to go back.
+fn read_number(
+    raw_stdout: &mut RawOutput,
+    keys: &mut Keys,
+    name: &str,
+    line: u16,
+) -> Option<usize> {
+    let mut digits = String::new();
+
+    loop {
+        print!(
+            "{}{}{}: {}{}",
+            cursor::Goto(1, line),
+            clear::CurrentLine,
+            name,
+            digits,
+            cursor::Show
+        );
+
+        raw_stdout.flush().unwrap();
+
+        match keys.next()? {
+            Key::Char('\n') => match digits.parse() {
+                Ok(number) if number > 0 => break Some(number),
+                _ => digits.clear(),
+            },
+            Key::Char(c) if c.is_ascii_digit() && digits.len() < MAX_DIGITS => digits.push(c),
+            Key::Backspace => {
+                digits.pop();
+            }
+            Key::Esc | terminal::INTERRUPT => break None,
+            _ => (),
+        }
+    }
+}
+
+/// Ask for the size and mines of a custom field, until a playable one
+/// is given. Returns `None` if escape is pressed to go back.
+fn read_custom(raw_stdout: &mut RawOutput, keys: &mut Keys) -> Option<Difficulty> {
+    let line = TOP_LINE + CHOICES.len() as u16 + 3;
+
+    loop {
+        let width = read_number(raw_stdout, keys, "width", line)?;
+        let height = read_number(raw_stdout, keys, "height", line + 1)?;
+        let mines = read_number(raw_stdout, keys, "mines", line + 2)?;
+
+        match cli::validate(width, height, mines) {
+            Ok(difficulty) => break Some(difficulty),
+            Err(e) => {
+                // show what was wrong, then start again.
+                print!(
+                    "{}{}{}{}{}",
+                    cursor::Goto(1, line + 1),
+                    clear::CurrentLine,
+                    cursor::Goto(1, line + 2),
+                    clear::CurrentLine,
+                    cursor::Goto(1, line + 4),
+                );
+                print!("{}{}", clear::CurrentLine, e);
+            }
+        }
+    }
+}
+
+/// Ask the player which difficulty to play, in a menu on the terminal.
+///
+/// Returns `None` if they quit without choosing.
+pub fn choose_difficulty() -> Option<Difficulty> {
+    let mut raw_stdout = Guard::new().unwrap();
+    let mut keys = Terminal::keys();
+    let mut selected = 0;
+
+    let choice = loop {
+        draw_choices(&mut raw_stdout, selected);
+
+        match keys.next() {
+            Some(Key::Up) | Some(Key::Char('k')) => selected = selected.saturating_sub(1),
+            Some(Key::Down) | Some(Key::Char('j')) => {
+                selected = (selected + 1).min(CHOICES.len() - 1)
+            }
+
+            Some(Key::Char('\n')) => match difficulty_for(selected) {
+                Some(difficulty) => break Some(difficulty),
+                None => {
+                    if let Some(difficulty) = read_custom(&mut raw_stdout, &mut keys) {
+                        break Some(difficulty);
+                    }
+                }
+            },
+
+            Some(Key::Char('q')) | Some(Key::Esc) | Some(terminal::INTERRUPT) | None => break None,
+            _ => (),
+        }
+    };
+
+    // dropping the guard leaves the screen clean for the game, or the shell.
+    choice
+}
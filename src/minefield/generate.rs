@@ -0,0 +1,317 @@
+use std::cell::{Cell, OnceCell};
+
+use rand::Rng;
+
+use super::{Edge, MineField, MineFieldError, Region};
+use crate::difficulty::Difficulty;
+use crate::mask::Mask;
+use crate::position::Position;
+use crate::rules::Rules;
+use crate::theme::{Skin, Theme};
+use crate::tile::{Tile, TileState};
+use crate::topology;
+
+/// Creating fields and placing their mines.
+impl MineField {
+    /// Create a new, empty minefield.
+    pub fn empty(width: usize, height: usize) -> Self {
+        let mut tiles = Vec::new();
+
+        // Fill the vec with the correct amount of empty tiles.
+        for _index in 0..(width * height) {
+            tiles.push(Tile {
+                state: TileState::Hidden,
+                has_mine: false,
+                is_void: false,
+                note: None,
+            });
+        }
+
+        Self {
+            width,
+            height,
+            mines: 0,
+            flags: 0,
+            tiles,
+            target: None,
+            rules: Rules::default(),
+            topology: &topology::SQUARE,
+            theme: Theme::default(),
+            skin: Skin::default(),
+            is_over: false,
+            fatal_mines: Vec::new(),
+            counts: OnceCell::new(),
+            tally: Cell::new(None),
+        }
+    }
+
+    /// Populate the minefield with a given amount of mines.
+    pub fn populate(&mut self, amount: usize) -> Result<(), MineFieldError> {
+        self.populate_with(amount, &mut rand::thread_rng())
+    }
+
+    /// Populate the minefield with a given amount of mines, placed by
+    /// a particular random number generator. A seeded generator always
+    /// places them in the same way.
+    pub fn populate_with(
+        &mut self,
+        amount: usize,
+        rng: &mut impl Rng,
+    ) -> Result<(), MineFieldError> {
+        // Get a vec of all the empty tiles that we can populate.
+        let mut empty_tiles = Vec::new();
+
+        self.tiles_changed();
+
+        for tile in self.tiles.iter_mut() {
+            if !tile.has_mine && !tile.is_void {
+                empty_tiles.push(tile)
+            }
+        }
+
+        // Check if we have enough empty tiles to populate.
+        if empty_tiles.len() < amount {
+            return Err(MineFieldError::TooManyMines {
+                mines: amount,
+                space: empty_tiles.len(),
+            });
+        }
+
+        // If we do, select some and populate them by index.
+        let target_indices = rand::seq::index::sample(rng, empty_tiles.len(), amount);
+
+        for index in target_indices.iter() {
+            empty_tiles[index].has_mine = true;
+        }
+
+        self.mines += amount;
+
+        Ok(())
+    }
+
+    /// Create a new minefield and populate it.
+    pub fn new(width: usize, height: usize, mines: usize) -> Result<Self, MineFieldError> {
+        Self::new_with(width, height, mines, &mut rand::thread_rng())
+    }
+
+    /// Create a new minefield and populate it with a particular
+    /// random number generator.
+    pub fn new_with(
+        width: usize,
+        height: usize,
+        mines: usize,
+        rng: &mut impl Rng,
+    ) -> Result<Self, MineFieldError> {
+        let mut field = Self::empty(width, height);
+        field.populate_with(mines, rng)?;
+
+        Ok(field)
+    }
+
+    /// Create a new minefield at a difficulty and populate it.
+    pub fn for_difficulty(difficulty: Difficulty) -> Result<Self, MineFieldError> {
+        Self::for_difficulty_with(difficulty, &mut rand::thread_rng())
+    }
+
+    /// Create a new minefield at a difficulty and populate it with a
+    /// particular random number generator.
+    pub fn for_difficulty_with(
+        difficulty: Difficulty,
+        rng: &mut impl Rng,
+    ) -> Result<Self, MineFieldError> {
+        let (width, height, mines) = difficulty.dimensions();
+        Self::new_with(width, height, mines, rng)
+    }
+
+    /// Create a beginner field: 10x10 with 10 mines.
+    #[inline]
+    pub fn beginner() -> Self {
+        Self::for_difficulty(Difficulty::Beginner).unwrap()
+    }
+
+    /// Create an intermediate field: 15x15 with 40 mines.
+    #[inline]
+    pub fn intermediate() -> Self {
+        Self::for_difficulty(Difficulty::Intermediate).unwrap()
+    }
+
+    /// Create an expert field: 30x16 with 99 mines.
+    #[inline]
+    pub fn expert() -> Self {
+        Self::for_difficulty(Difficulty::Expert).unwrap()
+    }
+
+    /// Create a new minefield with mines at particular positions, such
+    /// as a board read from a file. A position given twice is one mine.
+    pub fn with_mines_at(
+        width: usize,
+        height: usize,
+        mines: &[Position],
+    ) -> Result<Self, MineFieldError> {
+        let mut field = Self::empty(width, height);
+
+        for &position in mines {
+            let tile = field.get_tile_mut(position)?;
+
+            if !tile.has_mine {
+                tile.has_mine = true;
+                field.mines += 1;
+            }
+        }
+
+        Ok(field)
+    }
+
+    /// The amount of mines that fills a field of some size to a density,
+    /// from 0 to 1, to the nearest whole mine.
+    pub fn mines_for_density(width: usize, height: usize, density: f64) -> usize {
+        (width as f64 * height as f64 * density).round() as usize
+    }
+
+    /// Create a new minefield with a density of mines, rather than an
+    /// amount of them, and populate it.
+    pub fn with_density(width: usize, height: usize, density: f64) -> Result<Self, MineFieldError> {
+        let mines = Self::mines_for_density(width, height, density);
+        Self::new_with(width, height, mines, &mut rand::thread_rng())
+    }
+
+    /// Create a new minefield in the shape of a mask, and populate it.
+    pub fn with_mask(mask: &Mask, mines: usize) -> Result<Self, MineFieldError> {
+        Self::with_mask_with(mask, mines, &mut rand::thread_rng())
+    }
+
+    /// Create a new minefield in the shape of a mask, and populate it
+    /// with a particular random number generator.
+    pub fn with_mask_with(
+        mask: &Mask,
+        mines: usize,
+        rng: &mut impl Rng,
+    ) -> Result<Self, MineFieldError> {
+        let mut field = Self::empty(mask.width(), mask.height());
+
+        for position in field.iter_positions().collect::<Vec<_>>() {
+            field.get_tile_mut(position)?.is_void = !mask.contains(position.row, position.column);
+        }
+
+        field.populate_with(mines, rng)?;
+        Ok(field)
+    }
+
+    /// Create a new field like this one, with the mines placed afresh.
+    pub fn regenerate(&self) -> Self {
+        self.regenerate_with(&mut rand::thread_rng())
+    }
+
+    /// Create a new field like this one, with the mines placed afresh
+    /// by a particular random number generator.
+    pub fn regenerate_with(&self, rng: &mut impl Rng) -> Self {
+        let mut field = Self::empty(self.width, self.height);
+
+        for (tile, old) in field.tiles.iter_mut().zip(self.tiles.iter()) {
+            tile.is_void = old.is_void;
+        }
+
+        field.populate_with(self.mines, rng).unwrap();
+        field.target = self.target;
+        field.rules = self.rules;
+        field.topology = self.topology;
+        field.theme = self.theme;
+        field.skin = self.skin;
+
+        field
+    }
+
+    /// Grow the field by some rows or columns at one edge, with mines
+    /// placed in the new tiles as densely as in the rest of the field.
+    /// Growing at the top or left moves every tile down or right.
+    ///
+    /// New tiles next to revealed ones never hold mines, so the numbers
+    /// already on show stay right, and openings on the old edge spill
+    /// over into the new tiles.
+    pub fn grow_with(&mut self, edge: Edge, amount: usize, rng: &mut impl Rng) {
+        let density = self.mines as f64 / self.tiles.len().max(1) as f64;
+
+        let (width, height) = match edge {
+            Edge::Top | Edge::Bottom => (self.width, self.height + amount),
+            Edge::Left | Edge::Right => (self.width + amount, self.height),
+        };
+
+        let old = Region {
+            top: if edge == Edge::Top { amount } else { 0 },
+            left: if edge == Edge::Left { amount } else { 0 },
+            rows: self.height,
+            columns: self.width,
+        };
+
+        let mut tiles = vec![
+            Tile {
+                state: TileState::Hidden,
+                has_mine: false,
+                is_void: false,
+                note: None,
+            };
+            width * height
+        ];
+
+        for (index, tile) in self.tiles.iter().enumerate() {
+            let (row, column) = (index / self.width + old.top, index % self.width + old.left);
+            tiles[row * width + column] = *tile;
+        }
+
+        self.tiles = tiles;
+        self.width = width;
+        self.height = height;
+
+        for position in self.fatal_mines.iter_mut() {
+            position.row += old.top;
+            position.column += old.left;
+        }
+
+        if let Some(target) = &mut self.target {
+            target.top += old.top;
+            target.left += old.left;
+        }
+
+        // only new tiles out of sight of the revealed ones can be mines.
+        let space: Vec<usize> = self
+            .iter_positions()
+            .filter(|&position| {
+                !old.contains(position)
+                    && self
+                        .get_tiles_near(position)
+                        .unwrap()
+                        .iter()
+                        .all(|tile| tile.state != TileState::Visible)
+            })
+            .map(|position| position.row * width + position.column)
+            .collect();
+
+        let new_tiles = width * height - old.rows * old.columns;
+        let mines = ((new_tiles as f64 * density).round() as usize).min(space.len());
+
+        for index in rand::seq::index::sample(rng, space.len(), mines).iter() {
+            self.tiles[space[index]].has_mine = true;
+        }
+
+        self.mines += mines;
+        self.tiles_changed();
+
+        // an opening that reached the old edge carries on past it.
+        let openings: Vec<Position> = self
+            .iter_positions()
+            .filter(|&position| {
+                old.contains(position)
+                    && self.get_tile_state(position) == Ok(TileState::Visible)
+                    && !self.has_mines_near(position).unwrap()
+            })
+            .collect();
+
+        for position in openings {
+            for near in self.get_indices_near(position).unwrap() {
+                if !old.contains(near) {
+                    self.flood_empty_tiles(near).unwrap();
+                }
+            }
+        }
+    }
+}
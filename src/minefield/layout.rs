@@ -0,0 +1,105 @@
+use super::{MineField, MineFieldError};
+use crate::position::Position;
+use crate::tile::{Tile, TileState};
+
+/// Writing fields out as text grids and reading them back in.
+///
+/// Each tile is a single character:
+///
+/// - `.` is a hidden tile and `*` is a hidden mine.
+/// - `f` is a flag on a safe tile and `F` is a flag on a mine.
+/// - `0` to `8` is a revealed tile. The number is there to be read,
+///   and is worked out again from the mines when the layout is loaded.
+/// - `X` is a mine that has been dug up.
+/// - ` ` is a void tile, outside the shape of the field.
+impl MineField {
+    /// Write the field out as a layout, one line per row.
+    pub fn to_string_layout(&self) -> String {
+        let mut text = String::new();
+
+        for row in 0..self.height {
+            let line: String = (0..self.width)
+                .map(|column| {
+                    let position = Position::new(row, column);
+                    let tile = self.get_tile(position).unwrap();
+
+                    match (tile.is_void, tile.state, tile.has_mine) {
+                        (true, _, _) => ' ',
+                        (false, TileState::Hidden, false) => '.',
+                        (false, TileState::Hidden, true) => '*',
+                        (false, TileState::Flagged, false) => 'f',
+                        (false, TileState::Flagged, true) => 'F',
+                        (false, TileState::Visible, true) => 'X',
+                        (false, TileState::Visible, false) => {
+                            // letters mean other things here, so counts past 8
+                            // are written as 8. they're counted again on load.
+                            let count = self.count_mines_near(position).unwrap();
+                            std::char::from_digit(count.min(8) as u32, 10).unwrap()
+                        }
+                    }
+                })
+                .collect();
+
+            text.push_str(line.trim_end());
+            text.push('\n');
+        }
+
+        text
+    }
+
+    /// Read a field from a layout, one line per row.
+    ///
+    /// Shorter lines are padded out with void tiles, and blank
+    /// lines at the start and end are ignored, as with masks.
+    pub fn from_layout(text: &str) -> Result<Self, MineFieldError> {
+        let lines: Vec<&str> = text.lines().map(str::trim_end).collect();
+
+        let first = lines.iter().position(|line| !line.is_empty());
+        let last = lines.iter().rposition(|line| !line.is_empty());
+
+        let lines = match (first, last) {
+            (Some(first), Some(last)) => &lines[first..=last],
+            _ => return Err(MineFieldError::EmptyLayout),
+        };
+
+        let width = lines.iter().map(|line| line.chars().count()).max().unwrap();
+        let mut field = Self::empty(width, lines.len());
+
+        for (row, line) in lines.iter().enumerate() {
+            for column in 0..width {
+                let position = Position::new(row, column);
+                let c = line.chars().nth(column).unwrap_or(' ');
+
+                let (state, has_mine) = match c {
+                    ' ' => {
+                        field.get_tile_mut(position).unwrap().is_void = true;
+                        continue;
+                    }
+                    '.' => (TileState::Hidden, false),
+                    '*' => (TileState::Hidden, true),
+                    'f' => (TileState::Flagged, false),
+                    'F' => (TileState::Flagged, true),
+                    'X' => (TileState::Visible, true),
+                    '0'..='8' => (TileState::Visible, false),
+                    c => return Err(MineFieldError::BadLayoutChar(c)),
+                };
+
+                *field.get_tile_mut(position).unwrap() = Tile {
+                    state,
+                    has_mine,
+                    is_void: false,
+                    note: None,
+                };
+
+                field.mines += has_mine as usize;
+                field.flags += (state == TileState::Flagged) as usize;
+            }
+        }
+
+        if field.iter_tiles().all(|tile| tile.is_void) {
+            return Err(MineFieldError::NoTiles);
+        }
+
+        Ok(field)
+    }
+}
@@ -0,0 +1,1188 @@
+use std::cell::{Cell, OnceCell};
+use std::error::Error;
+use std::fmt;
+
+use crate::position::Position;
+use crate::rules::Rules;
+use crate::theme::{Skin, Theme};
+use crate::tile::{Tile, TileState};
+use crate::topology::Topology;
+
+mod generate;
+mod layout;
+mod query;
+mod state;
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum MineFieldState {
+    Failed,
+    Cleared,
+    InProgress,
+}
+
+/// A rectangular area of the minefield.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub struct Region {
+    pub top: usize,
+    pub left: usize,
+    pub rows: usize,
+    pub columns: usize,
+}
+
+impl Region {
+    /// Create a region of a given size in the middle of a field.
+    pub fn centred(rows: usize, columns: usize, height: usize, width: usize) -> Self {
+        Self {
+            top: height.saturating_sub(rows) / 2,
+            left: width.saturating_sub(columns) / 2,
+            rows,
+            columns,
+        }
+    }
+
+    /// Check if a position lies within the region.
+    #[inline]
+    pub fn contains(&self, position: Position) -> bool {
+        (self.top..self.top + self.rows).contains(&position.row)
+            && (self.left..self.left + self.columns).contains(&position.column)
+    }
+}
+
+/// What the first opening cleared for the player has to be like.
+#[derive(Debug, Default, Eq, PartialEq, Copy, Clone)]
+pub struct Opening {
+    /// The fewest tiles that the opening has to reveal.
+    pub min_tiles: usize,
+
+    /// Whether the opening has to start from the middle of the field,
+    /// so that the cursor starts on a zero there.
+    pub is_centred: bool,
+}
+
+/// A side of the field, which an endless field can grow from.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum Edge {
+    Top,
+    Bottom,
+    Left,
+    Right,
+}
+
+/// A tile that differs between two fields, with how it looks on each.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub struct TileChange {
+    pub position: Position,
+    pub before: Tile,
+    pub after: Tile,
+}
+
+impl TileChange {
+    /// The same change made the other way round.
+    #[inline]
+    pub fn reversed(self) -> Self {
+        Self {
+            before: self.after,
+            after: self.before,
+            ..self
+        }
+    }
+}
+
+/// Something that went wrong while setting up or playing on a field.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum MineFieldError {
+    /// A position that isn't on the field.
+    OutOfBounds { row: usize, column: usize },
+
+    /// More mines than there are free tiles to hold them.
+    TooManyMines { mines: usize, space: usize },
+
+    /// A target region that doesn't fit on the field.
+    TargetDoesNotFit,
+
+    /// A flag placed with every flag already used, under strict flags.
+    NoFlagsLeft,
+
+    /// A flag placed in a flagless game.
+    FlagsDisabled,
+
+    /// A layout without any lines in it.
+    EmptyLayout,
+
+    /// A character in a layout that doesn't stand for a tile.
+    BadLayoutChar(char),
+
+    /// A layout made only of void tiles.
+    NoTiles,
+}
+
+impl fmt::Display for MineFieldError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MineFieldError::OutOfBounds { row, column } => {
+                write!(f, "Row {}, column {} isn't on the field.", row, column)
+            }
+            MineFieldError::TooManyMines { mines, space } => write!(
+                f,
+                "Not enough space for {} mines, only {} tiles are free.",
+                mines, space
+            ),
+            MineFieldError::TargetDoesNotFit => {
+                write!(f, "Target region doesn't fit on the field.")
+            }
+            MineFieldError::NoFlagsLeft => {
+                write!(f, "No flags left, since there are only as many as mines.")
+            }
+            MineFieldError::FlagsDisabled => write!(f, "Flags are turned off in this game."),
+            MineFieldError::EmptyLayout => write!(f, "The layout is empty."),
+            MineFieldError::BadLayoutChar(c) => write!(
+                f,
+                "Layouts can only contain '.*fFX', numbers and spaces, not '{}'.",
+                c
+            ),
+            MineFieldError::NoTiles => write!(f, "The layout doesn't have any tiles in it."),
+        }
+    }
+}
+
+impl Error for MineFieldError {}
+
+/// A grid of tiles, some of which hide mines.
+///
+/// The methods are split up by what they do: `generate` creates fields,
+/// `state` changes their tiles as the game goes on, `query` looks at
+/// them without changing anything, and `layout` writes them out as text.
+#[derive(Clone)]
+pub struct MineField {
+    width: usize,
+    height: usize,
+    mines: usize,
+    flags: usize,
+    tiles: Vec<Tile>,
+    target: Option<Region>,
+    rules: Rules,
+    topology: &'static dyn Topology,
+    theme: Theme,
+    skin: Skin,
+
+    /// Whether the game is over, so that wrong flags are shown.
+    is_over: bool,
+
+    /// The mines that were dug up to lose the game, once it's over.
+    fatal_mines: Vec<Position>,
+
+    /// The mines around each tile, in the same order as the tiles, and
+    /// a tally of the tiles that's kept up to date as they're dug and
+    /// flagged. Both are worked out afresh the first time they're
+    /// needed after the mines are placed or moved, rather than on every
+    /// move or every frame.
+    counts: OnceCell<Vec<usize>>,
+    tally: Cell<Option<Tally>>,
+}
+
+/// How many tiles there are of the kinds that decide how the game is
+/// going, so it can be told without looking at every tile.
+#[derive(Debug, Default, Eq, PartialEq, Copy, Clone)]
+struct Tally {
+    /// The safe tiles that need revealing to win, and how many of
+    /// them are revealed or still hidden.
+    safe: usize,
+    revealed: usize,
+    hidden: usize,
+
+    /// The mines that have been dug up, anywhere on the field.
+    dug_mines: usize,
+}
+
+impl Tally {
+    /// What a tile adds to the tally, going by whether it's in the
+    /// part of the field that has to be cleared.
+    fn of(tile: &Tile, must_clear: bool) -> Self {
+        let is_safe = must_clear && !tile.has_mine;
+
+        Self {
+            safe: is_safe as usize,
+            revealed: (is_safe && tile.state == TileState::Visible) as usize,
+            hidden: (is_safe && tile.state == TileState::Hidden) as usize,
+            dug_mines: (tile.has_mine && tile.state == TileState::Visible) as usize,
+        }
+    }
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            safe: self.safe + other.safe,
+            revealed: self.revealed + other.revealed,
+            hidden: self.hidden + other.hidden,
+            dug_mines: self.dug_mines + other.dug_mines,
+        }
+    }
+
+    fn sub(self, other: Self) -> Self {
+        Self {
+            safe: self.safe - other.safe,
+            revealed: self.revealed - other.revealed,
+            hidden: self.hidden - other.hidden,
+            dug_mines: self.dug_mines - other.dug_mines,
+        }
+    }
+}
+
+impl MineField {
+    /// Access the tile width of the minefield.
+    #[inline]
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Access the tile height of the minefield.
+    #[inline]
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Access the amount of mines on the minefield.
+    #[inline]
+    pub fn mines(&self) -> usize {
+        self.mines
+    }
+
+    #[inline]
+    pub fn flags(&self) -> usize {
+        self.flags
+    }
+
+    /// The amount of mines that haven't been flagged, if every flag is
+    /// right. This stops at zero, even with more flags than mines.
+    #[inline]
+    pub fn mines_remaining(&self) -> usize {
+        self.mines.saturating_sub(self.flags)
+    }
+
+    /// Access the region that must be cleared to win, if there is one.
+    #[inline]
+    pub fn target(&self) -> Option<Region> {
+        self.target
+    }
+
+    /// Only require a region of the field to be cleared to win.
+    ///
+    /// Mines outside of the region still end the game if they're dug.
+    pub fn set_target(&mut self, region: Region) -> Result<(), MineFieldError> {
+        let fits = region.rows > 0
+            && region.columns > 0
+            && region.top + region.rows <= self.height
+            && region.left + region.columns <= self.width;
+
+        if !fits {
+            return Err(MineFieldError::TargetDoesNotFit);
+        }
+
+        self.target = Some(region);
+        self.tiles_changed();
+        Ok(())
+    }
+
+    /// Access the rules that the field is played by.
+    #[inline]
+    pub fn rules(&self) -> Rules {
+        self.rules
+    }
+
+    /// Change the rules that the field is played by.
+    #[inline]
+    pub fn set_rules(&mut self, rules: Rules) {
+        self.rules = rules;
+    }
+
+    /// Access the shape of the grid the tiles are laid out on.
+    #[inline]
+    pub fn topology(&self) -> &'static dyn Topology {
+        self.topology
+    }
+
+    /// Lay the tiles out on a different shape of grid.
+    #[inline]
+    pub fn set_topology(&mut self, topology: &'static dyn Topology) {
+        self.topology = topology;
+        self.tiles_changed();
+    }
+
+    /// Access the theme that the tiles are drawn in.
+    #[inline]
+    pub fn theme(&self) -> Theme {
+        self.theme
+    }
+
+    /// Draw the tiles in a different theme.
+    #[inline]
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+    }
+
+    /// Access the glyphs that the tiles are drawn with.
+    #[inline]
+    pub fn skin(&self) -> Skin {
+        self.skin
+    }
+
+    /// Draw the tiles with a different set of glyphs.
+    #[inline]
+    pub fn set_skin(&mut self, skin: Skin) {
+        self.skin = skin;
+    }
+}
+
+/// Allow the minefield to be printed to the console, with coordinate
+/// labels around it when it's formatted with `{:#}`.
+impl fmt::Display for MineField {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let text = match f.alternate() {
+            true => self.render_labelled_area(0, 0, self.height, self.width),
+            false => self.render_area(0, 0, self.height, self.width),
+        };
+
+        write!(f, "{}", text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mask::Mask;
+    use crate::tile::{Note, TileState};
+    use crate::topology;
+    use proptest::prelude::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    /// Turn (row, column) pairs into positions, to compare neighbours.
+    fn positions(pairs: &[(usize, usize)]) -> Vec<Position> {
+        pairs.iter().copied().map(Position::from).collect()
+    }
+
+    #[test]
+    fn floods_open_in_waves_from_the_dug_tile() {
+        let mut field = MineField::from_layout("....*\n.....").unwrap();
+        let waves = field.flood_in_waves(Position::new(0, 1)).unwrap();
+
+        let sizes: Vec<usize> = waves.iter().map(Vec::len).collect();
+        assert_eq!(waves[0], [Position::new(0, 1)]);
+        assert_eq!(sizes, [1, 5, 2]);
+        assert_eq!(field.progress(), (8, 9));
+    }
+
+    #[test]
+    fn progress_keeps_up_with_the_tiles() {
+        let mut field = MineField::from_layout("..*\n...").unwrap();
+        assert_eq!(field.progress(), (0, 5));
+
+        field.dig_tile(Position::new(0, 0)).unwrap();
+        assert_eq!(field.progress(), (1, 5));
+
+        field
+            .set_target(Region {
+                top: 0,
+                left: 0,
+                rows: 1,
+                columns: 2,
+            })
+            .unwrap();
+        assert_eq!(field.progress(), (1, 2));
+
+        field.cover_all();
+        assert_eq!(field.progress(), (0, 2));
+    }
+
+    #[test]
+    fn flood_fill_opens_giant_empty_board() {
+        let mut field = MineField::empty(500, 500);
+        field.flood_empty_tiles(Position::new(250, 250)).unwrap();
+
+        assert!(field
+            .iter_tiles()
+            .all(|tile| tile.state == TileState::Visible));
+        assert_eq!(field.get_state(), MineFieldState::Cleared);
+    }
+
+    /// Create an empty field with mines at the given positions.
+    fn field_with_mines(width: usize, height: usize, mines: &[(usize, usize)]) -> MineField {
+        let mut field = MineField::empty(width, height);
+
+        for &(row, column) in mines {
+            field
+                .get_tile_mut(Position::new(row, column))
+                .unwrap()
+                .has_mine = true;
+        }
+
+        field.mines = mines.len();
+        field
+    }
+
+    #[test]
+    fn neighbours_on_degenerate_boards() {
+        let single = MineField::empty(1, 1);
+        assert_eq!(
+            single.get_indices_near(Position::new(0, 0)).unwrap(),
+            positions(&[])
+        );
+
+        let column = MineField::empty(1, 5);
+        assert_eq!(
+            column.get_indices_near(Position::new(0, 0)).unwrap(),
+            positions(&[(1, 0)])
+        );
+        assert_eq!(
+            column.get_indices_near(Position::new(2, 0)).unwrap(),
+            positions(&[(1, 0), (3, 0)])
+        );
+        assert_eq!(
+            column.get_indices_near(Position::new(4, 0)).unwrap(),
+            positions(&[(3, 0)])
+        );
+
+        let row = MineField::empty(5, 1);
+        assert_eq!(
+            row.get_indices_near(Position::new(0, 0)).unwrap(),
+            positions(&[(0, 1)])
+        );
+        assert_eq!(
+            row.get_indices_near(Position::new(0, 2)).unwrap(),
+            positions(&[(0, 1), (0, 3)])
+        );
+        assert_eq!(
+            row.get_indices_near(Position::new(0, 4)).unwrap(),
+            positions(&[(0, 3)])
+        );
+
+        assert!(row.get_indices_near(Position::new(1, 0)).is_err());
+        assert!(column.get_indices_near(Position::new(0, 1)).is_err());
+    }
+
+    #[test]
+    fn neighbours_at_corners_and_edges() {
+        let field = MineField::empty(4, 3);
+
+        assert_eq!(
+            field.get_indices_near(Position::new(0, 0)).unwrap(),
+            positions(&[(0, 1), (1, 0), (1, 1)])
+        );
+        assert_eq!(
+            field.get_indices_near(Position::new(2, 3)).unwrap(),
+            positions(&[(1, 2), (1, 3), (2, 2)])
+        );
+        assert_eq!(
+            field.get_indices_near(Position::new(0, 2)).unwrap().len(),
+            5
+        );
+        assert_eq!(
+            field.get_indices_near(Position::new(1, 0)).unwrap().len(),
+            5
+        );
+        assert_eq!(
+            field.get_indices_near(Position::new(1, 1)).unwrap().len(),
+            8
+        );
+    }
+
+    #[test]
+    fn flags_are_counted_as_they_change() {
+        let mut field = field_with_mines(3, 1, &[(0, 0)]);
+        field.toggle_flag(Position::new(0, 0)).unwrap();
+        field.toggle_flag(Position::new(0, 1)).unwrap();
+        assert_eq!(field.flags(), 2);
+
+        field.toggle_flag(Position::new(0, 1)).unwrap();
+        assert_eq!(field.flags(), 1);
+
+        // revealed tiles can't be flagged.
+        field.dig_tile(Position::new(0, 2)).unwrap();
+        field.toggle_flag(Position::new(0, 2)).unwrap();
+        assert_eq!(field.flags(), 1);
+    }
+
+    #[test]
+    fn strict_flags_stop_at_the_mine_count() {
+        let mut field = field_with_mines(3, 1, &[(0, 0)]);
+        field.set_rules(Rules {
+            strict_flags: true,
+            ..Rules::default()
+        });
+
+        field.toggle_flag(Position::new(0, 1)).unwrap();
+        assert_eq!(field.mines_remaining(), 0);
+        assert_eq!(
+            field.toggle_flag(Position::new(0, 0)),
+            Err(MineFieldError::NoFlagsLeft)
+        );
+
+        // taking a flag back frees it up again.
+        field.toggle_flag(Position::new(0, 1)).unwrap();
+        field.toggle_flag(Position::new(0, 0)).unwrap();
+        assert_eq!(field.mines_remaining(), 0);
+        assert_eq!(field.flags(), 1);
+    }
+
+    #[test]
+    fn hex_tiles_have_six_neighbours() {
+        let mut field = MineField::empty(5, 5);
+        field.set_topology(&topology::HEX);
+
+        // even rows lean left, odd rows lean right.
+        assert_eq!(
+            field.get_indices_near(Position::new(2, 2)).unwrap(),
+            positions(&[(1, 1), (1, 2), (2, 1), (2, 3), (3, 1), (3, 2)])
+        );
+        assert_eq!(
+            field.get_indices_near(Position::new(1, 2)).unwrap(),
+            positions(&[(0, 2), (0, 3), (1, 1), (1, 3), (2, 2), (2, 3)])
+        );
+        assert_eq!(
+            field.get_indices_near(Position::new(0, 0)).unwrap(),
+            positions(&[(0, 1), (1, 0)])
+        );
+
+        assert_eq!(field.render_area(0, 0, 2, 2), "# # \r\n # # ");
+    }
+
+    #[test]
+    fn single_tile_board() {
+        let mut field = MineField::new(1, 1, 0).unwrap();
+        assert_eq!(
+            field.clear_first_opening(Opening::default()),
+            Some(Position::new(0, 0))
+        );
+        assert_eq!(field.get_state(), MineFieldState::Cleared);
+
+        let mut field = field_with_mines(1, 1, &[(0, 0)]);
+        assert_eq!(field.clear_first_opening(Opening::default()), None);
+        field.toggle_flag(Position::new(0, 0)).unwrap();
+        assert_eq!(field.get_state(), MineFieldState::Cleared);
+    }
+
+    #[test]
+    fn flood_fill_along_a_single_row() {
+        let mut field = field_with_mines(6, 1, &[(0, 5)]);
+        field.flood_empty_tiles(Position::new(0, 0)).unwrap();
+
+        for column in 0..5 {
+            assert_eq!(
+                field.get_tile_state(Position::new(0, column)),
+                Ok(TileState::Visible)
+            );
+        }
+
+        assert_eq!(
+            field.get_tile_state(Position::new(0, 5)),
+            Ok(TileState::Hidden)
+        );
+        assert_eq!(field.get_state(), MineFieldState::Cleared);
+    }
+
+    #[test]
+    fn chord_down_a_single_column() {
+        let mut field = field_with_mines(1, 4, &[(0, 0)]);
+        field.dig_tile(Position::new(1, 0)).unwrap();
+        field.toggle_flag(Position::new(0, 0)).unwrap();
+
+        assert_eq!(
+            field.do_chord(Position::new(1, 0)).unwrap(),
+            [Position::new(2, 0), Position::new(3, 0)]
+        );
+        assert_eq!(
+            field.get_tile_state(Position::new(2, 0)),
+            Ok(TileState::Visible)
+        );
+        assert_eq!(
+            field.get_tile_state(Position::new(3, 0)),
+            Ok(TileState::Visible)
+        );
+        assert_eq!(field.get_state(), MineFieldState::Cleared);
+    }
+
+    #[test]
+    fn chording_around_a_wrong_flag_digs_the_mine() {
+        let mut field = field_with_mines(1, 3, &[(0, 0)]);
+        field.dig_tile(Position::new(1, 0)).unwrap();
+        field.toggle_flag(Position::new(2, 0)).unwrap();
+        field.do_chord(Position::new(1, 0)).unwrap();
+
+        assert_eq!(
+            field.get_tile_state(Position::new(0, 0)),
+            Ok(TileState::Visible)
+        );
+        assert_eq!(field.get_state(), MineFieldState::Failed);
+    }
+
+    #[test]
+    fn render_degenerate_boards() {
+        assert_eq!(MineField::empty(3, 1).to_string(), "# # # ");
+        assert_eq!(MineField::empty(1, 3).to_string(), "# \r\n# \r\n# ");
+    }
+
+    #[test]
+    fn chord_without_cascade_reveals_single_tiles() {
+        let mut field = field_with_mines(1, 5, &[(0, 0)]);
+        field.set_rules(Rules {
+            chord_cascade: false,
+            ..Rules::default()
+        });
+
+        field.dig_tile(Position::new(1, 0)).unwrap();
+        field.toggle_flag(Position::new(0, 0)).unwrap();
+
+        assert_eq!(
+            field.do_chord(Position::new(1, 0)).unwrap(),
+            [Position::new(2, 0)]
+        );
+        assert_eq!(
+            field.get_tile_state(Position::new(3, 0)),
+            Ok(TileState::Hidden)
+        );
+    }
+
+    #[test]
+    fn target_region_only_needs_its_own_tiles_cleared() {
+        let mut field = MineField::empty(5, 5);
+        field.get_tile_mut(Position::new(0, 0)).unwrap().has_mine = true;
+        field
+            .set_target(Region::centred(1, 1, field.height(), field.width()))
+            .unwrap();
+
+        assert_eq!(field.get_state(), MineFieldState::InProgress);
+
+        field.dig_tile(Position::new(2, 2)).unwrap();
+        assert_eq!(field.get_state(), MineFieldState::Cleared);
+
+        field.dig_tile(Position::new(0, 0)).unwrap();
+        assert_eq!(field.get_state(), MineFieldState::Failed);
+    }
+
+    #[test]
+    fn endless_fields_grow_around_what_is_revealed() {
+        let mut field = field_with_mines(4, 2, &[(1, 3)]);
+        field.flood_empty_tiles(Position::new(0, 0)).unwrap();
+        assert!(field.edges_near_revealed(1).contains(&Edge::Top));
+
+        field.grow_with(Edge::Top, 2, &mut StdRng::seed_from_u64(1));
+        assert_eq!((field.width(), field.height(), field.mines()), (4, 4, 2));
+        assert!(field.has_mine_at(Position::new(3, 3)).unwrap());
+        assert_eq!(field.count_mines_near(Position::new(2, 2)), Ok(1));
+
+        // the new mine is kept away from the revealed tiles, and the
+        // opening spills over into the row beside it.
+        assert!((0..4).any(|column| field.has_mine_at(Position::new(0, column)).unwrap()));
+        assert!((0..4).all(|column| !field.has_mine_at(Position::new(1, column)).unwrap()));
+        assert_eq!(
+            field.get_tile_state(Position::new(1, 0)),
+            Ok(TileState::Visible)
+        );
+        assert_eq!(field.get_state(), MineFieldState::InProgress);
+    }
+
+    #[test]
+    fn density_picks_the_amount_of_mines() {
+        assert_eq!(MineField::mines_for_density(30, 16, 0.24), 115);
+        assert_eq!(MineField::mines_for_density(10, 10, 0.123), 12);
+
+        let field = MineField::with_density(10, 10, 0.18).unwrap();
+        assert_eq!(field.mines(), 18);
+        assert!(MineField::with_density(2, 2, 1.5).is_err());
+    }
+
+    #[test]
+    fn openings_are_as_big_and_centred_as_asked() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let centred = Opening {
+            min_tiles: 0,
+            is_centred: true,
+        };
+
+        for _ in 0..20 {
+            let mut field = MineField::new_with(9, 9, 30, &mut rng).unwrap();
+            assert_eq!(
+                field.clear_first_opening_with(centred, &mut rng),
+                Some(Position::new(4, 4))
+            );
+            assert_eq!(
+                (field.mines(), field.count_mines_near(Position::new(4, 4))),
+                (30, Ok(0))
+            );
+        }
+
+        let big = Opening {
+            min_tiles: 30,
+            is_centred: false,
+        };
+
+        for _ in 0..20 {
+            let mut field = MineField::new_with(16, 16, 40, &mut rng).unwrap();
+            field.clear_first_opening_with(big, &mut rng).unwrap();
+            assert!(field.progress().0 >= 30);
+        }
+    }
+
+    #[test]
+    fn covering_up_keeps_the_mines() {
+        let mut field = field_with_mines(3, 3, &[(0, 0), (2, 2)]);
+        field.toggle_flag(Position::new(0, 0)).unwrap();
+        field.flood_empty_tiles(Position::new(0, 2)).unwrap();
+
+        field.cover_all();
+
+        assert_eq!(field.flags(), 0);
+        assert!(field
+            .iter_tiles()
+            .all(|tile| tile.state == TileState::Hidden));
+        assert!(
+            field.has_mine_at(Position::new(0, 0)).unwrap()
+                && field.has_mine_at(Position::new(2, 2)).unwrap()
+        );
+
+        let fresh = field.regenerate();
+        assert_eq!((fresh.width(), fresh.height(), fresh.mines()), (3, 3, 2));
+    }
+
+    #[test]
+    fn seeded_generators_make_the_same_game() {
+        let play = |seed| {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let mut field = MineField::new_with(16, 16, 40, &mut rng).unwrap();
+            let opening = field.clear_first_opening_with(Opening::default(), &mut rng);
+
+            (
+                field.to_string_layout(),
+                opening,
+                field.regenerate_with(&mut rng).to_string_layout(),
+            )
+        };
+
+        assert_eq!(play(5), play(5));
+        assert_ne!(play(5), play(6));
+    }
+
+    #[test]
+    fn void_tiles_are_left_out_of_the_game() {
+        let mask = Mask::parse(".#.\n###\n.#.").unwrap();
+        let mut field = MineField::with_mask(&mask, 4).unwrap();
+
+        // only the five tiles in the shape can hold mines.
+        assert!(MineField::with_mask(&mask, 6).is_err());
+        assert!(!field.get_tile(Position::new(0, 0)).unwrap().has_mine);
+        assert_eq!(
+            field.get_indices_near(Position::new(0, 1)).unwrap(),
+            positions(&[(1, 0), (1, 1), (1, 2)])
+        );
+
+        field.dig_tile(Position::new(0, 0)).unwrap();
+        assert_eq!(
+            field.get_tile_state(Position::new(0, 0)),
+            Ok(TileState::Hidden)
+        );
+
+        // digging the one safe tile wins, without touching the void.
+        let safe = field
+            .iter_positions()
+            .find(|&position| {
+                let tile = field.get_tile(position).unwrap();
+                !tile.is_void && !tile.has_mine
+            })
+            .unwrap();
+        field.dig_tile(safe).unwrap();
+        assert_eq!(field.get_state(), MineFieldState::Cleared);
+        assert!(
+            field
+                .regenerate()
+                .get_tile(Position::new(2, 2))
+                .unwrap()
+                .is_void
+        );
+    }
+
+    #[test]
+    fn dug_mines_can_be_flagged_out_of_the_way() {
+        let mut field = field_with_mines(3, 1, &[(0, 0), (0, 2)]);
+        field.dig_tile(Position::new(0, 0)).unwrap();
+        field.dig_tile(Position::new(0, 1)).unwrap();
+        assert_eq!(field.dug_mines(), 1);
+
+        assert_eq!(field.flag_dug_mines(), [Position::new(0, 0)]);
+        assert_eq!(field.flags(), 1);
+        assert_eq!(field.dug_mines(), 0);
+        assert_eq!(field.get_state(), MineFieldState::Cleared);
+    }
+
+    #[test]
+    fn three_bv_counts_openings_and_lone_numbers() {
+        // an opening reveals the number beside it.
+        assert_eq!(field_with_mines(3, 1, &[(0, 0)]).three_bv(), 1);
+        // numbers with no opening next to them need a click each.
+        assert_eq!(field_with_mines(3, 1, &[(0, 1)]).three_bv(), 2);
+        assert_eq!(field_with_mines(5, 1, &[(0, 2)]).three_bv(), 2);
+        assert_eq!(field_with_mines(5, 1, &[(0, 1), (0, 3)]).three_bv(), 3);
+        assert_eq!(MineField::empty(4, 4).three_bv(), 1);
+    }
+
+    #[test]
+    fn diffs_are_symmetric_and_complete() {
+        let before = field_with_mines(4, 3, &[(0, 0), (2, 3)]);
+        let mut after = before.clone();
+        after.toggle_flag(Position::new(0, 0)).unwrap();
+        after.flood_empty_tiles(Position::new(2, 0)).unwrap();
+        after.get_tile_mut(Position::new(1, 3)).unwrap().has_mine = true;
+
+        assert!(before.diff(&before).is_empty());
+
+        let forwards = before.diff(&after);
+        let backwards = after.diff(&before);
+        let reversed: Vec<TileChange> = backwards.into_iter().map(TileChange::reversed).collect();
+        assert_eq!(forwards, reversed);
+
+        // every tile that differs is listed, and applying them all gets there.
+        let differing = before
+            .iter_tiles()
+            .zip(after.iter_tiles())
+            .filter(|(a, b)| a != b)
+            .count();
+        assert_eq!(forwards.len(), differing);
+
+        let mut patched = before.clone();
+        for change in forwards {
+            let position = change.position;
+            *patched.get_tile_mut(position).unwrap() = change.after;
+        }
+        assert!(patched.diff(&after).is_empty());
+    }
+
+    #[test]
+    fn flagless_games_chord_off_the_numbers() {
+        let mut field = MineField::from_layout("1*1.").unwrap();
+        field.set_rules(Rules {
+            no_flags: true,
+            ..Rules::default()
+        });
+
+        assert_eq!(
+            field.toggle_flag(Position::new(0, 3)),
+            Err(MineFieldError::FlagsDisabled)
+        );
+
+        // the first 1 gives the mine away, which the chord counts.
+        assert_eq!(field.obvious_mines(), [Position::new(0, 1)]);
+        field.do_chord(Position::new(0, 2)).unwrap();
+        assert_eq!(
+            field.get_tile_state(Position::new(0, 3)).unwrap(),
+            TileState::Visible
+        );
+        assert_eq!(field.get_state(), MineFieldState::Cleared);
+    }
+
+    #[test]
+    fn obvious_mines_are_flagged() {
+        let mut field = MineField::from_layout("*1\n11").unwrap();
+        assert_eq!(field.flag_obvious_mines(), [Position::new(0, 0)]);
+        assert_eq!(
+            field.get_tile_state(Position::new(0, 0)).unwrap(),
+            TileState::Flagged
+        );
+        assert_eq!(field.flags(), 1);
+
+        // nothing around the other numbers is certain yet.
+        let mut field = MineField::from_layout("*.\n.1").unwrap();
+        assert!(field.flag_obvious_mines().is_empty());
+    }
+
+    #[test]
+    fn autocomplete_flags_the_last_mines() {
+        let mut field = MineField::from_layout("*.*\n111").unwrap();
+        assert!(field.try_autocomplete().is_empty());
+
+        field.flood_empty_tiles(Position::new(0, 1)).unwrap();
+        assert_eq!(
+            field.try_autocomplete(),
+            [Position::new(0, 0), Position::new(0, 2)]
+        );
+        assert_eq!(field.flags(), 2);
+        assert_eq!(field.get_state(), MineFieldState::Cleared);
+        assert!(field.try_autocomplete().is_empty());
+    }
+
+    #[test]
+    fn game_over_marks_wrong_flags_and_the_fatal_mine() {
+        let mut field = MineField::from_layout("Ff*\nX..").unwrap();
+        field.set_theme(Theme::Mono);
+        field.game_over();
+
+        let drawn: Vec<String> = field
+            .iter_positions()
+            .map(|position| field.char_for_tile(position).unwrap())
+            .collect();
+        assert_eq!(drawn.concat(), "Ff*X31");
+
+        // a new round has nothing to mark.
+        let field = field.regenerate();
+        assert_eq!(field.char_for_tile(Position::new(0, 1)).unwrap(), "#");
+    }
+
+    #[test]
+    fn notes_go_round_on_hidden_tiles() {
+        let mut field = MineField::from_layout("*1.").unwrap();
+
+        let notes: Vec<Option<Note>> = (0..5)
+            .map(|_| field.cycle_note(Position::new(0, 0)).unwrap())
+            .collect();
+        assert_eq!(
+            notes,
+            [
+                Some(Note::Mine),
+                Some(Note::Safe),
+                Some(Note::Mark(1)),
+                Some(Note::Mark(2)),
+                Some(Note::Mark(3)),
+            ]
+        );
+        assert_eq!(field.cycle_note(Position::new(0, 0)).unwrap(), None);
+
+        // revealed tiles can't be noted, and notes aren't flags.
+        assert_eq!(field.cycle_note(Position::new(0, 1)).unwrap(), None);
+        field.cycle_note(Position::new(0, 2)).unwrap();
+        assert_eq!(field.flags(), 0);
+        assert_eq!(
+            field.describe_tile(Position::new(0, 2)).unwrap(),
+            "hidden, noted as a maybe-mine"
+        );
+    }
+
+    #[test]
+    fn labels_name_the_rows_and_columns() {
+        let field = MineField::empty(3, 2);
+
+        assert_eq!(format!("{}", field), "# # # \r\n# # # ");
+        assert_eq!(format!("{:#}", field), "  A B C\r\n1 # # # \r\n2 # # # ");
+
+        // past Z, the columns take two letters and spread out to fit them.
+        let field = MineField::empty(28, 1);
+        let labelled = format!("{:#}", field);
+        let (letters, tiles) = labelled.split_once("\r\n").unwrap();
+
+        assert_eq!(field.label_cell_width(), 3);
+        assert!(letters.starts_with("  A  B  C  "));
+        assert!(letters.ends_with("Y  Z  AA AB"));
+        assert_eq!(tiles, format!("1 {}", "#  ".repeat(28)));
+    }
+
+    #[test]
+    fn layouts_survive_a_round_trip() {
+        let text = "\n.*f\nF1X\n 2\n\n";
+        let field = MineField::from_layout(text).unwrap();
+
+        assert_eq!((field.width(), field.height()), (3, 3));
+        assert_eq!((field.mines(), field.flags()), (3, 2));
+        assert!(field.get_tile(Position::new(2, 0)).unwrap().is_void);
+        assert!(field.get_tile(Position::new(2, 2)).unwrap().is_void);
+
+        // revealed numbers are worked out again from the mines.
+        assert_eq!(field.to_string_layout(), ".*f\nF3X\n 2\n");
+
+        assert_eq!(
+            MineField::from_layout("\n  \n").err(),
+            Some(MineFieldError::EmptyLayout)
+        );
+        assert_eq!(
+            MineField::from_layout(".9.").err(),
+            Some(MineFieldError::BadLayoutChar('9'))
+        );
+    }
+
+    #[test]
+    fn errors_say_what_went_wrong() {
+        let mut field = MineField::empty(3, 2);
+
+        assert_eq!(
+            field.get_tile(Position::new(2, 0)).unwrap_err(),
+            MineFieldError::OutOfBounds { row: 2, column: 0 }
+        );
+        assert_eq!(
+            field.populate(7).unwrap_err(),
+            MineFieldError::TooManyMines { mines: 7, space: 6 }
+        );
+        assert_eq!(
+            field.set_target(Region::centred(3, 1, 2, 3)).unwrap_err(),
+            MineFieldError::TargetDoesNotFit
+        );
+    }
+
+    /// Create a field with mines placed by a seeded generator.
+    fn seeded_field(width: usize, height: usize, mines: usize, seed: u64) -> MineField {
+        let mut rng = StdRng::seed_from_u64(seed);
+        MineField::new_with(width, height, mines, &mut rng).unwrap()
+    }
+
+    /// A field of up to 12x12 tiles and how many of them are mines.
+    fn field_size() -> impl Strategy<Value = (usize, usize, usize)> {
+        (1..=12usize, 1..=12usize)
+            .prop_flat_map(|(width, height)| (Just(width), Just(height), 0..=width * height))
+    }
+
+    proptest! {
+        #[test]
+        fn populate_fills_up_to_the_size_of_the_field(
+            (width, height, mines) in field_size(),
+            seed: u64,
+        ) {
+            let field = seeded_field(width, height, mines, seed);
+            prop_assert_eq!(field.mines(), mines);
+            prop_assert_eq!(field.iter_tiles().filter(|tile| tile.has_mine).count(), mines);
+
+            let mut full = MineField::empty(width, height);
+            let mut rng = StdRng::seed_from_u64(seed);
+            prop_assert!(full.populate_with(width * height + 1, &mut rng).is_err());
+            prop_assert_eq!(full.mines(), 0);
+        }
+
+        #[test]
+        fn mines_near_a_tile_are_among_its_neighbours(
+            (width, height, mines) in field_size(),
+            seed: u64,
+        ) {
+            let field = seeded_field(width, height, mines, seed);
+
+            for position in field.iter_positions() {
+                let Position { row, column } = position;
+                let near = field.get_indices_near(position).unwrap();
+                let rows = (row + 2).min(height) - row.saturating_sub(1);
+                let columns = (column + 2).min(width) - column.saturating_sub(1);
+                prop_assert_eq!(near.len(), rows * columns - 1);
+
+                let mined = near.iter().filter(|&&neighbour| field.has_mine_at(neighbour).unwrap());
+                prop_assert_eq!(field.count_mines_near(position).unwrap(), mined.count());
+            }
+        }
+
+        #[test]
+        fn flood_fill_opens_exactly_the_area_around_it(
+            (width, height, mines) in field_size(),
+            seed: u64,
+            start: (usize, usize),
+        ) {
+            let mut field = seeded_field(width, height, mines, seed);
+            let (row, column) = (start.0 % height, start.1 % width);
+            prop_assume!(!field.has_mine_at(Position::new(row, column)).unwrap());
+
+            field.flood_empty_tiles(Position::new(row, column)).unwrap();
+            prop_assert_ne!(field.get_state(), MineFieldState::Failed);
+
+            for position in field.iter_positions() {
+                let is_visible = field.get_tile_state(position).unwrap() == TileState::Visible;
+
+                // every revealed zero opens up all of its neighbours.
+                if is_visible && !field.has_mines_near(position).unwrap() {
+                    for neighbour in field.get_indices_near(position).unwrap() {
+                        prop_assert_eq!(field.get_tile_state(neighbour), Ok(TileState::Visible));
+                    }
+                }
+            }
+        }
+
+        #[test]
+        fn chords_with_right_flags_never_dig_a_mine(
+            (width, height, mines) in field_size(),
+            seed: u64,
+        ) {
+            let mut field = seeded_field(width, height, mines, seed);
+
+            for position in field.iter_positions().collect::<Vec<_>>() {
+                if field.has_mine_at(position).unwrap() {
+                    field.toggle_flag(position).unwrap();
+                } else {
+                    field.dig_tile(position).unwrap();
+                }
+            }
+
+            for position in field.iter_positions().collect::<Vec<_>>() {
+                field.do_chord(position).unwrap();
+            }
+
+            prop_assert_eq!(field.flags(), mines);
+            prop_assert_eq!(field.get_state(), MineFieldState::Cleared);
+        }
+
+        #[test]
+        fn games_are_won_by_clearing_and_lost_by_digging_mines(
+            (width, height, mines) in field_size(),
+            seed: u64,
+        ) {
+            let mut field = seeded_field(width, height, mines, seed);
+            let safe: Vec<_> = field
+                .iter_positions()
+                .filter(|&position| !field.has_mine_at(position).unwrap())
+                .collect();
+
+            for (index, &position) in safe.iter().enumerate() {
+                prop_assert_eq!(field.get_state(), MineFieldState::InProgress);
+                field.dig_tile(position).unwrap();
+
+                let expected = match index + 1 == safe.len() {
+                    true => MineFieldState::Cleared,
+                    false => MineFieldState::InProgress,
+                };
+                prop_assert_eq!(field.get_state(), expected);
+            }
+
+            let mine = field
+                .iter_positions()
+                .find(|&position| field.has_mine_at(position).unwrap());
+
+            if let Some(mine) = mine {
+                field.dig_tile(mine).unwrap();
+                prop_assert_eq!(field.get_state(), MineFieldState::Failed);
+            }
+        }
+
+        #[test]
+        fn flag_count_matches_the_flagged_tiles(
+            toggles in prop::collection::vec((0..6usize, 0..6usize), 0..40),
+        ) {
+            let mut field = seeded_field(6, 6, 8, 1);
+            field.flood_empty_tiles(Position::new(0, 0)).ok();
+
+            for (row, column) in toggles {
+                field.toggle_flag(Position::new(row, column)).unwrap();
+
+                let flagged = field
+                    .iter_tiles()
+                    .filter(|tile| tile.state == TileState::Flagged)
+                    .count();
+                prop_assert_eq!(field.flags(), flagged);
+            }
+        }
+
+        #[test]
+        fn kept_counts_match_counting_afresh(
+            (width, height, mines) in field_size(),
+            seed: u64,
+            moves in prop::collection::vec((0..12usize, 0..12usize, 0..3u8), 0..20),
+        ) {
+            let mut field = seeded_field(width, height, mines, seed);
+
+            for (row, column, kind) in moves {
+                let position = Position::new(row % height, column % width);
+
+                match kind {
+                    0 => field.flood_empty_tiles(position).unwrap(),
+                    1 => field.toggle_flag(position).unwrap(),
+                    _ => {
+                        field.do_chord(position).unwrap();
+                    }
+                }
+            }
+
+            let mut fresh = field.clone();
+            fresh.tiles_changed();
+
+            prop_assert_eq!(field.get_state(), fresh.get_state());
+            prop_assert_eq!(field.progress(), fresh.progress());
+            prop_assert_eq!(field.dug_mines(), fresh.dug_mines());
+        }
+    }
+}
@@ -0,0 +1,453 @@
+use super::{Edge, MineField, MineFieldError, MineFieldState, Tally, TileChange};
+use crate::position::Position;
+use crate::tile::{Tile, TileState};
+
+/// Looking at the field without changing it.
+impl MineField {
+    /// Check if a position is in range.
+    #[inline]
+    pub fn position_is_valid(&self, position: Position) -> bool {
+        position.is_within(self.height, self.width)
+    }
+
+    /// Get an immutable reference to each tile in order.
+    #[inline]
+    pub fn iter_tiles(&self) -> impl Iterator<Item = &Tile> {
+        self.tiles.iter()
+    }
+
+    pub fn iter_positions(&self) -> impl Iterator<Item = Position> + '_ {
+        let range = 0..(self.width * self.height);
+
+        // map each index to the corresponding position
+        range.map(move |index| self.position_of(index))
+    }
+
+    /// Find the position of the tile kept at an index.
+    #[inline]
+    pub(super) fn position_of(&self, index: usize) -> Position {
+        Position::new(index / self.width, index % self.width)
+    }
+
+    /// Find where a tile is kept, checking that it's on the field.
+    pub(super) fn index_of(&self, position: Position) -> Result<usize, MineFieldError> {
+        if self.position_is_valid(position) {
+            Ok(position.row * self.width + position.column)
+        } else {
+            Err(MineFieldError::OutOfBounds {
+                row: position.row,
+                column: position.column,
+            })
+        }
+    }
+
+    /// Check if a tile has to be revealed to win, if it's safe. With a
+    /// target region, only the tiles inside it do.
+    pub(super) fn must_clear(&self, index: usize) -> bool {
+        let position = self.position_of(index);
+
+        self.target.is_none_or(|region| region.contains(position)) && !self.tiles[index].is_void
+    }
+
+    /// Count up the tiles, unless they've been counted since the mines
+    /// were last placed, in which case the tally's been kept up to date.
+    fn tally(&self) -> Tally {
+        if let Some(tally) = self.tally.get() {
+            return tally;
+        }
+
+        let tally = self
+            .iter_tiles()
+            .enumerate()
+            .fold(Tally::default(), |tally, (index, tile)| {
+                tally.add(Tally::of(tile, self.must_clear(index)))
+            });
+
+        self.tally.set(Some(tally));
+        tally
+    }
+
+    /// The mines around every tile, counted once for each placement
+    /// of the mines.
+    fn counts(&self) -> &[usize] {
+        self.counts.get_or_init(|| {
+            self.iter_positions()
+                .map(|position| {
+                    self.get_tiles_near(position)
+                        .unwrap()
+                        .iter()
+                        .filter(|tile| tile.has_mine)
+                        .count()
+                })
+                .collect()
+        })
+    }
+
+    /// Get an immutable reference to a specific tile.
+    pub fn get_tile(&self, position: Position) -> Result<&Tile, MineFieldError> {
+        // check if the tile is in range, then fetch it.
+        Ok(&self.tiles[self.index_of(position)?])
+    }
+
+    pub fn has_mine_at(&self, position: Position) -> Result<bool, MineFieldError> {
+        Ok(self.get_tile(position)?.has_mine)
+    }
+
+    pub fn get_tile_state(&self, position: Position) -> Result<TileState, MineFieldError> {
+        // TODO: go through the code and see where
+        // this can be put to use.
+        Ok(self.get_tile(position)?.state)
+    }
+
+    pub fn get_indices_near(&self, position: Position) -> Result<Vec<Position>, MineFieldError> {
+        // Check if the tile even exists.
+        self.get_tile(position)?;
+
+        Ok(self
+            .topology
+            .neighbours(position, self.height, self.width)
+            .into_iter()
+            .filter(|position| !self.tiles[position.row * self.width + position.column].is_void)
+            .collect())
+    }
+
+    pub fn get_tiles_near(&self, position: Position) -> Result<Vec<&Tile>, MineFieldError> {
+        // convert positions to their corresponding tiles
+        self.get_indices_near(position)?
+            .into_iter()
+            .map(|position| self.get_tile(position))
+            .collect()
+    }
+
+    /// Count the amount of mines near a tile.
+    pub fn count_mines_near(&self, position: Position) -> Result<usize, MineFieldError> {
+        Ok(self.counts()[self.index_of(position)?])
+    }
+
+    pub fn has_mines_near(&self, position: Position) -> Result<bool, MineFieldError> {
+        Ok(self.count_mines_near(position)? > 0)
+    }
+
+    /// Get the char representation of a tile, drawn in the field's theme
+    /// and skin.
+    pub fn char_for_tile(&self, position: Position) -> Result<String, MineFieldError> {
+        let tile = self.get_tile(position)?;
+
+        let in_target = self.target.is_some_and(|region| region.contains(position));
+
+        // void tiles aren't part of the field, so they're left blank.
+        if tile.is_void {
+            return Ok(String::from(self.skin.blank()));
+        }
+
+        Ok(match tile.state {
+            // notes cover up the tile, and hidden tiles that need
+            // clearing are highlighted.
+            TileState::Hidden => match tile.note {
+                Some(note) => self.theme.note(self.skin, note),
+                None => self.theme.hidden(self.skin, in_target),
+            },
+            // once it's all over, flags without mines are shown as wrong.
+            TileState::Flagged if self.is_over && !tile.has_mine => {
+                self.theme.wrong_flag(self.skin)
+            }
+            TileState::Flagged => self.theme.flag(self.skin),
+            TileState::Visible if tile.has_mine => {
+                if self.fatal_mines.contains(&position) {
+                    self.theme.fatal_mine(self.skin)
+                } else {
+                    self.theme.mine(self.skin)
+                }
+            }
+            TileState::Visible => {
+                match self.count_mines_near(position).unwrap() {
+                    // if the tile is exposed and empty, show the empty
+                    // tile or display the amount of surrounding mines
+                    0 => String::from(self.skin.blank()),
+                    n => self.theme.number(self.skin, n),
+                }
+            }
+        })
+    }
+
+    /// Describe a tile in a word or two, as the player sees it, like
+    /// `hidden`, `empty` or `a 2`.
+    pub fn describe_tile(&self, position: Position) -> Result<String, MineFieldError> {
+        let tile = self.get_tile(position)?;
+
+        Ok(match (tile.is_void, tile.state, tile.has_mine) {
+            (true, _, _) => String::from("a gap"),
+            (false, TileState::Hidden, _) => match tile.note {
+                Some(note) => format!("hidden, noted as {}", note.describe()),
+                None => String::from("hidden"),
+            },
+            (false, TileState::Flagged, _) => String::from("flagged"),
+            (false, TileState::Visible, true) => String::from("a mine"),
+            (false, TileState::Visible, false) => match self.count_mines_near(position)? {
+                0 => String::from("empty"),
+                count => format!("a {}", count),
+            },
+        })
+    }
+
+    /// Work out the field's 3BV, the fewest clicks it takes to clear
+    /// without using flags or chords.
+    ///
+    /// Each opening of empty tiles takes a single click, which also opens
+    /// the numbers around its edge. Every other safe tile needs its own.
+    pub fn three_bv(&self) -> usize {
+        let mut opened = vec![false; self.tiles.len()];
+        let mut clicks = 0;
+
+        for (index, tile) in self.iter_tiles().enumerate() {
+            let position = self.position_of(index);
+            let is_empty =
+                !tile.has_mine && !tile.is_void && !self.has_mines_near(position).unwrap();
+
+            if opened[index] || !is_empty {
+                continue;
+            }
+
+            // open the whole opening, the same way a dig floods it.
+            clicks += 1;
+            let mut stack = vec![position];
+
+            while let Some(position) = stack.pop() {
+                let index = self.index_of(position).unwrap();
+
+                if opened[index] {
+                    continue;
+                }
+
+                opened[index] = true;
+
+                if !self.has_mines_near(position).unwrap() {
+                    stack.extend(self.get_indices_near(position).unwrap());
+                }
+            }
+        }
+
+        let unopened = self
+            .iter_tiles()
+            .zip(opened.iter())
+            .filter(|(tile, &opened)| !opened && !tile.has_mine && !tile.is_void)
+            .count();
+
+        clicks + unopened
+    }
+
+    /// Count the mines that have been dug up.
+    pub fn dug_mines(&self) -> usize {
+        self.tally().dug_mines
+    }
+
+    /// Work out the state of the game.
+    pub fn get_state(&self) -> MineFieldState {
+        let tally = self.tally();
+
+        // if a mine is exposed, they've failed, and if a tile is still
+        // unsolved, they haven't cleared.
+        if tally.dug_mines > 0 {
+            MineFieldState::Failed
+        } else if tally.hidden > 0 {
+            MineFieldState::InProgress
+        } else {
+            MineFieldState::Cleared
+        }
+    }
+
+    /// Find every covered tile that must be a mine, going by a visible
+    /// number with only as many covered tiles around it as its count.
+    pub fn obvious_mines(&self) -> Vec<Position> {
+        let mut obvious = Vec::new();
+
+        for position in self.iter_positions() {
+            if self.get_tile_state(position).unwrap() != TileState::Visible {
+                continue;
+            }
+
+            let number = self.count_mines_near(position).unwrap();
+            let covered: Vec<Position> = self
+                .get_indices_near(position)
+                .unwrap()
+                .into_iter()
+                .filter(|&near| self.get_tile_state(near).unwrap() != TileState::Visible)
+                .collect();
+
+            if number > 0 && covered.len() == number {
+                for tile in covered {
+                    if !obvious.contains(&tile) {
+                        obvious.push(tile);
+                    }
+                }
+            }
+        }
+
+        obvious
+    }
+
+    /// Count the safe tiles that have been revealed, out of all the
+    /// safe tiles that need revealing to win.
+    pub fn progress(&self) -> (usize, usize) {
+        let tally = self.tally();
+        (tally.revealed, tally.safe)
+    }
+
+    /// Find the edges of the field that a revealed tile is within some
+    /// amount of rows or columns of.
+    pub fn edges_near_revealed(&self, distance: usize) -> Vec<Edge> {
+        let revealed: Vec<Position> = self
+            .iter_positions()
+            .zip(self.iter_tiles())
+            .filter(|(_, tile)| tile.state == TileState::Visible)
+            .map(|(position, _)| position)
+            .collect();
+
+        let edges = [
+            (
+                Edge::Top,
+                revealed.iter().any(|position| position.row < distance),
+            ),
+            (
+                Edge::Bottom,
+                revealed
+                    .iter()
+                    .any(|position| position.row + distance >= self.height),
+            ),
+            (
+                Edge::Left,
+                revealed.iter().any(|position| position.column < distance),
+            ),
+            (
+                Edge::Right,
+                revealed
+                    .iter()
+                    .any(|position| position.column + distance >= self.width),
+            ),
+        ];
+
+        edges
+            .iter()
+            .filter(|(_, is_near)| *is_near)
+            .map(|(edge, _)| *edge)
+            .collect()
+    }
+
+    /// Find every tile that differs between this field and another,
+    /// as the changes that would turn this field into the other one.
+    ///
+    /// Both fields must be the same size, or this will panic.
+    pub fn diff(&self, other: &MineField) -> Vec<TileChange> {
+        assert!(
+            self.width == other.width && self.height == other.height,
+            "can't compare a {}x{} field with a {}x{} one",
+            self.width,
+            self.height,
+            other.width,
+            other.height
+        );
+
+        self.iter_positions()
+            .zip(self.iter_tiles().zip(other.iter_tiles()))
+            .filter(|(_, (before, after))| before != after)
+            .map(|(position, (&before, &after))| TileChange {
+                position,
+                before,
+                after,
+            })
+            .collect()
+    }
+
+    /// Render a rectangular area of the minefield, one line per row.
+    ///
+    /// Rows and columns outside of the field are left out.
+    pub fn render_area(&self, top: usize, left: usize, rows: usize, columns: usize) -> String {
+        self.render_cells(top, left, rows, columns, 2)
+    }
+
+    /// Render an area with each tile padded out to a cell some amount
+    /// of characters across, of at least two.
+    fn render_cells(
+        &self,
+        top: usize,
+        left: usize,
+        rows: usize,
+        columns: usize,
+        cell_width: usize,
+    ) -> String {
+        let bottom = (top + rows).min(self.height);
+        let right = (left + columns).min(self.width);
+
+        // wide glyphs fill two characters on their own.
+        let gap = match self.skin.is_wide() {
+            true => " ".repeat(cell_width - 2),
+            false => " ".repeat(cell_width - 1),
+        };
+
+        let mut lines = Vec::new();
+
+        for row in top..bottom {
+            let mut line = " ".repeat(self.topology.row_offset(row));
+
+            for column in left..right {
+                line.push_str(&self.char_for_tile(Position::new(row, column)).unwrap());
+                line.push_str(&gap);
+            }
+
+            lines.push(line);
+        }
+
+        // separate rows with newline chars.
+        lines.join("\r\n")
+    }
+
+    /// How many characters the row numbers take up beside the field,
+    /// when it's drawn with coordinate labels.
+    #[inline]
+    pub fn row_label_width(&self) -> usize {
+        self.height.to_string().len()
+    }
+
+    /// How many characters across each tile takes up when the field is
+    /// drawn with coordinate labels: enough for the longest column letters
+    /// and a space after them, and never less than the usual two.
+    pub fn label_cell_width(&self) -> usize {
+        let letters = Position::column_letters(self.width.saturating_sub(1));
+        (letters.len() + 1).max(2)
+    }
+
+    /// Render an area like `render_area`, with the columns lettered along
+    /// the top and the rows numbered down the left, so that each tile can
+    /// be found by its name in A1 notation. The tiles are spread out to
+    /// fit the letters over them once there are two to a column.
+    pub fn render_labelled_area(
+        &self,
+        top: usize,
+        left: usize,
+        rows: usize,
+        columns: usize,
+    ) -> String {
+        let bottom = (top + rows).min(self.height);
+        let right = (left + columns).min(self.width);
+        let width = self.row_label_width();
+        let cell_width = self.label_cell_width();
+
+        let letters: String = (left..right)
+            .map(|column| {
+                let letters = Position::column_letters(column);
+                format!("{:<cell_width$}", letters, cell_width = cell_width)
+            })
+            .collect();
+
+        let mut lines = vec![format!("{} {}", " ".repeat(width), letters.trim_end())];
+
+        for (row, line) in (top..bottom).zip(
+            self.render_cells(top, left, rows, columns, cell_width)
+                .split("\r\n"),
+        ) {
+            lines.push(format!("{:>width$} {}", row + 1, line, width = width));
+        }
+
+        lines.join("\r\n")
+    }
+}
@@ -0,0 +1,491 @@
+use std::collections::HashSet;
+
+use rand::seq::IteratorRandom;
+use rand::Rng;
+
+use super::{MineField, MineFieldError, MineFieldState, Opening, Tally};
+use crate::position::Position;
+use crate::tile::{Note, Tile, TileState};
+
+/// How many times the mines are placed afresh to find an opening that's
+/// big enough, before settling for the biggest one there is.
+const OPENING_ATTEMPTS: usize = 100;
+
+/// Changing the tiles as the game is played.
+impl MineField {
+    /// Forget everything worked out from the tiles, since they're about
+    /// to change in any way at all, or just have.
+    #[inline]
+    pub(super) fn tiles_changed(&mut self) {
+        self.counts.take();
+        self.tally.set(None);
+    }
+
+    /// Put a tile into a state, keeping the flag count and the tally
+    /// up to date as it goes.
+    fn change_state(&mut self, index: usize, state: TileState) {
+        let old = self.tiles[index];
+        self.tiles[index].state = state;
+
+        match (old.state == TileState::Flagged, state == TileState::Flagged) {
+            (false, true) => self.flags += 1,
+            (true, false) => self.flags -= 1,
+            _ => (),
+        }
+
+        if let Some(tally) = self.tally.get() {
+            let must_clear = self.must_clear(index);
+            let tally = tally
+                .sub(Tally::of(&old, must_clear))
+                .add(Tally::of(&self.tiles[index], must_clear));
+
+            self.tally.set(Some(tally));
+        }
+    }
+
+    /// Get a mutable reference to each tile in order.
+    #[inline]
+    pub fn iter_mut_tiles(&mut self) -> impl Iterator<Item = &mut Tile> {
+        self.tiles_changed();
+        self.tiles.iter_mut()
+    }
+
+    /// Get a mutable reference to a specific tile.
+    pub fn get_tile_mut(&mut self, position: Position) -> Result<&mut Tile, MineFieldError> {
+        let index = self.index_of(position)?;
+        self.tiles_changed();
+        Ok(&mut self.tiles[index])
+    }
+
+    /// Toggle a tile state between `Hidden` and `Flagged`.
+    ///
+    /// With strict flags, a flag can't be placed once there are as
+    /// many flags as mines, although one can still be taken away.
+    /// In a flagless game, no flag can be placed at all.
+    pub fn toggle_flag(&mut self, position: Position) -> Result<(), MineFieldError> {
+        if self.rules.no_flags {
+            return Err(MineFieldError::FlagsDisabled);
+        }
+
+        let is_out_of_flags = self.rules.strict_flags && self.flags >= self.mines;
+        let index = self.index_of(position)?;
+
+        if self.tiles[index].is_void {
+            return Ok(());
+        }
+
+        match self.tiles[index].state {
+            TileState::Hidden if is_out_of_flags => return Err(MineFieldError::NoFlagsLeft),
+            TileState::Hidden => self.change_state(index, TileState::Flagged),
+            TileState::Flagged => self.change_state(index, TileState::Hidden),
+            _ => (),
+        }
+
+        Ok(())
+    }
+
+    /// Move the note on a hidden tile on to the next one, returning the
+    /// note it has now. Tiles that aren't hidden are left as they are.
+    pub fn cycle_note(&mut self, position: Position) -> Result<Option<Note>, MineFieldError> {
+        // notes don't count for anything, so the tally is left alone.
+        let index = self.index_of(position)?;
+        let tile = &mut self.tiles[index];
+
+        if !tile.is_void && tile.state == TileState::Hidden {
+            tile.note = Note::next(tile.note);
+        }
+
+        Ok(tile.note)
+    }
+
+    /// Put a tile into any state, keeping the flag count up to date.
+    ///
+    /// This skips the rules of play, so it's meant for restoring
+    /// tiles to a state they've been in before.
+    pub fn set_tile_state(
+        &mut self,
+        position: Position,
+        state: TileState,
+    ) -> Result<(), MineFieldError> {
+        let index = self.index_of(position)?;
+        self.change_state(index, state);
+
+        Ok(())
+    }
+
+    /// Change a tile state from `Hidden` to `Visible`.
+    pub fn dig_tile(&mut self, position: Position) -> Result<(), MineFieldError> {
+        let index = self.index_of(position)?;
+        let tile = self.tiles[index];
+
+        if tile.state == TileState::Hidden && !tile.is_void {
+            self.change_state(index, TileState::Visible);
+        }
+
+        Ok(())
+    }
+
+    /// Perform a flood fill on empty space.
+    pub fn flood_empty_tiles(&mut self, position: Position) -> Result<(), MineFieldError> {
+        self.flood_in_waves(position).map(|_| ())
+    }
+
+    /// Perform a flood fill on empty space, breadth first, returning the
+    /// tiles it opened in waves: the dug tile, then the tiles a step
+    /// away from it, then the ones a step further, and so on.
+    ///
+    /// This works through each wave in a loop rather than recursing, so
+    /// that huge custom boards can't overflow the call stack.
+    pub fn flood_in_waves(
+        &mut self,
+        position: Position,
+    ) -> Result<Vec<Vec<Position>>, MineFieldError> {
+        let mut waves = Vec::new();
+        let mut wave = vec![position];
+
+        while !wave.is_empty() {
+            let mut opened = Vec::new();
+            let mut next = Vec::new();
+
+            for position in wave {
+                // flags and tiles that are already open stop the flood.
+                if self.get_tile_state(position)? != TileState::Hidden {
+                    continue;
+                }
+
+                self.dig_tile(position)?;
+
+                if self.get_tile_state(position)? == TileState::Visible {
+                    opened.push(position);
+                }
+
+                if !self.has_mines_near(position)? {
+                    next.extend(self.get_indices_near(position)?);
+                }
+            }
+
+            if !opened.is_empty() {
+                waves.push(opened);
+            }
+
+            wave = next;
+        }
+
+        Ok(waves)
+    }
+
+    /// Reveal a hidden tile, either alone or with a flood fill
+    /// if it turns out to have no mines around it. Returns the tiles
+    /// that were opened.
+    pub fn reveal(
+        &mut self,
+        position: Position,
+        cascade: bool,
+    ) -> Result<Vec<Position>, MineFieldError> {
+        if cascade {
+            return Ok(self.flood_in_waves(position)?.concat());
+        }
+
+        let was_hidden = self.get_tile_state(position)? == TileState::Hidden;
+        self.dig_tile(position)?;
+
+        match was_hidden && self.get_tile_state(position)? == TileState::Visible {
+            true => Ok(vec![position]),
+            false => Ok(vec![]),
+        }
+    }
+
+    /// Open a random empty field for convenience, as the opening asks,
+    /// then return the index of a tile within it.
+    pub fn clear_first_opening(&mut self, opening: Opening) -> Option<Position> {
+        self.clear_first_opening_with(opening, &mut rand::thread_rng())
+    }
+
+    /// Open an empty field picked by a particular random number
+    /// generator, as the opening asks, then return the index of a tile
+    /// within it.
+    ///
+    /// A centred opening has the mines around the middle moved out of
+    /// its way. If no empty field is big enough, the mines are placed
+    /// afresh until one is, and a field that still can't manage it
+    /// after a while gets its biggest opening instead.
+    pub fn clear_first_opening_with(
+        &mut self,
+        opening: Opening,
+        rng: &mut impl Rng,
+    ) -> Option<Position> {
+        let centre = Position::new(self.height / 2, self.width / 2);
+        let is_centred = opening.is_centred && !self.get_tile(centre).ok()?.is_void;
+        let is_limited = is_centred || opening.min_tiles > 0;
+        let mut sizes = Vec::new();
+
+        for attempt in 0..OPENING_ATTEMPTS {
+            if is_centred {
+                self.clear_mines_around(centre, rng);
+            }
+
+            sizes = self.opening_sizes();
+
+            let targets: Vec<Position> = sizes
+                .iter()
+                .filter(|&&(position, size)| {
+                    size >= opening.min_tiles && (!is_centred || position == centre)
+                })
+                .map(|&(position, _)| position)
+                .collect();
+
+            // select a random empty tile to open.
+            if let Some(&position) = targets.iter().choose(rng) {
+                self.flood_empty_tiles(position).unwrap();
+                return Some(position);
+            }
+
+            if !is_limited || attempt + 1 == OPENING_ATTEMPTS {
+                break;
+            }
+
+            self.replace_mines(rng);
+        }
+
+        // settle for the biggest opening there is, if there's any at all.
+        let &(position, _) = sizes.iter().max_by_key(|(_, size)| *size)?;
+        self.flood_empty_tiles(position).unwrap();
+        Some(position)
+    }
+
+    /// Find every tile an opening could be started from, which is any
+    /// safe tile without mines around it, with how many tiles the
+    /// opening would reveal.
+    fn opening_sizes(&self) -> Vec<(Position, usize)> {
+        let is_empty = |position: Position| {
+            let tile = self.get_tile(position).unwrap();
+            !tile.has_mine && !tile.is_void && !self.has_mines_near(position).unwrap()
+        };
+
+        // each empty area is found once, and its tiles are marked with it.
+        let mut areas = vec![None; self.tiles.len()];
+        let mut sizes = Vec::new();
+
+        for (index, position) in self.iter_positions().enumerate() {
+            if areas[index].is_some() || !is_empty(position) {
+                continue;
+            }
+
+            let area = sizes.len();
+            let mut revealed = HashSet::new();
+            let mut stack = vec![position];
+            areas[index] = Some(area);
+
+            while let Some(position) = stack.pop() {
+                revealed.insert(position);
+
+                for near in self.get_indices_near(position).unwrap() {
+                    let index = self.index_of(near).unwrap();
+                    revealed.insert(near);
+
+                    if areas[index].is_none() && is_empty(near) {
+                        areas[index] = Some(area);
+                        stack.push(near);
+                    }
+                }
+            }
+
+            sizes.push(revealed.len());
+        }
+
+        self.iter_positions()
+            .zip(areas)
+            .filter_map(|(position, area)| Some((position, sizes[area?])))
+            .collect()
+    }
+
+    /// Move any mines on or around a tile to random places away from
+    /// it, if there's room for them, so that it's a zero.
+    fn clear_mines_around(&mut self, position: Position, rng: &mut impl Rng) {
+        let mut around = self.get_indices_near(position).unwrap();
+        around.push(position);
+
+        let mines = around
+            .iter()
+            .filter(|&&position| self.has_mine_at(position).unwrap())
+            .count();
+
+        let space: Vec<usize> = self
+            .iter_tiles()
+            .enumerate()
+            .filter(|&(index, tile)| {
+                !tile.has_mine && !tile.is_void && !around.contains(&self.position_of(index))
+            })
+            .map(|(index, _)| index)
+            .collect();
+
+        if mines == 0 || space.len() < mines {
+            return;
+        }
+
+        for &position in around.iter() {
+            self.get_tile_mut(position).unwrap().has_mine = false;
+        }
+
+        for index in rand::seq::index::sample(rng, space.len(), mines).iter() {
+            self.tiles[space[index]].has_mine = true;
+        }
+
+        self.tiles_changed();
+    }
+
+    /// Place the mines afresh, on a field that hasn't been played on.
+    fn replace_mines(&mut self, rng: &mut impl Rng) {
+        let mines = self.mines;
+
+        for tile in self.iter_mut_tiles() {
+            tile.has_mine = false;
+        }
+
+        self.mines = 0;
+        self.populate_with(mines, rng).unwrap();
+    }
+
+    /// Perform what's known as a "chording" move.
+    ///
+    /// This is where a tile is surrounded by as many flags
+    /// as the number it shows. All other tiles are then
+    /// assumed to be safe, and are uncovered. If any of the
+    /// flags are wrong, that means digging up a mine.
+    ///
+    /// In a flagless game, the mines that the revealed numbers give
+    /// away stand in for the flags.
+    ///
+    /// Returns the tiles that were opened, which is none at all if the
+    /// flags don't add up.
+    pub fn do_chord(&mut self, position: Position) -> Result<Vec<Position>, MineFieldError> {
+        let this_tile = self.get_tile(position)?;
+        if this_tile.state != TileState::Visible {
+            return Ok(vec![]);
+        }
+
+        // only go by what the player can see: the number and the flags.
+        let number = self.count_mines_near(position)?;
+        let mut nearby_flags = 0;
+
+        let mut hidden_indices = Vec::new();
+        let obvious = match self.rules.no_flags {
+            true => self.obvious_mines(),
+            false => Vec::new(),
+        };
+
+        for near in self.get_indices_near(position)? {
+            match self.get_tile_state(near)? {
+                TileState::Flagged => nearby_flags += 1,
+                TileState::Hidden if obvious.contains(&near) => nearby_flags += 1,
+                TileState::Hidden => hidden_indices.push(near),
+                TileState::Visible => (),
+            }
+        }
+
+        // if they are equal, clear everything else around the tile.
+        let mut opened = Vec::new();
+
+        if nearby_flags == number {
+            let cascade = self.rules.chord_cascade;
+
+            for near in hidden_indices {
+                opened.extend(self.reveal(near, cascade)?);
+            }
+        }
+
+        Ok(opened)
+    }
+
+    /// Cover every tile back up, leaving the mines where they are.
+    pub fn cover_all(&mut self) {
+        for index in 0..self.tiles.len() {
+            self.change_state(index, TileState::Hidden);
+        }
+    }
+
+    /// Flag every mine that has been dug up, returning where they were.
+    ///
+    /// This gets the mines out of the way, so play can carry on past them.
+    pub fn flag_dug_mines(&mut self) -> Vec<Position> {
+        let mut flagged = Vec::new();
+
+        for index in 0..self.tiles.len() {
+            if self.tiles[index].has_mine && self.tiles[index].state == TileState::Visible {
+                self.change_state(index, TileState::Flagged);
+                flagged.push(self.position_of(index));
+            }
+        }
+
+        flagged
+    }
+
+    /// Flag every hidden tile that must be a mine, going by a visible
+    /// number with only as many covered tiles around it as its count.
+    /// Returns where the flags were placed.
+    pub fn flag_obvious_mines(&mut self) -> Vec<Position> {
+        let mut flagged = Vec::new();
+
+        for position in self.obvious_mines() {
+            if self.get_tile_state(position).unwrap() == TileState::Hidden {
+                self.set_tile_state(position, TileState::Flagged).unwrap();
+                flagged.push(position);
+            }
+        }
+
+        flagged
+    }
+
+    /// Finish off a field where every hidden tile left is a mine, so the
+    /// hidden tiles match the unflagged mines, by flagging them all.
+    /// Returns where the flags were placed, if there were any to place.
+    pub fn try_autocomplete(&mut self) -> Vec<Position> {
+        if self.get_state() == MineFieldState::Failed {
+            return vec![];
+        }
+
+        let hidden = self
+            .iter_tiles()
+            .filter(|tile| tile.state == TileState::Hidden && !tile.is_void);
+        let (tiles, mines) = hidden.fold((0, 0), |(tiles, mines), tile| {
+            (tiles + 1, mines + tile.has_mine as usize)
+        });
+
+        if tiles == 0 || tiles != mines {
+            return vec![];
+        }
+
+        let mut flagged = Vec::new();
+
+        for index in 0..self.tiles.len() {
+            if self.tiles[index].state == TileState::Hidden && !self.tiles[index].is_void {
+                self.change_state(index, TileState::Flagged);
+                flagged.push(self.position_of(index));
+            }
+        }
+
+        flagged
+    }
+
+    /// Make all hidden tiles visible, leaving flags where they are.
+    ///
+    /// The mines that were already dug up are the ones that lost the
+    /// game, so they're remembered to be drawn apart from the rest.
+    pub fn game_over(&mut self) {
+        self.fatal_mines = self
+            .iter_positions()
+            .filter(|&position| {
+                let tile = self.get_tile(position).unwrap();
+                tile.has_mine && tile.state == TileState::Visible
+            })
+            .collect();
+
+        for index in 0..self.tiles.len() {
+            if self.tiles[index].state == TileState::Hidden && !self.tiles[index].is_void {
+                self.change_state(index, TileState::Visible);
+            }
+        }
+
+        self.is_over = true;
+    }
+}
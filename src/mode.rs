@@ -0,0 +1,97 @@
+use std::time::Duration;
+
+/// How a game is played against the clock.
+#[derive(Debug, Default, Eq, PartialEq, Copy, Clone)]
+pub enum Mode {
+    /// Clear the field, however long it takes.
+    #[default]
+    Classic,
+
+    /// Clear as many boards as possible before the time runs out,
+    /// moving straight on to a new board after each one.
+    TimeAttack(Duration),
+
+    /// Clear the field before the time runs out, or lose.
+    Countdown(Duration),
+
+    /// Clear for as long as you like on a field that grows whenever
+    /// you get close to its edge, until you dig up a mine.
+    Endless,
+}
+
+impl Mode {
+    /// How long a round can last, if there's a limit.
+    pub fn time_limit(self) -> Option<Duration> {
+        match self {
+            Mode::Classic | Mode::Endless => None,
+            Mode::TimeAttack(limit) | Mode::Countdown(limit) => Some(limit),
+        }
+    }
+
+    /// How long is left after some time has passed, if there's a limit.
+    pub fn time_left(self, elapsed: Duration) -> Option<Duration> {
+        self.time_limit().map(|limit| limit.saturating_sub(elapsed))
+    }
+
+    /// Check if the time has run out.
+    pub fn is_out_of_time(self, elapsed: Duration) -> bool {
+        self.time_left(elapsed) == Some(Duration::ZERO)
+    }
+
+    /// Check if a cleared board moves straight on to a new one, on the
+    /// same timer.
+    pub fn carries_on(self) -> bool {
+        matches!(self, Mode::TimeAttack(_))
+    }
+
+    /// Check if a cleared board can set a best time, since it was timed
+    /// alone on a field of its usual size.
+    pub fn sets_best_times(self) -> bool {
+        matches!(self, Mode::Classic | Mode::Countdown(_))
+    }
+
+    /// Describe the mode beside the field, if it's not a classic game.
+    pub fn describe(self) -> Option<String> {
+        match self {
+            Mode::Classic => None,
+            Mode::TimeAttack(limit) => Some(format!("time attack: {}", describe_limit(limit))),
+            Mode::Countdown(limit) => Some(format!("countdown: {}", describe_limit(limit))),
+            Mode::Endless => Some(String::from("endless: the field grows")),
+        }
+    }
+}
+
+/// Describe a time limit, in minutes if it's a whole amount of them.
+fn describe_limit(limit: Duration) -> String {
+    match limit.as_secs() {
+        60 => String::from("1 minute"),
+        seconds if seconds % 60 == 0 => format!("{} minutes", seconds / 60),
+        seconds => format!("{} seconds", seconds),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn time_runs_out_on_limited_modes() {
+        let attack = Mode::TimeAttack(Duration::from_secs(120));
+        let countdown = Mode::Countdown(Duration::from_secs(45));
+
+        assert_eq!(
+            attack.time_left(Duration::from_secs(100)),
+            Some(Duration::from_secs(20))
+        );
+        assert!(!attack.is_out_of_time(Duration::from_secs(119)));
+        assert!(attack.is_out_of_time(Duration::from_secs(130)));
+        assert!(attack.carries_on() && !countdown.carries_on());
+        assert!(countdown.sets_best_times() && !Mode::Endless.sets_best_times());
+
+        assert_eq!(Mode::Classic.time_left(Duration::from_secs(9999)), None);
+        assert!(!Mode::Classic.is_out_of_time(Duration::from_secs(9999)));
+
+        assert_eq!(attack.describe().unwrap(), "time attack: 2 minutes");
+        assert_eq!(countdown.describe().unwrap(), "countdown: 45 seconds");
+    }
+}
@@ -0,0 +1,230 @@
+use std::iter;
+
+#[cfg(any(feature = "termion", feature = "crossterm"))]
+use crate::terminal::Key;
+
+use crate::minefield::Edge;
+use crate::position::Position;
+
+/// How far the cursor jumps with shift held on an arrow.
+pub const JUMP: usize = 5;
+
+/// A way of moving the cursor around the field.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum Motion {
+    Up(usize),
+    Down(usize),
+    Left(usize),
+    Right(usize),
+    RowStart,
+    RowEnd,
+
+    /// Jump to a row, counting from 1, or the first row if none is given.
+    First(Option<usize>),
+
+    /// Jump to a row, counting from 1, or the last row if none is given.
+    Last(Option<usize>),
+
+    /// Jump to the nearest hidden tile towards an edge, if there is one.
+    Hidden(Edge),
+}
+
+impl Motion {
+    /// Work out where the cursor ends up on a field of the given size,
+    /// going by which tiles are hidden.
+    pub fn apply(
+        self,
+        position: Position,
+        height: usize,
+        width: usize,
+        is_hidden: impl Fn(Position) -> bool,
+    ) -> Position {
+        let Position { row, column } = position;
+        let last_row = height.saturating_sub(1);
+        let last_column = width.saturating_sub(1);
+        let to_row = |line: usize| line.saturating_sub(1).min(last_row);
+
+        let (row, column) = match self {
+            Motion::Up(count) => (row.saturating_sub(count), column),
+            Motion::Down(count) => (row.saturating_add(count).min(last_row), column),
+            Motion::Left(count) => (row, column.saturating_sub(count)),
+            Motion::Right(count) => (row, column.saturating_add(count).min(last_column)),
+            Motion::RowStart => (row, 0),
+            Motion::RowEnd => (row, last_column),
+            Motion::First(line) => (line.map_or(0, to_row), column),
+            Motion::Last(line) => (line.map_or(last_row, to_row), column),
+            Motion::Hidden(edge) => {
+                // walk towards the edge a tile at a time, stopping on a hidden one.
+                let tiles = iter::successors(Some(position), |&Position { row, column }| {
+                    let (row, column) = match edge {
+                        Edge::Top => (row.checked_sub(1)?, column),
+                        Edge::Bottom => (row + 1, column),
+                        Edge::Left => (row, column.checked_sub(1)?),
+                        Edge::Right => (row, column + 1),
+                    };
+
+                    Some(Position::new(row, column)).filter(|tile| tile.is_within(height, width))
+                });
+
+                match tiles.skip(1).find(|&tile| is_hidden(tile)) {
+                    Some(tile) => (tile.row, tile.column),
+                    None => (row, column),
+                }
+            }
+        };
+
+        Position::new(row, column)
+    }
+}
+
+/// Collects vim-style count prefixes and multi-key motions
+/// like `gg` as the keys come in.
+#[cfg(any(feature = "termion", feature = "crossterm"))]
+#[derive(Debug, Default)]
+pub struct MotionKeys {
+    count: Option<usize>,
+    pending_g: bool,
+}
+
+#[cfg(any(feature = "termion", feature = "crossterm"))]
+impl MotionKeys {
+    /// Create a reader with nothing typed yet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Check whether a count or half-typed motion is waiting for more keys.
+    pub fn is_pending(&self) -> bool {
+        self.count.is_some() || self.pending_g
+    }
+
+    /// Throw away anything typed so far.
+    pub fn reset(&mut self) {
+        self.count = None;
+        self.pending_g = false;
+    }
+
+    /// Feed in a key, getting back a motion once one has been completed.
+    ///
+    /// Keys that aren't part of a motion return `None` and
+    /// cancel any count that was being typed.
+    pub fn feed(&mut self, key: Key) -> Option<Motion> {
+        let count = self.count;
+        let pending_g = self.pending_g;
+        self.reset();
+
+        let times = count.unwrap_or(1);
+
+        match key {
+            // a leading zero is a motion of its own, not part of a count.
+            Key::Char(digit @ '0'..='9') if count.is_some() || digit != '0' => {
+                let digit = digit.to_digit(10).unwrap() as usize;
+                self.count = Some(count.unwrap_or(0).saturating_mul(10).saturating_add(digit));
+                None
+            }
+
+            Key::Char('g') if pending_g => Some(Motion::First(count)),
+            Key::Char('g') => {
+                self.count = count;
+                self.pending_g = true;
+                None
+            }
+
+            Key::Up | Key::Char('k') => Some(Motion::Up(times)),
+            Key::Down | Key::Char('j') => Some(Motion::Down(times)),
+            Key::Left | Key::Char('h') => Some(Motion::Left(times)),
+            Key::Right | Key::Char('l') => Some(Motion::Right(times)),
+            Key::Char('0') => Some(Motion::RowStart),
+            Key::Char('$') => Some(Motion::RowEnd),
+            Key::Char('G') => Some(Motion::Last(count)),
+
+            // shift jumps further, and ctrl to the next hidden tile.
+            Key::ShiftUp => Some(Motion::Up(times.saturating_mul(JUMP))),
+            Key::ShiftDown => Some(Motion::Down(times.saturating_mul(JUMP))),
+            Key::ShiftLeft => Some(Motion::Left(times.saturating_mul(JUMP))),
+            Key::ShiftRight => Some(Motion::Right(times.saturating_mul(JUMP))),
+            Key::CtrlUp => Some(Motion::Hidden(Edge::Top)),
+            Key::CtrlDown => Some(Motion::Hidden(Edge::Bottom)),
+            Key::CtrlLeft => Some(Motion::Hidden(Edge::Left)),
+            Key::CtrlRight => Some(Motion::Hidden(Edge::Right)),
+
+            // and these go straight to the edges of the field.
+            Key::Home => Some(Motion::RowStart),
+            Key::End => Some(Motion::RowEnd),
+            Key::PageUp => Some(Motion::First(None)),
+            Key::PageDown => Some(Motion::Last(None)),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(all(test, any(feature = "termion", feature = "crossterm")))]
+mod tests {
+    use super::*;
+
+    fn feed_all(keys: &str) -> Vec<Motion> {
+        let mut motion_keys = MotionKeys::new();
+        keys.chars()
+            .filter_map(|c| motion_keys.feed(Key::Char(c)))
+            .collect()
+    }
+
+    #[test]
+    fn counts_and_jumps() {
+        assert_eq!(
+            feed_all("5l10j0$ggG3Gx2k"),
+            vec![
+                Motion::Right(5),
+                Motion::Down(10),
+                Motion::RowStart,
+                Motion::RowEnd,
+                Motion::First(None),
+                Motion::Last(None),
+                Motion::Last(Some(3)),
+                Motion::Up(2),
+            ]
+        );
+
+        // anything else cancels a half-typed count or motion.
+        assert_eq!(feed_all("5x1gfh"), vec![Motion::Left(1)]);
+
+        assert_eq!(
+            Motion::Right(100).apply(Position::new(2, 3), 8, 10, |_| false),
+            Position::new(2, 9)
+        );
+        assert_eq!(
+            Motion::Up(100).apply(Position::new(2, 3), 8, 10, |_| false),
+            Position::new(0, 3)
+        );
+        assert_eq!(
+            Motion::Last(Some(3)).apply(Position::new(6, 3), 8, 10, |_| false),
+            Position::new(2, 3)
+        );
+        assert_eq!(
+            Motion::First(Some(99)).apply(Position::new(0, 3), 8, 10, |_| false),
+            Position::new(7, 3)
+        );
+    }
+
+    #[test]
+    fn modified_arrows_jump_further() {
+        let mut motion_keys = MotionKeys::new();
+        motion_keys.feed(Key::Char('2'));
+        assert_eq!(motion_keys.feed(Key::ShiftRight), Some(Motion::Right(10)));
+        assert_eq!(motion_keys.feed(Key::PageDown), Some(Motion::Last(None)));
+        assert_eq!(
+            motion_keys.feed(Key::CtrlLeft),
+            Some(Motion::Hidden(Edge::Left))
+        );
+
+        // only the tiles in columns 1 and 6 of the row are hidden.
+        let is_hidden = |tile: Position| tile.column == 1 || tile.column == 6;
+        let jump =
+            |edge, column| Motion::Hidden(edge).apply(Position::new(0, column), 1, 8, is_hidden);
+
+        assert_eq!(jump(Edge::Right, 1), Position::new(0, 6));
+        assert_eq!(jump(Edge::Left, 6), Position::new(0, 1));
+        assert_eq!(jump(Edge::Left, 1), Position::new(0, 1));
+        assert_eq!(jump(Edge::Right, 7), Position::new(0, 7));
+    }
+}
@@ -0,0 +1,301 @@
+use std::fmt;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+use std::time::Duration;
+
+use crate::cli;
+use crate::difficulty::Difficulty;
+use crate::versus::Outcome;
+
+/// The port a race is hosted on, unless another is given.
+pub const DEFAULT_PORT: u16 = 3999;
+
+/// How a player's race is going.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum Status {
+    Playing,
+    Won,
+    Lost,
+}
+
+impl Status {
+    fn name(self) -> &'static str {
+        match self {
+            Status::Playing => "playing",
+            Status::Won => "won",
+            Status::Lost => "lost",
+        }
+    }
+
+    fn parse(name: &str) -> Option<Self> {
+        match name {
+            "playing" => Some(Status::Playing),
+            "won" => Some(Status::Won),
+            "lost" => Some(Status::Lost),
+            _ => None,
+        }
+    }
+}
+
+/// How far a player has got through the board.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub struct Progress {
+    pub revealed: usize,
+    pub total: usize,
+    pub time_taken: Duration,
+    pub status: Status,
+}
+
+impl Progress {
+    /// The race as a turn, to be compared with the other player's.
+    pub fn outcome(&self) -> Outcome {
+        Outcome {
+            is_cleared: self.status == Status::Won,
+            time_taken: self.time_taken,
+            progress: self.revealed as f64 / self.total.max(1) as f64,
+        }
+    }
+}
+
+/// A line sent between the two players of a race.
+///
+/// The host sends the board as soon as the other player joins, like
+/// `board 1234 30x16_99 square`, and then both send their progress
+/// after every dig, like `progress 120 381 45200 playing`, with the
+/// time in milliseconds.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum Message {
+    Board {
+        seed: u64,
+        difficulty: Difficulty,
+        hex: bool,
+    },
+    Progress(Progress),
+}
+
+impl Message {
+    pub fn parse(line: &str) -> Result<Self, String> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let number = |word: &str| {
+            word.parse()
+                .map_err(|_| format!("expected a number, got '{}'.", word))
+        };
+
+        match words[..] {
+            ["board", seed, size, shape] => {
+                let (width, height, mines) = size
+                    .split_once('x')
+                    .and_then(|(width, rest)| Some((width, rest.split_once('_')?)))
+                    .and_then(|(width, (height, mines))| {
+                        Some((
+                            width.parse().ok()?,
+                            height.parse().ok()?,
+                            mines.parse().ok()?,
+                        ))
+                    })
+                    .ok_or_else(|| format!("expected a size like 'WxH_M', got '{}'.", size))?;
+
+                Ok(Message::Board {
+                    seed: seed
+                        .parse()
+                        .map_err(|_| format!("expected a seed, got '{}'.", seed))?,
+                    difficulty: cli::validate(width, height, mines).map_err(|e| e.to_string())?,
+                    hex: shape == "hex",
+                })
+            }
+            ["progress", revealed, total, millis, status] => Ok(Message::Progress(Progress {
+                revealed: number(revealed)?,
+                total: number(total)?,
+                time_taken: Duration::from_millis(number(millis)? as u64),
+                status: Status::parse(status)
+                    .ok_or_else(|| format!("unknown status '{}'.", status))?,
+            })),
+            _ => Err(format!("unexpected message '{}'.", line)),
+        }
+    }
+}
+
+impl fmt::Display for Message {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Message::Board {
+                seed,
+                difficulty,
+                hex,
+            } => {
+                let (width, height, mines) = difficulty.dimensions();
+                let shape = if *hex { "hex" } else { "square" };
+                write!(f, "board {} {}x{}_{} {}", seed, width, height, mines, shape)
+            }
+            Message::Progress(progress) => write!(
+                f,
+                "progress {} {} {} {}",
+                progress.revealed,
+                progress.total,
+                progress.time_taken.as_millis(),
+                progress.status.name()
+            ),
+        }
+    }
+}
+
+/// The other player of a race, on the far end of a connection.
+///
+/// Their messages are read on their own thread, so that the game can
+/// check for news without waiting on them.
+pub struct Rival {
+    stream: TcpStream,
+    messages: Receiver<Message>,
+    progress: Option<Progress>,
+    is_connected: bool,
+}
+
+impl Rival {
+    fn new(stream: TcpStream) -> io::Result<Self> {
+        let reader = BufReader::new(stream.try_clone()?);
+        let (sender, messages) = mpsc::channel();
+
+        thread::spawn(move || {
+            // a line that can't be read ends the race as badly as a
+            // dropped connection, so both just stop the reading.
+            for line in reader.lines() {
+                match line.map(|line| Message::parse(&line)) {
+                    Ok(Ok(message)) if sender.send(message).is_ok() => (),
+                    _ => break,
+                }
+            }
+        });
+
+        Ok(Self {
+            stream,
+            messages,
+            progress: None,
+            is_connected: true,
+        })
+    }
+
+    /// Wait for someone to join a race on a port.
+    pub fn host(port: u16) -> io::Result<Self> {
+        let listener = TcpListener::bind(("0.0.0.0", port))?;
+        let (stream, _) = listener.accept()?;
+
+        Self::new(stream)
+    }
+
+    /// Join a race hosted at an address, like `localhost:3999`.
+    pub fn join(address: &str) -> io::Result<Self> {
+        Self::new(TcpStream::connect(address)?)
+    }
+
+    /// Send a message to the other player.
+    pub fn send(&mut self, message: Message) -> io::Result<()> {
+        writeln!(self.stream, "{}", message)
+    }
+
+    /// Wait for the host to send the board: its seed, its difficulty, and
+    /// whether it's hex.
+    pub fn board(&mut self) -> io::Result<(u64, Difficulty, bool)> {
+        match self.messages.recv() {
+            Ok(Message::Board {
+                seed,
+                difficulty,
+                hex,
+            }) => Ok((seed, difficulty, hex)),
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "the host didn't send a board",
+            )),
+        }
+    }
+
+    /// Catch up on the other player's messages. Returns whether
+    /// anything has changed since the last check.
+    pub fn poll(&mut self) -> bool {
+        let mut is_changed = false;
+
+        loop {
+            match self.messages.try_recv() {
+                Ok(Message::Progress(progress)) => self.progress = Some(progress),
+                Ok(Message::Board { .. }) => continue,
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    is_changed |= self.is_connected;
+                    self.is_connected = false;
+                    break;
+                }
+            }
+
+            is_changed = true;
+        }
+
+        is_changed
+    }
+
+    /// How far the other player has got, as of their last message.
+    #[inline]
+    pub fn progress(&self) -> Option<Progress> {
+        self.progress
+    }
+
+    /// Check if the other player has nothing more to send, since
+    /// they've finished or gone.
+    pub fn is_done(&self) -> bool {
+        !self.is_connected || self.progress.is_some_and(|p| p.status != Status::Playing)
+    }
+
+    /// Describe how the other player is getting on.
+    pub fn describe(&self) -> String {
+        let percent = |progress: &Progress| progress.outcome().progress * 100.0;
+
+        match (&self.progress, self.is_connected) {
+            (None, true) => String::from("rival: ready"),
+            (None, false) => String::from("rival: gone"),
+            (Some(progress), _) if progress.status == Status::Won => format!(
+                "rival: cleared in {:.1}s",
+                progress.time_taken.as_secs_f64()
+            ),
+            (Some(progress), _) if progress.status == Status::Lost => {
+                format!("rival: out at {:.0}%", percent(progress))
+            }
+            (Some(progress), true) => format!("rival: {:.0}% cleared", percent(progress)),
+            (Some(progress), false) => format!("rival: gone at {:.0}%", percent(progress)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn messages_survive_the_trip() {
+        let messages = [
+            Message::Board {
+                seed: 1234,
+                difficulty: Difficulty::Custom {
+                    width: 30,
+                    height: 16,
+                    mines: 99,
+                },
+                hex: false,
+            },
+            Message::Progress(Progress {
+                revealed: 120,
+                total: 381,
+                time_taken: Duration::from_millis(45200),
+                status: Status::Playing,
+            }),
+        ];
+
+        for message in messages.iter() {
+            assert_eq!(Message::parse(&message.to_string()), Ok(*message));
+        }
+
+        assert_eq!(messages[0].to_string(), "board 1234 30x16_99 square");
+        assert!(Message::parse("board 1 10x10_100 square").is_err());
+        assert!(Message::parse("progress 1 2 3 winning").is_err());
+        assert!(Message::parse("hello").is_err());
+    }
+}
@@ -0,0 +1,119 @@
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use crate::config::Config;
+
+/// Reference times for the standard difficulties, roughly the world
+/// records for the classic boards of the same size and mine count.
+const BUILT_IN_RECORDS: [(&str, u64); 3] = [
+    ("beginner", 1_000),
+    ("intermediate", 7_000),
+    ("expert", 28_000),
+];
+
+/// What the player's pace is compared against.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum Reference {
+    /// The record time for the board, if there is one.
+    Record,
+
+    /// The player's own best time on the board.
+    Best,
+
+    /// A fixed time for any board.
+    Time(Duration),
+}
+
+impl Reference {
+    /// Parse a reference from the command line: `record`,
+    /// `best`, or a time in seconds.
+    pub fn parse(text: &str) -> Option<Self> {
+        match text {
+            "record" => Some(Reference::Record),
+            "best" => Some(Reference::Best),
+            _ => match text.parse::<f64>() {
+                Ok(seconds) if seconds > 0.0 && seconds.is_finite() => {
+                    Some(Reference::Time(Duration::from_secs_f64(seconds)))
+                }
+                _ => None,
+            },
+        }
+    }
+
+    /// A short name for the reference, like "vs record".
+    pub fn describe(&self) -> String {
+        match self {
+            Reference::Record => String::from("vs record"),
+            Reference::Best => String::from("vs best"),
+            Reference::Time(time) => format!("vs {}s", time.as_secs_f64()),
+        }
+    }
+}
+
+/// Record times for each board, keyed in the same way as best times.
+///
+/// The built-in times can be changed, and times for other boards added,
+/// with `record.<board> = <seconds>` lines in the config file.
+#[derive(Debug)]
+pub struct Records {
+    times: BTreeMap<String, Duration>,
+}
+
+impl Records {
+    /// Gather the built-in record times, along with any from the config.
+    pub fn load(config: &Config) -> Self {
+        let mut times: BTreeMap<String, Duration> = BUILT_IN_RECORDS
+            .iter()
+            .map(|&(key, millis)| (String::from(key), Duration::from_millis(millis)))
+            .collect();
+
+        for (key, value) in config.with_prefix("record.") {
+            if let Some(Reference::Time(time)) = Reference::parse(value) {
+                times.insert(String::from(key), time);
+            }
+        }
+
+        Self { times }
+    }
+
+    /// The record time for a board, if there is one.
+    pub fn get(&self, board_key: &str) -> Option<Duration> {
+        self.times.get(board_key).copied()
+    }
+}
+
+/// Work out how far behind (positive) or ahead (negative) of a reference
+/// time the player is, in seconds, assuming it was kept at a steady pace.
+pub fn delta(elapsed: Duration, progress: f64, reference: Duration) -> f64 {
+    elapsed.as_secs_f64() - reference.as_secs_f64() * progress
+}
+
+/// Describe a pace delta, like "+3.2s".
+pub fn describe_delta(delta: f64) -> String {
+    format!("{:+.1}s", delta)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn references_and_records() {
+        assert_eq!(Reference::parse("best"), Some(Reference::Best));
+        assert_eq!(
+            Reference::parse("12.5"),
+            Some(Reference::Time(Duration::from_millis(12_500)))
+        );
+        assert_eq!(Reference::parse("-3"), None);
+        assert_eq!(Reference::parse("fast"), None);
+
+        let records = Records::load(&Config::parse("record.expert = 40\nrecord.8x8_10 = 3"));
+        assert_eq!(records.get("beginner"), Some(Duration::from_secs(1)));
+        assert_eq!(records.get("expert"), Some(Duration::from_secs(40)));
+        assert_eq!(records.get("8x8_10"), Some(Duration::from_secs(3)));
+
+        // halfway through a 20 second reference, 12 seconds in.
+        let behind = delta(Duration::from_secs(12), 0.5, Duration::from_secs(20));
+        assert_eq!(describe_delta(behind), "+2.0s");
+    }
+}
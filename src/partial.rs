@@ -0,0 +1,111 @@
+use crate::view::{BoardView, Cell};
+
+/// A board position where only the player's view is known.
+///
+/// This is what gets pasted in for analysis: revealed numbers and
+/// flags, with no idea of where the rest of the mines are.
+pub struct PartialBoard {
+    width: usize,
+    height: usize,
+    mines: Option<usize>,
+    cells: Vec<Cell>,
+}
+
+/// Read a single character of a text grid.
+fn parse_cell(c: char) -> Option<Cell> {
+    match c {
+        '.' | '#' | '?' => Some(Cell::Hidden),
+        'F' | 'f' | '~' => Some(Cell::Flagged),
+        '-' | '_' => Some(Cell::Revealed(0)),
+        _ => c.to_digit(9).map(|n| Cell::Revealed(n as usize)),
+    }
+}
+
+impl PartialBoard {
+    /// Parse a text grid, one line per row.
+    ///
+    /// Hidden tiles are `.`, `#` or `?`; flags are `F`, `f` or `~`;
+    /// revealed tiles are digits, with `-` or `_` also meaning zero.
+    /// Whitespace within lines and blank lines are ignored.
+    ///
+    /// `*` is refused, since it's a deduced mine in what `analyze`
+    /// writes out and a hidden mine in a layout, but neither can be
+    /// told apart from a flag here.
+    pub fn parse(text: &str) -> Result<Self, &'static str> {
+        let mut cells = Vec::new();
+        let mut width = None;
+        let mut height = 0;
+
+        for line in text.lines() {
+            if line.contains('*') {
+                return Err("'*' isn't a flag here: use 'F', 'f' or '~' instead.");
+            }
+
+            let row: Option<Vec<Cell>> = line
+                .chars()
+                .filter(|c| !c.is_whitespace())
+                .map(parse_cell)
+                .collect();
+
+            let row = row.ok_or("Unknown character in grid.")?;
+
+            if row.is_empty() {
+                continue;
+            }
+
+            if *width.get_or_insert(row.len()) != row.len() {
+                return Err("Every row of the grid must be the same length.");
+            }
+
+            cells.extend(row);
+            height += 1;
+        }
+
+        match width {
+            Some(width) => Ok(Self {
+                width,
+                height,
+                mines: None,
+                cells,
+            }),
+            None => Err("The grid is empty."),
+        }
+    }
+
+    /// Give the total amount of mines, for more accurate probabilities.
+    pub fn set_mines(&mut self, mines: usize) {
+        self.mines = Some(mines);
+    }
+}
+
+impl BoardView for PartialBoard {
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn height(&self) -> usize {
+        self.height
+    }
+
+    fn mines(&self) -> Option<usize> {
+        self.mines
+    }
+
+    fn cell(&self, row: usize, column: usize) -> Cell {
+        self.cells[row * self.width + column]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stars_are_not_read_as_flags() {
+        assert!(PartialBoard::parse("1 *\n. .").is_err());
+
+        let board = PartialBoard::parse("1 F\n. ~").unwrap();
+        assert_eq!(board.cell(0, 1), Cell::Flagged);
+        assert_eq!(board.cell(1, 1), Cell::Flagged);
+    }
+}
@@ -0,0 +1,108 @@
+/// A tile on the field, counted from the top left.
+///
+/// Keeping the row and column named together stops them
+/// from being swapped by accident on the way through.
+#[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Copy, Clone)]
+pub struct Position {
+    pub row: usize,
+    pub column: usize,
+}
+
+impl Position {
+    /// Create a position from a row and column.
+    #[inline]
+    pub fn new(row: usize, column: usize) -> Self {
+        Self { row, column }
+    }
+
+    /// Check if the position is on a field of the given size.
+    #[inline]
+    pub fn is_within(self, height: usize, width: usize) -> bool {
+        self.row < height && self.column < width
+    }
+
+    /// Name a column in letters, like the columns of a spreadsheet:
+    /// `A` to `Z`, then `AA` onwards.
+    pub fn column_letters(column: usize) -> String {
+        let mut letters = Vec::new();
+        let mut column = column + 1;
+
+        while column > 0 {
+            column -= 1;
+            letters.push(char::from(b'A' + (column % 26) as u8));
+            column /= 26;
+        }
+
+        letters.iter().rev().collect()
+    }
+
+    /// Name the position in A1 notation, like a spreadsheet cell: the
+    /// column in letters, then the row counted from 1.
+    pub fn to_a1(self) -> String {
+        format!("{}{}", Self::column_letters(self.column), self.row + 1)
+    }
+
+    /// Read a position written in A1 notation, in either case, like
+    /// `D7` or `aa10`.
+    pub fn from_a1(text: &str) -> Option<Self> {
+        let text = text.trim();
+        let (letters, digits) = text.split_at(text.find(|c: char| !c.is_ascii_alphabetic())?);
+
+        if letters.is_empty() || !digits.bytes().all(|byte| byte.is_ascii_digit()) {
+            return None;
+        }
+
+        let column = letters.bytes().try_fold(0usize, |column, letter| {
+            let value = (letter.to_ascii_uppercase() - b'A') as usize + 1;
+            column.checked_mul(26)?.checked_add(value)
+        })?;
+        let row: usize = digits.parse().ok()?;
+
+        Some(Self::new(row.checked_sub(1)?, column - 1))
+    }
+
+    /// Every position touching this one, diagonals included,
+    /// that's on a field of the given size.
+    pub fn neighbours(self, height: usize, width: usize) -> impl Iterator<Item = Position> {
+        let rows = self.row.saturating_sub(1)..=self.row.saturating_add(1);
+        let columns = self.column.saturating_sub(1)..=self.column.saturating_add(1);
+
+        rows.flat_map(move |row| {
+            columns
+                .clone()
+                .map(move |column| Position::new(row, column))
+        })
+        .filter(move |&position| position != self && position.is_within(height, width))
+    }
+}
+
+impl From<(usize, usize)> for Position {
+    fn from((row, column): (usize, usize)) -> Self {
+        Self::new(row, column)
+    }
+}
+
+impl From<Position> for (usize, usize) {
+    fn from(position: Position) -> Self {
+        (position.row, position.column)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a1_names_are_read_back() {
+        for position in [(0, 0), (6, 2), (0, 25), (9, 26), (15, 29), (99, 701)] {
+            let position = Position::from(position);
+            assert_eq!(Position::from_a1(&position.to_a1()), Some(position));
+        }
+
+        assert_eq!(Position::from_a1(" d7 "), Some(Position::new(6, 3)));
+        assert_eq!(Position::from_a1("A0"), None);
+        assert_eq!(Position::from_a1("7D"), None);
+        assert_eq!(Position::from_a1("D"), None);
+        assert_eq!(Position::from_a1("D+7"), None);
+    }
+}
@@ -0,0 +1,301 @@
+use serde::{Deserialize, Serialize};
+
+use crate::minefield::{MineField, MineFieldState};
+use crate::position::Position;
+use crate::tile::TileState;
+
+/// How far a game has got.
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Status {
+    InProgress,
+    Won,
+    Lost,
+}
+
+impl From<MineFieldState> for Status {
+    fn from(state: MineFieldState) -> Self {
+        match state {
+            MineFieldState::InProgress => Status::InProgress,
+            MineFieldState::Cleared => Status::Won,
+            MineFieldState::Failed => Status::Lost,
+        }
+    }
+}
+
+/// A tile that the player can't see the number of.
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Cover {
+    Hidden,
+    Flag,
+    Mine,
+}
+
+/// A tile as the player sees it. A revealed tile is written as the
+/// amount of mines around it, and any other tile as the name of what
+/// covers it: `"hidden"`, `"flag"` or `"mine"` for a dug mine.
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Tile {
+    Revealed(u8),
+    Covered(Cover),
+}
+
+/// The state of a game, as written after each move of a headless
+/// game with `--json`.
+///
+/// Each state is a JSON object on a line of its own, like this:
+///
+/// ```json
+/// {"width":3,"height":2,"status":"in-progress","mines":2,"flags":1,
+///  "mines_remaining":1,"tiles":[["flag",2,"hidden"],["hidden",2,null]]}
+/// ```
+///
+/// (without the line break.) `tiles` has a list for each row, with
+/// `null` for void tiles outside the shape of the field.
+#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
+pub struct GameState {
+    pub width: usize,
+    pub height: usize,
+    pub status: Status,
+    pub mines: usize,
+    pub flags: usize,
+
+    /// The amount of mines without flags on them, if every flag is
+    /// right. This goes below zero when there are too many flags.
+    pub mines_remaining: isize,
+
+    pub tiles: Vec<Vec<Option<Tile>>>,
+}
+
+impl GameState {
+    /// Take the state of a game from its field.
+    pub fn of(field: &MineField) -> Self {
+        let tiles = (0..field.height())
+            .map(|row| {
+                (0..field.width())
+                    .map(|column| {
+                        let position = Position::new(row, column);
+                        let tile = field.get_tile(position).unwrap();
+
+                        match (tile.is_void, tile.state, tile.has_mine) {
+                            (true, _, _) => None,
+                            (false, TileState::Hidden, _) => Some(Tile::Covered(Cover::Hidden)),
+                            (false, TileState::Flagged, _) => Some(Tile::Covered(Cover::Flag)),
+                            (false, TileState::Visible, true) => Some(Tile::Covered(Cover::Mine)),
+                            (false, TileState::Visible, false) => Some(Tile::Revealed(
+                                field.count_mines_near(position).unwrap() as u8,
+                            )),
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+
+        Self {
+            width: field.width(),
+            height: field.height(),
+            status: field.get_state().into(),
+            mines: field.mines(),
+            flags: field.flags(),
+            mines_remaining: field.mines() as isize - field.flags() as isize,
+            tiles,
+        }
+    }
+}
+
+/// The tiles that changed between two states of a game on the same
+/// field, as streamed to spectators after each move.
+///
+/// Each change is the row, column and new look of a tile, like this:
+///
+/// ```json
+/// {"status":"in-progress","flags":1,"mines_remaining":1,"changes":[[0,0,"flag"],[1,0,2]]}
+/// ```
+#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
+pub struct Diff {
+    pub status: Status,
+    pub flags: usize,
+    pub mines_remaining: isize,
+    pub changes: Vec<(usize, usize, Option<Tile>)>,
+}
+
+impl Diff {
+    /// Find what changed from one state to the next. Both have to be of
+    /// the same size of field.
+    pub fn between(old: &GameState, new: &GameState) -> Self {
+        let changes = new
+            .tiles
+            .iter()
+            .zip(old.tiles.iter())
+            .enumerate()
+            .flat_map(|(row, (new_row, old_row))| {
+                new_row
+                    .iter()
+                    .zip(old_row.iter())
+                    .enumerate()
+                    .filter(|(_, (new_tile, old_tile))| new_tile != old_tile)
+                    .map(move |(column, (&tile, _))| (row, column, tile))
+            })
+            .collect();
+
+        Self {
+            status: new.status,
+            flags: new.flags,
+            mines_remaining: new.mines_remaining,
+            changes,
+        }
+    }
+
+    /// Bring an old state up to date. Changes to tiles that aren't on
+    /// its field are left out.
+    pub fn apply(&self, state: &mut GameState) {
+        state.status = self.status;
+        state.flags = self.flags;
+        state.mines_remaining = self.mines_remaining;
+
+        for &(row, column, tile) in self.changes.iter() {
+            if let Some(old_tile) = state.tiles.get_mut(row).and_then(|r| r.get_mut(column)) {
+                *old_tile = tile;
+            }
+        }
+    }
+}
+
+/// What a game streams to spectators, a JSON object to a line: the
+/// whole state when they first watch or the field changes size, like
+/// `{"update":"state",...}` with the fields of a `GameState`, and then
+/// `{"update":"diff",...}` with the fields of a `Diff` after each move.
+/// The player's cursor is sent as `{"update":"cursor","row":1,"column":2}`
+/// whenever it moves.
+#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(tag = "update", rename_all = "kebab-case")]
+pub enum Update {
+    State(GameState),
+    Diff(Diff),
+    Cursor { row: usize, column: usize },
+}
+
+/// What's been sent to someone following a game from elsewhere, so that
+/// only what's changed since has to be sent next.
+#[derive(Debug, Default)]
+pub struct Feed {
+    state: Option<GameState>,
+    cursor: Option<Position>,
+}
+
+impl Feed {
+    /// The updates that bring a follower up to date with the field and
+    /// cursor, if anything has changed.
+    pub fn updates(&mut self, field: &MineField, cursor: Position) -> Vec<Update> {
+        let state = GameState::of(field);
+        let mut updates = Vec::new();
+
+        match &self.state {
+            Some(last) if *last == state => (),
+            Some(last) if (last.width, last.height) == (state.width, state.height) => {
+                updates.push(Update::Diff(Diff::between(last, &state)))
+            }
+            _ => updates.push(Update::State(state.clone())),
+        }
+
+        if self.cursor != Some(cursor) {
+            updates.push(Update::Cursor {
+                row: cursor.row,
+                column: cursor.column,
+            });
+        }
+
+        self.state = Some(state);
+        self.cursor = Some(cursor);
+        updates
+    }
+
+    /// Everything sent so far, all at once, for someone who has only
+    /// just started following.
+    pub fn catch_up(&self) -> Vec<Update> {
+        let state = self.state.clone().map(Update::State);
+        let cursor = self
+            .cursor
+            .map(|Position { row, column }| Update::Cursor { row, column });
+
+        state.into_iter().chain(cursor).collect()
+    }
+}
+
+/// A move sent by the guest of a co-op game to the host, who makes it
+/// on the one true field, as a line like `{"move":"dig","row":3,"column":4}`.
+/// The guest's cursor is sent as a `"cursor"` move whenever it moves, so
+/// the host can show where it is.
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Serialize, Deserialize)]
+#[serde(tag = "move", rename_all = "kebab-case")]
+pub enum Move {
+    Dig { row: usize, column: usize },
+    Flag { row: usize, column: usize },
+    Chord { row: usize, column: usize },
+    Cursor { row: usize, column: usize },
+}
+
+/// A move that couldn't be read or made, as written in place of the
+/// state by a headless game with `--json`: `{"error":"..."}`.
+#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
+pub struct Error {
+    pub error: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn state_matches_the_documented_schema() {
+        let field = MineField::from_layout("F2.\n*2").unwrap();
+        let json = serde_json::to_string(&GameState::of(&field)).unwrap();
+
+        assert_eq!(
+            json,
+            "{\"width\":3,\"height\":2,\"status\":\"in-progress\",\"mines\":2,\"flags\":1,\
+             \"mines_remaining\":1,\"tiles\":[[\"flag\",2,\"hidden\"],[\"hidden\",2,null]]}"
+        );
+
+        let state: GameState = serde_json::from_str(&json).unwrap();
+        assert_eq!(state, GameState::of(&field));
+    }
+
+    #[test]
+    fn diffs_bring_spectators_up_to_date() {
+        let mut field = MineField::from_layout(
+            "..*
+...
+*..",
+        )
+        .unwrap();
+        let mut seen = GameState::of(&field);
+
+        field.toggle_flag(Position::new(0, 2)).unwrap();
+        field.flood_empty_tiles(Position::new(1, 2)).unwrap();
+
+        let diff = Diff::between(&seen, &GameState::of(&field));
+        let json = serde_json::to_string(&Update::Diff(diff.clone())).unwrap();
+
+        assert_eq!(
+            json,
+            "{\"update\":\"diff\",\"status\":\"in-progress\",\"flags\":1,\
+             \"mines_remaining\":1,\"changes\":[[0,2,\"flag\"],[1,2,1]]}"
+        );
+
+        diff.apply(&mut seen);
+        assert_eq!(seen, GameState::of(&field));
+
+        // nothing is sent unless something has changed.
+        let mut feed = Feed::default();
+        assert_eq!(feed.updates(&field, Position::new(1, 2)).len(), 2);
+        assert_eq!(feed.updates(&field, Position::new(1, 2)), []);
+        assert_eq!(
+            feed.updates(&field, Position::new(0, 0)),
+            [Update::Cursor { row: 0, column: 0 }]
+        );
+        assert_eq!(feed.catch_up()[0], Update::State(seen));
+    }
+}
@@ -0,0 +1,323 @@
+use crate::engine::{Action, Event};
+use crate::minefield::{MineField, TileChange};
+use crate::position::Position;
+use crate::solver::{self, Verdict};
+use crate::tile::{Tile, TileState};
+
+/// A dig, flag or chord made in a round, with the tiles it changed.
+pub struct PlayedMove {
+    pub action: Action,
+    pub position: Position,
+    pub changes: Vec<TileChange>,
+}
+
+impl PlayedMove {
+    /// Work out what a move changed from the events it caused, going
+    /// by the field as it was just after it.
+    pub fn new(action: Action, position: Position, events: &[Event], field: &MineField) -> Self {
+        let change = |position: Position, state: TileState| {
+            let after = *field.get_tile(position).unwrap();
+
+            TileChange {
+                position,
+                before: Tile { state, ..after },
+                after,
+            }
+        };
+
+        let mut changes = Vec::new();
+
+        for event in events {
+            match event {
+                Event::TilesRevealed(waves) => {
+                    for &position in waves.iter().flatten() {
+                        changes.push(change(position, TileState::Hidden));
+                    }
+                }
+                Event::FlagToggled {
+                    position,
+                    is_flagged,
+                } => {
+                    let state = match is_flagged {
+                        true => TileState::Hidden,
+                        false => TileState::Flagged,
+                    };
+
+                    changes.push(change(*position, state));
+                }
+                Event::MinesFlagged(flagged) => {
+                    for &position in flagged {
+                        changes.push(change(position, TileState::Hidden));
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        Self {
+            action,
+            position,
+            changes,
+        }
+    }
+
+    /// Rebuild a field as it was before this move, from how it is now.
+    fn undo_on(&self, field: &MineField) -> MineField {
+        let mut before = field.clone();
+
+        for change in self.changes.iter().rev() {
+            before
+                .set_tile_state(change.position, change.before.state)
+                .unwrap();
+        }
+
+        before
+    }
+}
+
+/// The moves of a round that changed the field, leaving out any that
+/// were undone, so that the round can be looked back on at the end.
+#[derive(Default)]
+pub struct Moves {
+    made: Vec<PlayedMove>,
+    undone: Vec<PlayedMove>,
+}
+
+impl Moves {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Remember a move, unless it didn't change anything.
+    pub fn record(&mut self, played: PlayedMove) {
+        if !played.changes.is_empty() {
+            self.made.push(played);
+            self.undone.clear();
+        }
+    }
+
+    /// Set the last move aside, after it's been undone.
+    pub fn undo(&mut self) {
+        self.undone.extend(self.made.pop());
+    }
+
+    /// Bring back the last move that was undone, after it's been redone.
+    pub fn redo(&mut self) {
+        self.made.extend(self.undone.pop());
+    }
+
+    /// Forget every move, ready for a new round.
+    pub fn clear(&mut self) {
+        self.made.clear();
+        self.undone.clear();
+    }
+
+    /// The last move that still stands, if there is one.
+    pub fn last(&self) -> Option<&PlayedMove> {
+        self.made.last()
+    }
+}
+
+/// What a review makes of a tile once the round is over.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum Mark {
+    /// A flag on a mine.
+    RightFlag,
+    /// A flag on a tile without a mine.
+    WrongFlag,
+    /// A mine that was dug up, losing the game.
+    Fatal,
+    /// A hidden tile whose contents could have been worked out from
+    /// the board as it was before the last move.
+    Deducible(Verdict),
+}
+
+/// A look back over a finished round, tile by tile.
+pub struct Review {
+    width: usize,
+    marks: Vec<Option<Mark>>,
+    /// Whether the mine that lost the game could have been known to be
+    /// one, if a mine was dug at all.
+    fatal_was_deducible: Option<bool>,
+    last_move: Option<(Action, Position)>,
+}
+
+impl Review {
+    /// Go over a field as it was when play stopped, before any mines
+    /// were shown, along with the move that ended it.
+    pub fn new(field: &MineField, last_move: Option<&PlayedMove>) -> Self {
+        let deductions = last_move
+            .map(|last_move| solver::deduce(&last_move.undo_on(field)))
+            .unwrap_or_default();
+
+        let verdict_at = |position: Position| {
+            deductions
+                .iter()
+                .find(|deduction| deduction.position == position)
+                .map(|deduction| deduction.verdict)
+        };
+
+        let mut marks = Vec::with_capacity(field.width() * field.height());
+        let mut fatal_was_deducible = None;
+
+        for position in field.iter_positions() {
+            let tile = field.get_tile(position).unwrap();
+
+            let mark = match (tile.state, tile.has_mine) {
+                _ if tile.is_void => None,
+                (TileState::Flagged, true) => Some(Mark::RightFlag),
+                (TileState::Flagged, false) => Some(Mark::WrongFlag),
+                (TileState::Visible, true) => {
+                    let was_deducible = verdict_at(position) == Some(Verdict::Mine);
+                    fatal_was_deducible =
+                        Some(fatal_was_deducible.unwrap_or(false) || was_deducible);
+                    Some(Mark::Fatal)
+                }
+                _ => verdict_at(position).map(Mark::Deducible),
+            };
+
+            marks.push(mark);
+        }
+
+        Self {
+            width: field.width(),
+            marks,
+            fatal_was_deducible,
+            last_move: last_move.map(|last_move| (last_move.action, last_move.position)),
+        }
+    }
+
+    /// What the review makes of a tile, if anything.
+    pub fn mark(&self, position: Position) -> Option<Mark> {
+        self.marks
+            .get(position.row * self.width + position.column)
+            .copied()
+            .flatten()
+    }
+
+    /// Describe what the review makes of the tile at a position.
+    pub fn describe(&self, position: Position) -> String {
+        let tile = format!("Row {}, column {}", position.row + 1, position.column + 1);
+
+        match self.mark(position) {
+            Some(Mark::RightFlag) => format!("{} was rightly flagged", tile),
+            Some(Mark::WrongFlag) => format!("{} was flagged, but had no mine", tile),
+            Some(Mark::Fatal) => {
+                let how = match self.last_move {
+                    Some((Action::Chord, at)) if at != position => {
+                        format!(" by a chord on {}", at.to_a1())
+                    }
+                    _ => String::new(),
+                };
+
+                match self.fatal_was_deducible {
+                    Some(true) => format!("{} was dug{}, though it could be worked out", tile, how),
+                    _ => format!("{} was dug{}, and was a guess", tile, how),
+                }
+            }
+            Some(Mark::Deducible(Verdict::Safe)) => {
+                format!("{} could have been worked out as safe", tile)
+            }
+            Some(Mark::Deducible(Verdict::Mine)) => {
+                format!("{} could have been worked out as a mine", tile)
+            }
+            None => format!("{} has nothing to review", tile),
+        }
+    }
+
+    /// Sum the review up in a line.
+    pub fn summary(&self) -> String {
+        let count = |wanted: fn(Mark) -> bool| {
+            self.marks
+                .iter()
+                .flatten()
+                .filter(|&&mark| wanted(mark))
+                .count()
+        };
+
+        let right = count(|mark| mark == Mark::RightFlag);
+        let flags = right + count(|mark| mark == Mark::WrongFlag);
+        let deducible = count(|mark| matches!(mark, Mark::Deducible(_)));
+
+        format!(
+            "{} of {} flags right, {} tiles could be worked out",
+            right, flags, deducible
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::GameEngine;
+
+    #[test]
+    fn flags_and_the_fatal_dig_are_marked() {
+        // the 1 at the top gives away the mine beside it.
+        let field = MineField::from_layout("01*..\n011..\n000.f").unwrap();
+        let mut engine = GameEngine::new(field);
+        engine.set_cursor(Position::new(0, 2));
+
+        let events = engine.apply(Action::Dig);
+        let last_move = PlayedMove::new(Action::Dig, Position::new(0, 2), &events, engine.field());
+        let review = Review::new(engine.field(), Some(&last_move));
+
+        assert_eq!(review.mark(Position::new(0, 2)), Some(Mark::Fatal));
+        assert_eq!(review.mark(Position::new(2, 4)), Some(Mark::WrongFlag));
+        assert_eq!(
+            review.mark(Position::new(0, 3)),
+            Some(Mark::Deducible(Verdict::Safe))
+        );
+        assert_eq!(review.mark(Position::new(0, 0)), None);
+        assert!(review
+            .describe(Position::new(0, 2))
+            .contains("could be worked out"));
+    }
+
+    #[test]
+    fn a_round_without_moves_only_marks_flags() {
+        let field = MineField::from_layout("F.\n.*").unwrap();
+        let review = Review::new(&field, None);
+
+        assert_eq!(review.mark(Position::new(0, 0)), Some(Mark::RightFlag));
+        assert_eq!(review.mark(Position::new(1, 1)), None);
+        assert_eq!(
+            review.summary(),
+            "1 of 1 flags right, 0 tiles could be worked out"
+        );
+    }
+
+    #[test]
+    fn undone_moves_are_set_aside() {
+        let field = MineField::from_layout("*..").unwrap();
+        let mut engine = GameEngine::new(field);
+        let mut moves = Moves::new();
+
+        let events = engine.apply(Action::Flag);
+        moves.record(PlayedMove::new(
+            Action::Flag,
+            Position::new(0, 0),
+            &events,
+            engine.field(),
+        ));
+        assert_eq!(
+            moves.last().unwrap().changes[0].before.state,
+            TileState::Hidden
+        );
+
+        // a move that changes nothing isn't one to look back on.
+        let events = engine.apply(Action::Note);
+        moves.record(PlayedMove::new(
+            Action::Note,
+            Position::new(0, 0),
+            &events,
+            engine.field(),
+        ));
+        assert_eq!(moves.last().unwrap().action, Action::Flag);
+
+        moves.undo();
+        assert!(moves.last().is_none());
+        moves.redo();
+        assert_eq!(moves.last().unwrap().action, Action::Flag);
+    }
+}
@@ -0,0 +1,24 @@
+/// Options that change how the game itself plays out.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub struct Rules {
+    /// Whether a zero revealed by a chord opens up the area around
+    /// it, like digging does, or is revealed as a single tile.
+    pub chord_cascade: bool,
+
+    /// Whether placing more flags than there are mines is refused.
+    pub strict_flags: bool,
+
+    /// Whether flags are turned off entirely, for flagless ("NF") play.
+    /// Chords then go by the mines that the revealed numbers give away.
+    pub no_flags: bool,
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Self {
+            chord_cascade: true,
+            strict_flags: false,
+            no_flags: false,
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use std::collections::BTreeMap;
+use std::io;
+use std::time::Duration;
+
+use crate::difficulty::Difficulty;
+use crate::minefield::MineField;
+use crate::storage;
+
+const SCORES_FILE: &str = "scores";
+
+/// Boil the shape of a field down to a number, so differently shaped
+/// fields of the same size get their own best times.
+///
+/// This is FNV-1a over which tiles are void, which stays the same
+/// between builds, unlike the standard library's hasher.
+fn shape_hash(field: &MineField) -> u32 {
+    field.iter_tiles().fold(0x811c_9dc5, |hash, tile| {
+        (hash ^ tile.is_void as u32).wrapping_mul(0x0100_0193)
+    })
+}
+
+/// Work out the name that times on a field are recorded under.
+///
+/// The standard difficulties are recorded by name, and any
+/// other field by its geometry, in the same form as the CLI.
+pub fn board_key(field: &MineField) -> String {
+    let (width, height, mines) = (field.width(), field.height(), field.mines());
+
+    let mut key = match Difficulty::from_dimensions(width, height, mines).and_then(Difficulty::name)
+    {
+        Some(name) => String::from(name),
+        None => format!("{}x{}_{}", width, height, mines),
+    };
+
+    // clearing a target region is a different game entirely.
+    if let Some(region) = field.target() {
+        key.push_str(&format!("+target_{}x{}", region.columns, region.rows));
+    }
+
+    // and so is every differently shaped field.
+    if field.iter_tiles().any(|tile| tile.is_void) {
+        key.push_str(&format!("+mask_{:08x}", shape_hash(field)));
+    }
+
+    // so is playing on a different shape of grid.
+    if let Some(name) = field.topology().name() {
+        key.push_str(&format!("+{}", name));
+    }
+
+    // flagless games are kept apart, to be compared with each other.
+    if field.rules().no_flags {
+        key.push_str("+nf");
+    }
+
+    key
+}
+
+/// The best completion times, keyed by board.
+pub struct Scores {
+    best: BTreeMap<String, Duration>,
+}
+
+impl Scores {
+    /// Load the best times from previous games.
+    ///
+    /// Lines that can't be understood are skipped.
+    pub fn load() -> Self {
+        let contents = storage::read_data_file(SCORES_FILE).unwrap_or_default();
+        let mut best = BTreeMap::new();
+
+        for line in contents.lines() {
+            let mut split = line.split_whitespace();
+
+            if let (Some(key), Some(Ok(millis)), None) =
+                (split.next(), split.next().map(str::parse), split.next())
+            {
+                best.insert(String::from(key), Duration::from_millis(millis));
+            }
+        }
+
+        Self { best }
+    }
+
+    /// Write the best times back to the scores file.
+    pub fn save(&self) -> io::Result<()> {
+        let lines: Vec<String> = self
+            .best
+            .iter()
+            .map(|(key, time)| format!("{} {}", key, time.as_millis()))
+            .collect();
+
+        storage::write_data_file(SCORES_FILE, &lines.join("\n"))
+    }
+
+    /// Get the best time for a board, if it has been cleared before.
+    pub fn best(&self, key: &str) -> Option<Duration> {
+        self.best.get(key).copied()
+    }
+
+    /// Record a winning time, returning whether it's a new best.
+    pub fn record(&mut self, key: &str, time: Duration) -> bool {
+        match self.best(key) {
+            Some(best) if best <= time => false,
+            _ => {
+                self.best.insert(String::from(key), time);
+                true
+            }
+        }
+    }
+}
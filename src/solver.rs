@@ -0,0 +1,778 @@
+use std::collections::{BTreeSet, HashMap, VecDeque};
+
+use crate::position::Position;
+use crate::view::{BoardView, Cell};
+
+/// What the solver has proven about a hidden tile.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum Verdict {
+    Safe,
+    Mine,
+}
+
+/// A hidden tile whose contents can be worked out from the visible board.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub struct Deduction {
+    pub position: Position,
+    pub verdict: Verdict,
+}
+
+/// The chance of a hidden tile holding a mine.
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct Probability {
+    pub position: Position,
+    pub mine: f64,
+}
+
+/// The most steps spent searching for the arrangements of a linked
+/// group of tiles. Groups that take longer are left out, since the
+/// game would freeze up while counting them.
+const MAX_SEARCH_STEPS: usize = 1_000_000;
+
+/// A set of hidden tiles which hold an exact amount of mines between them.
+#[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Clone)]
+struct Constraint {
+    tiles: BTreeSet<(usize, usize)>,
+    mines: usize,
+}
+
+/// Gather the constraints given by every visible number on the field.
+///
+/// Flags and tiles that have already been deduced are taken out of each
+/// constraint, with mines among them taken off of the count. If any
+/// number can't possibly be satisfied, `None` is returned instead.
+fn gather_constraints(
+    board: &impl BoardView,
+    known: &HashMap<(usize, usize), Verdict>,
+) -> Option<Vec<Constraint>> {
+    let mut constraints = BTreeSet::new();
+
+    for (row, column) in board.positions() {
+        let mut mines = match board.cell(row, column) {
+            Cell::Revealed(number) => number,
+            _ => continue,
+        };
+
+        let mut tiles = BTreeSet::new();
+
+        for (adj_row, adj_column) in board.neighbours(row, column) {
+            let is_known_mine = known.get(&(adj_row, adj_column)) == Some(&Verdict::Mine);
+
+            match board.cell(adj_row, adj_column) {
+                Cell::Flagged => mines = mines.checked_sub(1)?,
+                Cell::Hidden if is_known_mine => mines = mines.checked_sub(1)?,
+                Cell::Hidden if !known.contains_key(&(adj_row, adj_column)) => {
+                    tiles.insert((adj_row, adj_column));
+                }
+                _ => (),
+            }
+        }
+
+        if mines > tiles.len() {
+            return None;
+        }
+
+        if !tiles.is_empty() {
+            constraints.insert(Constraint { tiles, mines });
+        }
+    }
+
+    Some(constraints.into_iter().collect())
+}
+
+/// Mark every tile in a set with the same verdict, noting if anything was new.
+fn conclude<'a>(
+    known: &mut HashMap<(usize, usize), Verdict>,
+    tiles: impl Iterator<Item = &'a (usize, usize)>,
+    verdict: Verdict,
+) -> bool {
+    let mut changed = false;
+
+    for &tile in tiles {
+        if known.insert(tile, verdict).is_none() {
+            changed = true;
+        }
+    }
+
+    changed
+}
+
+/// Work out every hidden tile that is provably safe or provably a mine.
+///
+/// This only looks at what the player can see: the revealed numbers and
+/// the flags they've placed. Flags are trusted to be correct.
+pub fn deduce(board: &impl BoardView) -> Vec<Deduction> {
+    let mut known = HashMap::new();
+    let mut changed = true;
+
+    while changed {
+        changed = false;
+
+        // stop at a contradiction, since anything would follow from it.
+        let constraints = match gather_constraints(board, &known) {
+            Some(constraints) => constraints,
+            None => break,
+        };
+
+        // find the constraints that each tile is a part of, so that
+        // only overlapping constraints are compared with each other.
+        let mut constraints_on = HashMap::new();
+
+        for (index, constraint) in constraints.iter().enumerate() {
+            for &tile in constraint.tiles.iter() {
+                constraints_on
+                    .entry(tile)
+                    .or_insert_with(Vec::new)
+                    .push(index);
+            }
+        }
+
+        for constraint in constraints.iter() {
+            // single-point deduction: no mines left, or nothing but mines.
+            if constraint.mines == 0 {
+                changed |= conclude(&mut known, constraint.tiles.iter(), Verdict::Safe);
+            } else if constraint.mines == constraint.tiles.len() {
+                changed |= conclude(&mut known, constraint.tiles.iter(), Verdict::Mine);
+            }
+
+            // set deduction: if this constraint is inside a bigger one, the
+            // rest of the bigger one holds the difference in mines.
+            let overlapping: BTreeSet<usize> = constraint
+                .tiles
+                .iter()
+                .flat_map(|tile| constraints_on[tile].iter().copied())
+                .collect();
+
+            for other in overlapping.into_iter().map(|index| &constraints[index]) {
+                if other.tiles.len() <= constraint.tiles.len()
+                    || !constraint.tiles.is_subset(&other.tiles)
+                    || other.mines < constraint.mines
+                {
+                    continue;
+                }
+
+                let rest = other.tiles.difference(&constraint.tiles);
+                let rest_mines = other.mines - constraint.mines;
+
+                if rest_mines == 0 {
+                    changed |= conclude(&mut known, rest, Verdict::Safe);
+                } else if rest_mines == other.tiles.len() - constraint.tiles.len() {
+                    changed |= conclude(&mut known, rest, Verdict::Mine);
+                }
+            }
+        }
+    }
+
+    let mut deductions: Vec<Deduction> = known
+        .into_iter()
+        .map(|((row, column), verdict)| Deduction {
+            position: Position::new(row, column),
+            verdict,
+        })
+        .collect();
+
+    deductions.sort_by_key(|deduction| deduction.position);
+    deductions
+}
+
+/// Pick a single deduction to nudge the player with.
+///
+/// Safe tiles are preferred, since they move the game along,
+/// then whichever tile is closest to the cursor.
+pub fn hint(board: &impl BoardView, cursor: Position) -> Option<Deduction> {
+    deduce(board).into_iter().min_by_key(|deduction| {
+        let Position { row, column } = deduction.position;
+        let distance = row.abs_diff(cursor.row) + column.abs_diff(cursor.column);
+        (deduction.verdict != Verdict::Safe, distance)
+    })
+}
+
+/// Every way of placing mines among a group of linked frontier tiles,
+/// tallied up by how many mines each arrangement uses.
+struct Arrangements {
+    tiles: Vec<(usize, usize)>,
+
+    /// `counts[k]` is the number of arrangements with k mines.
+    counts: Vec<f64>,
+
+    /// `tile_counts[k][i]` is the number of those with a mine on tile i.
+    tile_counts: Vec<Vec<f64>>,
+}
+
+/// Split the frontier into groups of tiles linked by shared constraints.
+fn group_constraints(constraints: &[Constraint]) -> Vec<Vec<&Constraint>> {
+    // each group is kept alongside every tile it covers.
+    let mut groups: Vec<(BTreeSet<_>, Vec<_>)> = Vec::new();
+
+    for constraint in constraints {
+        let mut tiles = constraint.tiles.clone();
+        let mut members = vec![constraint];
+
+        // merge every existing group that this constraint touches.
+        let mut index = 0;
+
+        while index < groups.len() {
+            if groups[index].0.is_disjoint(&tiles) {
+                index += 1;
+            } else {
+                let (other_tiles, other_members) = groups.swap_remove(index);
+                tiles.extend(other_tiles);
+                members.extend(other_members);
+            }
+        }
+
+        groups.push((tiles, members));
+    }
+
+    groups.into_iter().map(|(_, members)| members).collect()
+}
+
+/// The working state while searching for arrangements of a group.
+struct Search {
+    constraints_on: Vec<Vec<usize>>,
+    mines: Vec<bool>,
+    mines_left: Vec<usize>,
+    tiles_left: Vec<usize>,
+    steps_left: usize,
+}
+
+impl Search {
+    /// Try placing (or not placing) a mine on a tile, checking that
+    /// every constraint it's part of can still be satisfied.
+    fn place(&mut self, index: usize, is_mine: bool) -> bool {
+        let mut fits = true;
+
+        for &constraint in self.constraints_on[index].iter() {
+            self.tiles_left[constraint] -= 1;
+
+            if is_mine {
+                if self.mines_left[constraint] == 0 {
+                    fits = false;
+                }
+
+                self.mines_left[constraint] = self.mines_left[constraint].wrapping_sub(1);
+            }
+
+            if self.mines_left[constraint] > self.tiles_left[constraint] {
+                fits = false;
+            }
+        }
+
+        self.mines[index] = is_mine;
+        fits
+    }
+
+    /// Take back a placement made by `place`.
+    fn unplace(&mut self, index: usize) {
+        for &constraint in self.constraints_on[index].iter() {
+            self.tiles_left[constraint] += 1;
+
+            if self.mines[index] {
+                self.mines_left[constraint] = self.mines_left[constraint].wrapping_add(1);
+            }
+        }
+
+        self.mines[index] = false;
+    }
+
+    /// Fill in the tiles from `index` onwards in every valid way.
+    fn search(&mut self, index: usize, arrangements: &mut Arrangements) {
+        if self.steps_left == 0 {
+            return;
+        }
+
+        self.steps_left -= 1;
+
+        if index == self.mines.len() {
+            let mine_count = self.mines.iter().filter(|&&is_mine| is_mine).count();
+            arrangements.counts[mine_count] += 1.0;
+
+            for (tile, _) in self.mines.iter().enumerate().filter(|(_, &m)| m) {
+                arrangements.tile_counts[mine_count][tile] += 1.0;
+            }
+
+            return;
+        }
+
+        for &is_mine in [false, true].iter() {
+            if self.place(index, is_mine) {
+                self.search(index + 1, arrangements);
+            }
+
+            self.unplace(index);
+        }
+    }
+}
+
+/// Every tile covered by a group of constraints.
+///
+/// The tiles are ordered so that each constraint's tiles come close
+/// together, like following the edge of an opening around. That way
+/// the search fills constraints in one at a time and can give up on
+/// a bad arrangement early, rather than only near the end.
+fn group_tiles(group: &[&Constraint]) -> Vec<(usize, usize)> {
+    let mut tiles = Vec::new();
+    let mut seen = BTreeSet::new();
+    let mut used = vec![false; group.len()];
+    let mut queue = VecDeque::new();
+
+    for start in 0..group.len() {
+        if used[start] {
+            continue;
+        }
+
+        used[start] = true;
+        queue.push_back(start);
+
+        while let Some(index) = queue.pop_front() {
+            for &tile in group[index].tiles.iter() {
+                if !seen.insert(tile) {
+                    continue;
+                }
+
+                tiles.push(tile);
+
+                // visit the other constraints on this tile next.
+                for (other, constraint) in group.iter().enumerate() {
+                    if !used[other] && constraint.tiles.contains(&tile) {
+                        used[other] = true;
+                        queue.push_back(other);
+                    }
+                }
+            }
+        }
+    }
+
+    tiles
+}
+
+/// Count every arrangement of mines that satisfies a group of constraints.
+///
+/// Returns `None` if the group takes too long to enumerate.
+fn enumerate(group: &[&Constraint]) -> Option<Arrangements> {
+    let tiles = group_tiles(group);
+
+    let mut search = Search {
+        constraints_on: tiles
+            .iter()
+            .map(|tile| {
+                (0..group.len())
+                    .filter(|&index| group[index].tiles.contains(tile))
+                    .collect()
+            })
+            .collect(),
+        mines: vec![false; tiles.len()],
+        mines_left: group.iter().map(|constraint| constraint.mines).collect(),
+        tiles_left: group
+            .iter()
+            .map(|constraint| constraint.tiles.len())
+            .collect(),
+        steps_left: MAX_SEARCH_STEPS,
+    };
+
+    let mut arrangements = Arrangements {
+        counts: vec![0.0; tiles.len() + 1],
+        tile_counts: vec![vec![0.0; tiles.len()]; tiles.len() + 1],
+        tiles,
+    };
+
+    search.search(0, &mut arrangements);
+
+    if search.steps_left == 0 {
+        return None;
+    }
+
+    Some(arrangements)
+}
+
+/// Work out ln(n choose r), or `None` if it's zero.
+fn ln_choose(n: usize, r: usize) -> Option<f64> {
+    if r > n {
+        return None;
+    }
+
+    let r = r.min(n - r);
+    Some((0..r).map(|i| ((n - i) as f64 / (i + 1) as f64).ln()).sum())
+}
+
+/// Combine two distributions of mine counts.
+fn convolve(left: &[f64], right: &[f64]) -> Vec<f64> {
+    let mut result = vec![0.0; left.len() + right.len() - 1];
+
+    for (i, a) in left.iter().enumerate() {
+        for (j, b) in right.iter().enumerate() {
+            result[i + j] += a * b;
+        }
+    }
+
+    result
+}
+
+/// How the unflagged mines could be split between the frontier
+/// and the rest of the hidden tiles, for counting out an endgame.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct MineCount {
+    /// Mines that haven't been flagged, if the board knows its total.
+    pub remaining: Option<usize>,
+
+    /// Hidden tiles next to a revealed number.
+    pub frontier_tiles: usize,
+
+    /// Every amount of mines the frontier could hold, smallest first.
+    ///
+    /// Amounts can be missing from the middle, when the numbers
+    /// only fit an odd or even amount, for instance.
+    pub frontier_mines: Vec<usize>,
+
+    /// Hidden tiles away from every revealed number.
+    pub interior_tiles: usize,
+}
+
+/// Work out how many mines could be on the frontier, and so how many
+/// are left for the interior. Returns `None` if the flags can't be right.
+pub fn mine_count(board: &impl BoardView) -> Option<MineCount> {
+    let constraints = gather_constraints(board, &HashMap::new())?;
+    let groups = group_constraints(&constraints);
+
+    let mut frontier = BTreeSet::new();
+    let mut possible = vec![1.0];
+
+    for group in groups.iter() {
+        let tiles = group_tiles(group);
+
+        // a group that takes too long to enumerate could hold any amount of mines.
+        let counts = match enumerate(group) {
+            Some(arrangements) => arrangements.counts,
+            None => vec![1.0; tiles.len() + 1],
+        };
+
+        frontier.extend(tiles);
+        possible = convolve(&possible, &counts);
+    }
+
+    let mut flags = 0;
+    let mut interior_tiles = 0;
+
+    for (row, column) in board.positions() {
+        match board.cell(row, column) {
+            Cell::Flagged => flags += 1,
+            Cell::Hidden if !frontier.contains(&(row, column)) => interior_tiles += 1,
+            _ => (),
+        }
+    }
+
+    let remaining = board.mines().map(|mines| mines.saturating_sub(flags));
+
+    // with a total, the interior has to be able to hold the rest.
+    let frontier_mines: Vec<usize> = (0..possible.len())
+        .filter(|&k| possible[k] > 0.0)
+        .filter(|&k| {
+            remaining.is_none_or(|remaining| k <= remaining && remaining - k <= interior_tiles)
+        })
+        .collect();
+
+    if frontier_mines.is_empty() {
+        return None;
+    }
+
+    Some(MineCount {
+        remaining,
+        frontier_tiles: frontier.len(),
+        frontier_mines,
+        interior_tiles,
+    })
+}
+
+/// Work out the chance of a mine on every hidden tile that can be judged.
+///
+/// Tiles on the frontier are judged by counting every arrangement of
+/// mines that fits the visible numbers. If the board knows its total
+/// mine count, arrangements are weighted by how many ways the rest of
+/// the mines fit in the untouched tiles, which are judged too.
+///
+/// Nothing is returned if the visible numbers contradict each other.
+pub fn probabilities(board: &impl BoardView) -> Vec<Probability> {
+    let constraints = match gather_constraints(board, &HashMap::new()) {
+        Some(constraints) => constraints,
+        None => return Vec::new(),
+    };
+    let groups = group_constraints(&constraints);
+
+    // groups that take too long to enumerate are left out, but then the total
+    // can't be used, since their mine counts aren't known.
+    let arrangements: Vec<Arrangements> =
+        groups.iter().filter_map(|group| enumerate(group)).collect();
+    let all_enumerated = arrangements.len() == groups.len();
+
+    // if the constraints can't be satisfied at all, nothing can be said.
+    if arrangements
+        .iter()
+        .any(|group| group.counts.iter().sum::<f64>() == 0.0)
+    {
+        return Vec::new();
+    }
+
+    let frontier: BTreeSet<(usize, usize)> = arrangements
+        .iter()
+        .flat_map(|group| group.tiles.iter().copied())
+        .collect();
+
+    let mut flags = 0;
+    let mut interior = Vec::new();
+
+    for (row, column) in board.positions() {
+        match board.cell(row, column) {
+            Cell::Flagged => flags += 1,
+            Cell::Hidden if !frontier.contains(&(row, column)) => interior.push((row, column)),
+            _ => (),
+        }
+    }
+
+    let mut probabilities = Vec::new();
+
+    let remaining = board
+        .mines()
+        .filter(|_| all_enumerated)
+        .map(|mines| mines.saturating_sub(flags));
+
+    match remaining {
+        Some(remaining) => {
+            // the weight of an arrangement using k mines on the frontier
+            // is the number of ways to fit the rest in the interior.
+            let ln_weights: Vec<Option<f64>> = (0..=frontier.len())
+                .map(|k| ln_choose(interior.len(), remaining.checked_sub(k)?))
+                .collect();
+
+            let max_ln_weight = ln_weights
+                .iter()
+                .flatten()
+                .fold(f64::NEG_INFINITY, |max, &w| max.max(w));
+
+            if max_ln_weight == f64::NEG_INFINITY {
+                return Vec::new();
+            }
+
+            let weights: Vec<f64> = ln_weights
+                .iter()
+                .map(|w| w.map_or(0.0, |w| (w - max_ln_weight).exp()))
+                .collect();
+
+            for (index, group) in arrangements.iter().enumerate() {
+                // combine the mine counts of every other group.
+                let others = arrangements
+                    .iter()
+                    .enumerate()
+                    .filter(|&(other, _)| other != index)
+                    .fold(vec![1.0], |acc, (_, other)| convolve(&acc, &other.counts));
+
+                let mut group_weight = 0.0;
+                let mut tile_weights = vec![0.0; group.tiles.len()];
+
+                for (k, count) in group.counts.iter().enumerate() {
+                    for (rest, other_count) in others.iter().enumerate() {
+                        let weight = weights[k + rest] * other_count;
+                        group_weight += count * weight;
+
+                        for (tile, tile_weight) in tile_weights.iter_mut().enumerate() {
+                            *tile_weight += group.tile_counts[k][tile] * weight;
+                        }
+                    }
+                }
+
+                for (&(row, column), tile_weight) in group.tiles.iter().zip(tile_weights) {
+                    probabilities.push(Probability {
+                        position: Position::new(row, column),
+                        mine: tile_weight / group_weight,
+                    });
+                }
+            }
+
+            // the untouched tiles share whatever mines the frontier doesn't use.
+            let all = arrangements
+                .iter()
+                .fold(vec![1.0], |acc, group| convolve(&acc, &group.counts));
+
+            let mut total_weight = 0.0;
+            let mut interior_mines = 0.0;
+
+            for (k, count) in all.iter().enumerate() {
+                if k <= remaining {
+                    total_weight += count * weights[k];
+                    interior_mines += count * weights[k] * (remaining - k) as f64;
+                }
+            }
+
+            for &(row, column) in interior.iter() {
+                probabilities.push(Probability {
+                    position: Position::new(row, column),
+                    mine: interior_mines / total_weight / interior.len() as f64,
+                });
+            }
+        }
+
+        // without a total, every group is judged on its own,
+        // and nothing can be said about the untouched tiles.
+        None => {
+            for group in arrangements.iter() {
+                let total: f64 = group.counts.iter().sum();
+
+                for (tile, &(row, column)) in group.tiles.iter().enumerate() {
+                    let mines: f64 = group.tile_counts.iter().map(|counts| counts[tile]).sum();
+
+                    probabilities.push(Probability {
+                        position: Position::new(row, column),
+                        mine: mines / total,
+                    });
+                }
+            }
+        }
+    }
+
+    probabilities.sort_by_key(|probability| probability.position);
+    probabilities
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::minefield::MineField;
+    use crate::partial::PartialBoard;
+
+    /// Build a field from rows of '*' (mine) and '.' (empty), then dig
+    /// every tile marked in `dug` with 'o'.
+    fn field_from(mines: &[&str], dug: &[&str]) -> MineField {
+        let mut field = MineField::empty(mines[0].len(), mines.len());
+
+        for (row, line) in mines.iter().enumerate() {
+            for (column, c) in line.chars().enumerate() {
+                field
+                    .get_tile_mut(Position::new(row, column))
+                    .unwrap()
+                    .has_mine = c == '*';
+            }
+        }
+
+        for (row, line) in dug.iter().enumerate() {
+            for (column, c) in line.chars().enumerate() {
+                if c == 'o' {
+                    field.dig_tile(Position::new(row, column)).unwrap();
+                }
+            }
+        }
+
+        field
+    }
+
+    #[test]
+    fn single_point_deductions() {
+        // every dug 1 only touches the one hidden tile.
+        let field = field_from(&["*.", ".."], &[".o", "oo"]);
+        let deductions = deduce(&field);
+
+        assert!(deductions.contains(&Deduction {
+            position: Position::new(0, 0),
+            verdict: Verdict::Mine,
+        }));
+    }
+
+    #[test]
+    fn set_deductions() {
+        // the classic 1-1 against a wall: the third tile must be safe.
+        let field = field_from(&["*..", "..."], &["...", "oo."]);
+        let deductions = deduce(&field);
+
+        assert!(deductions.contains(&Deduction {
+            position: Position::new(0, 2),
+            verdict: Verdict::Safe,
+        }));
+        assert!(!deductions.iter().any(|d| d.verdict == Verdict::Mine));
+    }
+
+    #[test]
+    fn deductions_on_a_partial_board() {
+        let board = PartialBoard::parse(". . .\n2 F 1").unwrap();
+        let deductions = deduce(&board);
+
+        assert_eq!(
+            deductions,
+            vec![
+                Deduction {
+                    position: Position::new(0, 0),
+                    verdict: Verdict::Mine,
+                },
+                Deduction {
+                    position: Position::new(0, 1),
+                    verdict: Verdict::Safe,
+                },
+                Deduction {
+                    position: Position::new(0, 2),
+                    verdict: Verdict::Safe,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn probabilities_without_a_total() {
+        let board = PartialBoard::parse(". .\n1 .").unwrap();
+
+        for probability in probabilities(&board) {
+            assert!((probability.mine - 1.0 / 3.0).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn probabilities_with_a_total() {
+        let mut board = PartialBoard::parse("1 . .").unwrap();
+        board.set_mines(2);
+
+        let chances: Vec<f64> = probabilities(&board).iter().map(|p| p.mine).collect();
+        assert_eq!(chances, vec![1.0, 1.0]);
+    }
+
+    #[test]
+    fn no_probabilities_for_contradictions() {
+        let board = PartialBoard::parse("F F\n1 .").unwrap();
+        assert!(probabilities(&board).is_empty());
+    }
+
+    #[test]
+    fn counting_mines_on_the_frontier() {
+        let board = PartialBoard::parse(". 1 . .").unwrap();
+
+        assert_eq!(
+            mine_count(&board),
+            Some(MineCount {
+                remaining: None,
+                frontier_tiles: 2,
+                frontier_mines: vec![1],
+                interior_tiles: 1,
+            })
+        );
+
+        // two separate 1s must use two mines, which a total of one can't fit.
+        let mut board = PartialBoard::parse(". 1 . 1 . .").unwrap();
+        assert_eq!(mine_count(&board).unwrap().frontier_mines, vec![1, 2]);
+
+        board.set_mines(1);
+        assert_eq!(mine_count(&board).unwrap().frontier_mines, vec![1]);
+    }
+
+    #[test]
+    fn probabilities_along_a_long_edge() {
+        // a mine on every third tile of the top row, with the numbers
+        // below it. the edge is too long to try every arrangement of.
+        let text = format!(
+            "{}\n{}0\n{}",
+            ".".repeat(40),
+            "1".repeat(39),
+            "0".repeat(40)
+        );
+
+        let board = PartialBoard::parse(&text).unwrap();
+        let probabilities = probabilities(&board);
+
+        assert_eq!(probabilities.len(), 40);
+        assert_eq!(probabilities[39].mine, 0.0);
+    }
+}
@@ -0,0 +1,130 @@
+use std::collections::BTreeMap;
+
+use crate::config::Config;
+use crate::hooks;
+
+/// The terminal bell.
+const BELL: &str = "\x07";
+
+/// Something that happens during a game that can be heard.
+#[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Copy, Clone)]
+pub enum Cue {
+    /// A mine has been dug up.
+    Mine,
+
+    /// A key did nothing, like digging an open tile.
+    Invalid,
+    Win,
+    Loss,
+}
+
+impl Cue {
+    const ALL: [Cue; 4] = [Cue::Mine, Cue::Invalid, Cue::Win, Cue::Loss];
+
+    /// The name of the cue in the config file.
+    fn name(self) -> &'static str {
+        match self {
+            Cue::Mine => "mine",
+            Cue::Invalid => "invalid",
+            Cue::Win => "win",
+            Cue::Loss => "loss",
+        }
+    }
+
+    /// How the cue sounds unless the config says otherwise.
+    fn default_sound(self) -> Option<Sound> {
+        match self {
+            Cue::Mine | Cue::Invalid => Some(Sound::Bell),
+            Cue::Win | Cue::Loss => None,
+        }
+    }
+}
+
+/// How a cue is heard.
+#[derive(Debug, Eq, PartialEq, Clone)]
+enum Sound {
+    Bell,
+
+    /// A shell command that plays something, run like a hook.
+    Command(String),
+}
+
+/// The sounds made as a game is played, set up in the config file with
+/// lines like `sound.win = paplay ~/tada.wav`.
+///
+/// Each cue can be `bell`, `off` or a command. The bell rings for mines
+/// and keys that do nothing unless it's turned off, and `sound = off`
+/// silences everything at once.
+#[derive(Debug, Default)]
+pub struct Sounds {
+    sounds: BTreeMap<Cue, Sound>,
+}
+
+impl Sounds {
+    /// Find the sounds set up in the config.
+    pub fn from_config(config: &Config) -> Self {
+        if config.get("sound") == Some("off") {
+            return Self::default();
+        }
+
+        let sounds = Cue::ALL
+            .iter()
+            .filter_map(|&cue| {
+                let sound = match config.get(&format!("sound.{}", cue.name())) {
+                    None => cue.default_sound()?,
+                    Some("off") => return None,
+                    Some("bell") => Sound::Bell,
+                    Some(command) => Sound::Command(String::from(command)),
+                };
+
+                Some((cue, sound))
+            })
+            .collect();
+
+        Self { sounds }
+    }
+
+    /// Check if there's nothing to be heard at all.
+    pub fn is_empty(&self) -> bool {
+        self.sounds.is_empty()
+    }
+
+    /// Make the sound for a cue, if it has one, without waiting for it.
+    ///
+    /// The bell is returned to be written out with the rest of the
+    /// frame, and commands that fail to start are ignored.
+    pub fn play(&self, cue: Cue) -> &'static str {
+        match self.sounds.get(&cue) {
+            Some(Sound::Bell) => BELL,
+            Some(Sound::Command(command)) => {
+                hooks::spawn(&mut hooks::shell(command));
+                ""
+            }
+            None => "",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cues_ring_the_bell_unless_told_otherwise() {
+        let sounds = Sounds::from_config(&Config::parse("sound.invalid = off\n"));
+        assert_eq!(sounds.play(Cue::Mine), BELL);
+        assert_eq!(sounds.play(Cue::Invalid), "");
+        assert_eq!(sounds.play(Cue::Win), "");
+
+        let config = Config::parse("sound.win = paplay tada.wav\nsound.loss = bell\n");
+        let sounds = Sounds::from_config(&config);
+        assert_eq!(
+            sounds.sounds.get(&Cue::Win),
+            Some(&Sound::Command(String::from("paplay tada.wav")))
+        );
+        assert_eq!(sounds.play(Cue::Loss), BELL);
+
+        let config = Config::parse("sound = off\nsound.win = bell\n");
+        assert!(Sounds::from_config(&config).is_empty());
+    }
+}
@@ -0,0 +1,254 @@
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use serde::de::DeserializeOwned;
+
+use crate::game;
+use crate::minefield::MineField;
+use crate::position::Position;
+use crate::protocol::{Cover, Feed, GameState, Status, Tile, Update};
+use crate::terminal::{self, clear, cursor, style, Guard, Key};
+
+/// How long a spectator can hold up the game before they're dropped.
+const WRITE_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// How often a spectator checks for keys between updates.
+const TICK_RATE: Duration = Duration::from_millis(100);
+
+/// The spectators watching a game, and what they've been sent so far.
+#[derive(Default)]
+struct Audience {
+    watchers: Vec<TcpStream>,
+    feed: Feed,
+}
+
+/// Send updates to someone following the game, as lines of JSON.
+pub fn send(stream: &mut TcpStream, updates: &[Update]) -> io::Result<()> {
+    for update in updates {
+        let line = serde_json::to_string(update).unwrap();
+        writeln!(stream, "{}", line)?;
+    }
+
+    Ok(())
+}
+
+/// Streams a game to anyone who connects to a port, so they can watch
+/// it from another terminal.
+///
+/// Spectators get the whole state when they connect, then a diff each
+/// time the field changes. They can't send anything back.
+pub struct Broadcaster {
+    audience: Arc<Mutex<Audience>>,
+}
+
+impl Broadcaster {
+    /// Start letting spectators connect on a port.
+    pub fn start(port: u16) -> io::Result<Self> {
+        let listener = TcpListener::bind(("0.0.0.0", port))?;
+        let audience = Arc::new(Mutex::new(Audience::default()));
+        let shared = Arc::clone(&audience);
+
+        thread::spawn(move || {
+            for mut stream in listener.incoming().filter_map(Result::ok) {
+                let mut audience = shared.lock().unwrap();
+
+                if stream.set_write_timeout(Some(WRITE_TIMEOUT)).is_err() {
+                    continue;
+                }
+
+                // a spectator that has already gone just isn't added.
+                if send(&mut stream, &audience.feed.catch_up()).is_ok() {
+                    audience.watchers.push(stream);
+                }
+            }
+        });
+
+        Ok(Self { audience })
+    }
+
+    /// Send any changes to the field or cursor to every spectator,
+    /// dropping those that have gone.
+    pub fn update(&self, field: &MineField, cursor: Position) {
+        let mut audience = self.audience.lock().unwrap();
+        let updates = audience.feed.updates(field, cursor);
+
+        if !updates.is_empty() {
+            audience
+                .watchers
+                .retain_mut(|stream| send(stream, &updates).is_ok());
+        }
+    }
+}
+
+/// The character for a tile on a spectator's screen, like the board of a
+/// headless game.
+fn tile_char(tile: Option<Tile>) -> char {
+    match tile {
+        None => ' ',
+        Some(Tile::Covered(Cover::Hidden)) => '#',
+        Some(Tile::Covered(Cover::Flag)) => 'F',
+        Some(Tile::Covered(Cover::Mine)) => '*',
+        Some(Tile::Revealed(0)) => '.',
+        Some(Tile::Revealed(count)) => std::char::from_digit(count as u32, 36).unwrap_or('+'),
+    }
+}
+
+/// Draw a game being followed from elsewhere, with the cursors on it in
+/// their own styles and a line about it underneath.
+pub fn draw(
+    raw_stdout: &mut impl Write,
+    state: &GameState,
+    cursors: &[(Position, String)],
+    footer: &str,
+) -> io::Result<()> {
+    for (row, tiles) in state.tiles.iter().enumerate() {
+        let line: Vec<String> = tiles
+            .iter()
+            .enumerate()
+            .map(|(column, &tile)| {
+                let cursor = cursors
+                    .iter()
+                    .find(|(position, _)| *position == Position::new(row, column));
+
+                match cursor {
+                    Some((_, highlight)) => {
+                        format!("{}{}{}", highlight, tile_char(tile), style::Reset)
+                    }
+                    None => tile_char(tile).to_string(),
+                }
+            })
+            .collect();
+        write!(
+            raw_stdout,
+            "{}{}{}",
+            cursor::Goto(1, row as u16 + 1),
+            line.join(" "),
+            clear::UntilNewline
+        )?;
+    }
+
+    let status = match state.status {
+        Status::InProgress => format!("{} mines left", state.mines_remaining),
+        Status::Won => String::from("cleared!"),
+        Status::Lost => String::from("lost"),
+    };
+
+    write!(
+        raw_stdout,
+        "{}{}{}{}{}{}",
+        cursor::Goto(1, state.height as u16 + 2),
+        status,
+        clear::UntilNewline,
+        cursor::Goto(1, state.height as u16 + 3),
+        footer,
+        clear::UntilNewline
+    )?;
+    raw_stdout.flush()
+}
+
+/// Keep up with an update to a game being followed, and where its
+/// player's cursor is, clearing the screen for a new field.
+pub fn follow(
+    update: Update,
+    state: &mut Option<GameState>,
+    cursor: &mut Option<Position>,
+    raw_stdout: &mut impl Write,
+) -> io::Result<()> {
+    match update {
+        Update::State(new_state) => {
+            write!(raw_stdout, "{}", clear::All)?;
+            *state = Some(new_state);
+        }
+        Update::Diff(diff) => {
+            if let Some(state) = state {
+                diff.apply(state);
+            }
+        }
+        Update::Cursor { row, column } => *cursor = Some(Position::new(row, column)),
+    }
+
+    Ok(())
+}
+
+/// The player's cursor, shown inverted like in their own game.
+fn highlight(cursor: Option<Position>) -> Vec<(Position, String)> {
+    cursor
+        .map(|cursor| (cursor, style::Invert.to_string()))
+        .into_iter()
+        .collect()
+}
+
+/// Read lines of JSON from a connection on their own thread, until it
+/// closes or sends something that can't be read.
+pub fn spawn_reader<T: DeserializeOwned + Send + 'static>(stream: TcpStream) -> Receiver<T> {
+    let (sender, receiver) = mpsc::channel();
+
+    thread::spawn(move || {
+        for line in BufReader::new(stream).lines() {
+            let message = match line.map(|line| serde_json::from_str(&line)) {
+                Ok(Ok(message)) => message,
+                _ => break,
+            };
+
+            if sender.send(message).is_err() {
+                break;
+            }
+        }
+    });
+
+    receiver
+}
+
+/// Watch a game being broadcast at an address, like `localhost:4000`,
+/// until 'q' is pressed.
+pub fn watch(address: &str) -> io::Result<()> {
+    let updates: Receiver<Update> = spawn_reader(TcpStream::connect(address)?);
+
+    let keys = game::spawn_key_reader();
+    let mut raw_stdout = Guard::new()?;
+    let mut state: Option<GameState> = None;
+    let mut cursor = None;
+    let footer = format!("watching {}, press 'q' to stop", address);
+
+    write!(raw_stdout, "{}{}", clear::All, cursor::Hide)?;
+    write!(
+        raw_stdout,
+        "{}waiting for the game to start...",
+        cursor::Goto(1, 1)
+    )?;
+    raw_stdout.flush()?;
+
+    loop {
+        match updates.recv_timeout(TICK_RATE) {
+            Ok(update) => {
+                follow(update, &mut state, &mut cursor, &mut *raw_stdout)?;
+
+                if let Some(state) = &state {
+                    draw(&mut *raw_stdout, state, &highlight(cursor), &footer)?;
+                }
+            }
+            Err(RecvTimeoutError::Timeout) => (),
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+
+        if let Ok(Key::Char('q') | terminal::INTERRUPT) = keys.try_recv() {
+            return Ok(());
+        }
+    }
+
+    // the last of the game stays on screen until they're done looking.
+    let footer = "the game has ended, press 'q' to stop";
+
+    match &state {
+        Some(state) => draw(&mut *raw_stdout, state, &highlight(cursor), footer)?,
+        None => write!(raw_stdout, "{}{}", cursor::Goto(1, 1), footer)?,
+    }
+
+    raw_stdout.flush()?;
+    game::wait_for_key(&keys, Key::Char('q'));
+    Ok(())
+}
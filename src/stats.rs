@@ -0,0 +1,372 @@
+use std::collections::BTreeMap;
+use std::io;
+use std::time::Duration;
+
+use crate::storage;
+
+const STATS_FILE: &str = "stats";
+
+/// What the keys of daily challenge lines start with, before the board.
+const DAILY_PREFIX: &str = "daily:";
+
+/// How games on a single board have gone.
+#[derive(Debug, Default, Eq, PartialEq, Copy, Clone)]
+pub struct BoardStats {
+    pub played: u64,
+    pub won: u64,
+    pub total_time: Duration,
+    pub winning_time: Duration,
+    pub best: Option<Duration>,
+}
+
+impl BoardStats {
+    /// The share of games that were won, as a percentage.
+    pub fn win_rate(&self) -> f64 {
+        if self.played == 0 {
+            0.0
+        } else {
+            self.won as f64 / self.played as f64 * 100.0
+        }
+    }
+
+    /// The average time taken to win, if there have been any wins.
+    pub fn average(&self) -> Option<Duration> {
+        if self.won == 0 {
+            None
+        } else {
+            Some(self.winning_time / self.won as u32)
+        }
+    }
+
+    /// Read a line of the stats file, less its key.
+    fn parse(fields: &[&str]) -> Option<Self> {
+        let number = |index: usize| fields.get(index)?.parse::<u64>().ok();
+
+        if fields.len() != 5 {
+            return None;
+        }
+
+        Some(Self {
+            played: number(0)?,
+            won: number(1)?,
+            total_time: Duration::from_millis(number(2)?),
+            winning_time: Duration::from_millis(number(3)?),
+            best: match fields[4] {
+                "-" => None,
+                _ => Some(Duration::from_millis(number(4)?)),
+            },
+        })
+    }
+
+    /// Write the stats as they're kept in the stats file, less the key.
+    fn to_line(self) -> String {
+        let best = match self.best {
+            Some(best) => best.as_millis().to_string(),
+            None => String::from("-"),
+        };
+
+        format!(
+            "{} {} {} {} {}",
+            self.played,
+            self.won,
+            self.total_time.as_millis(),
+            self.winning_time.as_millis(),
+            best
+        )
+    }
+}
+
+/// How the daily challenges on a single board have gone.
+#[derive(Debug, Default, Eq, PartialEq, Copy, Clone)]
+pub struct DailyStats {
+    pub played: u64,
+    pub won: u64,
+
+    /// The last day a challenge was played, counted from 1970-01-01.
+    pub last_day: u64,
+
+    /// How many days in a row have been won, up to the last one played.
+    pub streak: u64,
+    pub best_streak: u64,
+}
+
+impl DailyStats {
+    /// Read a daily challenge line of the stats file, less its key.
+    fn parse(fields: &[&str]) -> Option<Self> {
+        let numbers: Vec<u64> = fields
+            .iter()
+            .map(|field| field.parse().ok())
+            .collect::<Option<_>>()?;
+
+        match numbers[..] {
+            [played, won, last_day, streak, best_streak] => Some(Self {
+                played,
+                won,
+                last_day,
+                streak,
+                best_streak,
+            }),
+            _ => None,
+        }
+    }
+
+    /// Write the stats as they're kept in the stats file, less the key.
+    fn to_line(self) -> String {
+        format!(
+            "{} {} {} {} {}",
+            self.played, self.won, self.last_day, self.streak, self.best_streak
+        )
+    }
+}
+
+/// Describe a time in seconds, to a tenth of a second.
+fn describe_time(time: Option<Duration>) -> String {
+    match time {
+        Some(time) => format!("{:.1}s", time.as_secs_f64()),
+        None => String::from("-"),
+    }
+}
+
+/// Statistics for every board that has been played, keyed by board,
+/// and for the daily challenges on each board.
+#[derive(Debug, Default)]
+pub struct Stats {
+    boards: BTreeMap<String, BoardStats>,
+    daily: BTreeMap<String, DailyStats>,
+}
+
+impl Stats {
+    /// Read stats from the text of a stats file, skipping lines
+    /// that can't be understood.
+    pub fn parse(text: &str) -> Self {
+        let mut stats = Self::default();
+
+        for line in text.lines() {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let (key, rest) = match fields.split_first() {
+                Some(split) => split,
+                None => continue,
+            };
+
+            match key.strip_prefix(DAILY_PREFIX) {
+                Some(board) => {
+                    if let Some(daily) = DailyStats::parse(rest) {
+                        stats.daily.insert(String::from(board), daily);
+                    }
+                }
+                None => {
+                    if let Some(board) = BoardStats::parse(rest) {
+                        stats.boards.insert(String::from(*key), board);
+                    }
+                }
+            }
+        }
+
+        stats
+    }
+
+    /// Load the stats from previous games.
+    pub fn load() -> Self {
+        Self::parse(&storage::read_data_file(STATS_FILE).unwrap_or_default())
+    }
+
+    /// Write the stats out as they're kept in the stats file.
+    fn to_text(&self) -> String {
+        let boards = self
+            .boards
+            .iter()
+            .map(|(key, stats)| format!("{} {}", key, stats.to_line()));
+        let daily = self
+            .daily
+            .iter()
+            .map(|(key, stats)| format!("{}{} {}", DAILY_PREFIX, key, stats.to_line()));
+
+        boards.chain(daily).collect::<Vec<_>>().join("\n")
+    }
+
+    /// Write the stats back to the stats file.
+    pub fn save(&self) -> io::Result<()> {
+        storage::write_data_file(STATS_FILE, &self.to_text())
+    }
+
+    /// Count a finished game on a board.
+    pub fn record(&mut self, key: &str, won: bool, time: Duration) {
+        let stats = self.boards.entry(String::from(key)).or_default();
+
+        stats.played += 1;
+        stats.total_time += time;
+
+        if won {
+            stats.won += 1;
+            stats.winning_time += time;
+            stats.best = Some(stats.best.map_or(time, |best| best.min(time)));
+        }
+    }
+
+    /// Count a finished daily challenge on a board, carrying on the
+    /// streak if the day before was won. Only the first go at each
+    /// day's board counts, so this returns `None` for any others, or
+    /// the streak after counting it.
+    pub fn record_daily(&mut self, key: &str, day: u64, won: bool) -> Option<u64> {
+        let stats = self.daily.entry(String::from(key)).or_default();
+
+        if stats.played > 0 && stats.last_day >= day {
+            return None;
+        }
+
+        let is_consecutive = stats.streak > 0 && stats.last_day + 1 == day;
+
+        stats.played += 1;
+        stats.last_day = day;
+        stats.streak = match (won, is_consecutive) {
+            (false, _) => 0,
+            (true, true) => stats.streak + 1,
+            (true, false) => 1,
+        };
+
+        if won {
+            stats.won += 1;
+            stats.best_streak = stats.best_streak.max(stats.streak);
+        }
+
+        Some(stats.streak)
+    }
+
+    /// Lay out every board's stats in a table, one row per board,
+    /// followed by the daily challenges.
+    pub fn summary(&self) -> String {
+        if self.boards.is_empty() && self.daily.is_empty() {
+            return String::from("No games have been played yet.");
+        }
+
+        let mut sections = Vec::new();
+
+        if !self.boards.is_empty() {
+            sections.push(self.board_table());
+        }
+
+        if !self.daily.is_empty() {
+            sections.push(self.daily_table());
+        }
+
+        sections.join("\n\n")
+    }
+
+    /// Lay out the daily challenges, one row per board.
+    fn daily_table(&self) -> String {
+        let key_width = self.daily.keys().map(String::len).max().unwrap_or(0).max(5);
+
+        let mut lines = vec![format!(
+            "{:<width$}  {:>6}  {:>5}  {:>6}  {:>4}",
+            "daily",
+            "played",
+            "won",
+            "streak",
+            "best",
+            width = key_width
+        )];
+
+        for (key, stats) in self.daily.iter() {
+            lines.push(format!(
+                "{:<width$}  {:>6}  {:>5}  {:>6}  {:>4}",
+                key,
+                stats.played,
+                stats.won,
+                stats.streak,
+                stats.best_streak,
+                width = key_width
+            ));
+        }
+
+        lines.join("\n")
+    }
+
+    /// Lay out every board's stats in a table, one row per board.
+    fn board_table(&self) -> String {
+        let key_width = self
+            .boards
+            .keys()
+            .map(String::len)
+            .max()
+            .unwrap_or(0)
+            .max(5);
+
+        let mut lines = vec![format!(
+            "{:<width$}  {:>6}  {:>5}  {:>5}  {:>8}  {:>8}  {:>9}",
+            "board",
+            "played",
+            "won",
+            "win%",
+            "best",
+            "average",
+            "total",
+            width = key_width
+        )];
+
+        for (key, stats) in self.boards.iter() {
+            lines.push(format!(
+                "{:<width$}  {:>6}  {:>5}  {:>4.0}%  {:>8}  {:>8}  {:>9}",
+                key,
+                stats.played,
+                stats.won,
+                stats.win_rate(),
+                describe_time(stats.best),
+                describe_time(stats.average()),
+                describe_time(Some(stats.total_time)),
+                width = key_width
+            ));
+        }
+
+        lines.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stats_survive_a_round_trip() {
+        let mut stats = Stats::parse("expert 3 1 90000 40000 40000\nbroken line\n");
+        stats.record("expert", true, Duration::from_secs(30));
+        stats.record("expert", false, Duration::from_secs(5));
+        stats.record("beginner", false, Duration::from_secs(2));
+
+        let expert = stats.boards["expert"];
+        assert_eq!((expert.played, expert.won), (5, 2));
+        assert_eq!(expert.best, Some(Duration::from_secs(30)));
+        assert_eq!(expert.average(), Some(Duration::from_secs(35)));
+        assert_eq!(expert.win_rate(), 40.0);
+        assert_eq!(stats.boards["beginner"].best, None);
+
+        let reloaded = Stats::parse(&stats.to_text());
+        assert_eq!(reloaded.boards.get("expert"), Some(&expert));
+
+        assert!(stats
+            .summary()
+            .lines()
+            .nth(2)
+            .unwrap()
+            .starts_with("expert"));
+    }
+
+    #[test]
+    fn daily_streaks_need_a_win_every_day() {
+        let mut stats = Stats::default();
+        assert_eq!(stats.record_daily("beginner", 100, true), Some(1));
+        assert_eq!(stats.record_daily("beginner", 100, false), None);
+        assert_eq!(stats.record_daily("beginner", 101, true), Some(2));
+        assert_eq!(stats.record_daily("expert", 101, true), Some(1));
+
+        // skipping a day starts the streak over, and so does a loss.
+        assert_eq!(stats.record_daily("beginner", 103, true), Some(1));
+        assert_eq!(stats.record_daily("beginner", 104, false), Some(0));
+
+        let reloaded = Stats::parse(&stats.to_text());
+        let beginner = reloaded.daily["beginner"];
+        assert_eq!((beginner.played, beginner.won), (4, 3));
+        assert_eq!((beginner.streak, beginner.best_streak), (0, 2));
+        assert!(reloaded.boards.is_empty());
+        assert!(reloaded.summary().starts_with("daily"));
+    }
+}
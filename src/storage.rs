@@ -0,0 +1,72 @@
+use std::env;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+/// Find the directory used to keep game data between runs.
+///
+/// This follows the XDG convention, falling back on
+/// `~/.local/share/minesweeper` if it isn't configured.
+pub fn data_dir() -> Option<PathBuf> {
+    let base = match env::var_os("XDG_DATA_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => PathBuf::from(env::var_os("HOME")?).join(".local/share"),
+    };
+
+    Some(base.join("minesweeper"))
+}
+
+/// Find the directory that the player's settings are kept in.
+///
+/// Like `data_dir`, this follows the XDG convention, falling
+/// back on `~/.config/minesweeper` if it isn't configured.
+pub fn config_dir() -> Option<PathBuf> {
+    let base = match env::var_os("XDG_CONFIG_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => PathBuf::from(env::var_os("HOME")?).join(".config"),
+    };
+
+    Some(base.join("minesweeper"))
+}
+
+fn no_data_dir() -> io::Error {
+    io::Error::new(io::ErrorKind::NotFound, "Couldn't find a data directory.")
+}
+
+/// Read a data file, treating a missing file as an empty one.
+pub fn read_data_file(name: &str) -> io::Result<String> {
+    let path = data_dir().ok_or_else(no_data_dir)?.join(name);
+
+    match fs::read_to_string(path) {
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(String::new()),
+        result => result,
+    }
+}
+
+/// Replace the contents of a data file, creating it if needed.
+pub fn write_data_file(name: &str, contents: &str) -> io::Result<()> {
+    let dir = data_dir().ok_or_else(no_data_dir)?;
+    fs::create_dir_all(&dir)?;
+
+    fs::write(dir.join(name), contents)
+}
+
+/// Find a directory inside the data directory, creating it if needed.
+pub fn data_subdir(name: &str) -> io::Result<PathBuf> {
+    let dir = data_dir().ok_or_else(no_data_dir)?.join(name);
+    fs::create_dir_all(&dir)?;
+
+    Ok(dir)
+}
+
+/// Read a file from the config directory, treating a missing file as an empty one.
+pub fn read_config_file(name: &str) -> io::Result<String> {
+    let dir = config_dir().ok_or_else(|| {
+        io::Error::new(io::ErrorKind::NotFound, "Couldn't find a config directory.")
+    })?;
+
+    match fs::read_to_string(dir.join(name)) {
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(String::new()),
+        result => result,
+    }
+}
@@ -0,0 +1,176 @@
+//! Escape codes for drawing on the terminal, in the same shape as
+//! termion's, for backends that don't come with their own.
+
+/// Colours for text and its background.
+pub mod color {
+    use std::fmt;
+
+    /// A colour that text or its background can be drawn in.
+    pub trait Color {
+        fn write_fg(&self, f: &mut fmt::Formatter) -> fmt::Result;
+        fn write_bg(&self, f: &mut fmt::Formatter) -> fmt::Result;
+    }
+
+    impl<C: Color + ?Sized> Color for &C {
+        fn write_fg(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            (*self).write_fg(f)
+        }
+
+        fn write_bg(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            (*self).write_bg(f)
+        }
+    }
+
+    /// One of the 256 colours in the terminal's palette.
+    #[derive(Debug, Copy, Clone)]
+    pub struct AnsiValue(pub u8);
+
+    impl Color for AnsiValue {
+        fn write_fg(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "\x1b[38;5;{}m", self.0)
+        }
+
+        fn write_bg(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "\x1b[48;5;{}m", self.0)
+        }
+    }
+
+    /// The terminal's own colour for text or its background.
+    #[derive(Debug, Copy, Clone)]
+    pub struct Reset;
+
+    impl Color for Reset {
+        fn write_fg(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "\x1b[39m")
+        }
+
+        fn write_bg(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "\x1b[49m")
+        }
+    }
+
+    macro_rules! palette_colors {
+        ($($name:ident = $value:expr,)*) => {
+            $(
+                #[derive(Debug, Copy, Clone)]
+                pub struct $name;
+
+                impl Color for $name {
+                    fn write_fg(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                        AnsiValue($value).write_fg(f)
+                    }
+
+                    fn write_bg(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                        AnsiValue($value).write_bg(f)
+                    }
+                }
+            )*
+        };
+    }
+
+    palette_colors! {
+        Red = 1,
+        Green = 2,
+        Yellow = 3,
+        Blue = 4,
+        Magenta = 5,
+        Cyan = 6,
+        White = 7,
+        LightBlack = 8,
+        LightRed = 9,
+        LightGreen = 10,
+        LightYellow = 11,
+        LightBlue = 12,
+        LightMagenta = 13,
+        LightCyan = 14,
+    }
+
+    /// Draw text in a colour.
+    pub struct Fg<C: Color>(pub C);
+
+    impl<C: Color> fmt::Display for Fg<C> {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            self.0.write_fg(f)
+        }
+    }
+
+    /// Draw the background behind text in a colour.
+    pub struct Bg<C: Color>(pub C);
+
+    impl<C: Color> fmt::Display for Bg<C> {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            self.0.write_bg(f)
+        }
+    }
+}
+
+/// Write a fixed escape code for each of a list of types.
+macro_rules! escape_codes {
+    ($($(#[$doc:meta])* $name:ident = $code:expr,)*) => {
+        $(
+            $(#[$doc])*
+            #[derive(Debug, Copy, Clone)]
+            pub struct $name;
+
+            impl std::fmt::Display for $name {
+                fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                    write!(f, $code)
+                }
+            }
+        )*
+    };
+}
+
+/// Moving and showing the cursor.
+pub mod cursor {
+    use std::fmt;
+
+    /// Move the cursor to a column and row, counted from 1.
+    #[derive(Debug, Copy, Clone)]
+    pub struct Goto(pub u16, pub u16);
+
+    impl fmt::Display for Goto {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "\x1b[{};{}H", self.1, self.0)
+        }
+    }
+
+    escape_codes! {
+        /// Show the cursor.
+        Show = "\x1b[?25h",
+        /// Hide the cursor.
+        Hide = "\x1b[?25l",
+        /// Remember where the cursor is.
+        Save = "\x1b[s",
+        /// Move the cursor back to where it was remembered.
+        Restore = "\x1b[u",
+    }
+}
+
+/// Clearing parts of the screen.
+pub mod clear {
+    escape_codes! {
+        /// Clear the whole screen.
+        All = "\x1b[2J",
+        /// Clear the line the cursor is on.
+        CurrentLine = "\x1b[2K",
+        /// Clear from the cursor to the end of its line.
+        UntilNewline = "\x1b[K",
+        /// Clear from the cursor to the end of the screen.
+        AfterCursor = "\x1b[J",
+    }
+}
+
+/// Styles for text.
+pub mod style {
+    escape_codes! {
+        /// Swap the colours of text and its background.
+        Invert = "\x1b[7m",
+        /// Strike a line through text.
+        CrossedOut = "\x1b[9m",
+        /// Stop striking through text.
+        NoCrossedOut = "\x1b[29m",
+        /// Go back to plain text.
+        Reset = "\x1b[m",
+    }
+}
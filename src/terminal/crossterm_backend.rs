@@ -0,0 +1,106 @@
+use std::io::{self, stdout, Stdout, Write};
+
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use crossterm::terminal;
+
+use super::{Backend, Key};
+
+/// The terminal, through crossterm.
+pub struct CrosstermBackend;
+
+/// Output to stdout, holding the terminal in raw mode until it's dropped.
+pub struct RawStdout(Stdout);
+
+impl Write for RawStdout {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.flush()
+    }
+}
+
+impl Drop for RawStdout {
+    fn drop(&mut self) {
+        crossterm::execute!(self.0, event::DisableFocusChange).ok();
+        terminal::disable_raw_mode().ok();
+    }
+}
+
+/// Keys read from the terminal by crossterm.
+pub struct KeyReader;
+
+impl Iterator for KeyReader {
+    type Item = Key;
+
+    fn next(&mut self) -> Option<Key> {
+        loop {
+            let key = match event::read().ok()? {
+                // Windows reports releasing keys too, which are skipped.
+                Event::Key(key) if key.kind != KeyEventKind::Release => key,
+                Event::FocusGained => return Some(Key::FocusGained),
+                Event::FocusLost => return Some(Key::FocusLost),
+                _ => continue,
+            };
+
+            let is_ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+            let is_shift = key.modifiers.contains(KeyModifiers::SHIFT);
+
+            return Some(match key.code {
+                KeyCode::Char(c) if is_ctrl => Key::Ctrl(c),
+                KeyCode::Char(c) => Key::Char(c),
+                KeyCode::Enter => Key::Char('\n'),
+                KeyCode::Tab => Key::Char('\t'),
+                KeyCode::Up if is_ctrl => Key::CtrlUp,
+                KeyCode::Down if is_ctrl => Key::CtrlDown,
+                KeyCode::Left if is_ctrl => Key::CtrlLeft,
+                KeyCode::Right if is_ctrl => Key::CtrlRight,
+                KeyCode::Up if is_shift => Key::ShiftUp,
+                KeyCode::Down if is_shift => Key::ShiftDown,
+                KeyCode::Left if is_shift => Key::ShiftLeft,
+                KeyCode::Right if is_shift => Key::ShiftRight,
+                KeyCode::Up => Key::Up,
+                KeyCode::Down => Key::Down,
+                KeyCode::Left => Key::Left,
+                KeyCode::Right => Key::Right,
+                KeyCode::Home => Key::Home,
+                KeyCode::End => Key::End,
+                KeyCode::PageUp => Key::PageUp,
+                KeyCode::PageDown => Key::PageDown,
+                KeyCode::Backspace => Key::Backspace,
+                KeyCode::Esc => Key::Esc,
+                _ => continue,
+            });
+        }
+    }
+}
+
+impl Backend for CrosstermBackend {
+    type Output = RawStdout;
+    type Keys = KeyReader;
+
+    fn raw_output() -> io::Result<Self::Output> {
+        terminal::enable_raw_mode()?;
+
+        // writing a command through crossterm switches on escape
+        // codes in Windows consoles that don't have them already.
+        let mut stdout = stdout();
+        crossterm::execute!(stdout, crossterm::cursor::Show)?;
+
+        Ok(RawStdout(stdout))
+    }
+
+    fn keys() -> Self::Keys {
+        KeyReader
+    }
+
+    fn report_focus(output: &mut Self::Output) -> io::Result<bool> {
+        crossterm::execute!(output, event::EnableFocusChange)?;
+        Ok(true)
+    }
+
+    fn size() -> io::Result<(u16, u16)> {
+        terminal::size()
+    }
+}
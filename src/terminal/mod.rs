@@ -0,0 +1,166 @@
+use std::io::{self, Write};
+use std::ops::{Deref, DerefMut};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Mutex;
+
+#[cfg(not(any(feature = "termion", feature = "crossterm")))]
+compile_error!("pick a terminal backend with the `termion` or `crossterm` feature.");
+
+#[cfg(not(feature = "crossterm"))]
+mod termion_backend;
+
+#[cfg(not(feature = "crossterm"))]
+pub use termion_backend::{clear, color, cursor, style, TermionBackend as Terminal};
+
+#[cfg(feature = "crossterm")]
+mod ansi;
+#[cfg(feature = "crossterm")]
+mod crossterm_backend;
+
+#[cfg(feature = "crossterm")]
+pub use ansi::{clear, color, cursor, style};
+#[cfg(feature = "crossterm")]
+pub use crossterm_backend::CrosstermBackend as Terminal;
+
+/// A key that was pressed, named the same way as termion's keys.
+///
+/// Both backends read their keys into this, since termion's own keys
+/// can't tell an arrow with shift or ctrl held from a plain one.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum Key {
+    Char(char),
+    Ctrl(char),
+    Up,
+    Down,
+    Left,
+    Right,
+    ShiftUp,
+    ShiftDown,
+    ShiftLeft,
+    ShiftRight,
+    CtrlUp,
+    CtrlDown,
+    CtrlLeft,
+    CtrlRight,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    Backspace,
+    Esc,
+
+    /// The terminal window was switched back to, if it reports focus.
+    /// Only crossterm reads these.
+    FocusGained,
+
+    /// The terminal window was switched away from, if it reports focus.
+    FocusLost,
+}
+
+/// What the game needs from the terminal it's played in.
+///
+/// termion is used by default. Building with the `crossterm` feature
+/// (and without the default features) uses crossterm instead, which
+/// also works on Windows. Either way, the game draws with the escape
+/// codes in `color`, `cursor`, `clear` and `style`.
+pub trait Backend {
+    /// Output to the terminal, which stays in raw mode until it's dropped.
+    type Output: Write;
+
+    /// Keys read from the terminal as they're pressed.
+    type Keys: Iterator<Item = Key>;
+
+    /// Switch the terminal to raw mode, returning its output.
+    fn raw_output() -> io::Result<Self::Output>;
+
+    /// Start reading keys. This blocks until each key is pressed,
+    /// and ends if the terminal can't be read anymore.
+    fn keys() -> Self::Keys;
+
+    /// Ask the terminal to say when it gains or loses focus, which is
+    /// read as `Key::FocusGained` and `Key::FocusLost`. Returns whether
+    /// the backend can read those reports at all.
+    fn report_focus(output: &mut Self::Output) -> io::Result<bool>;
+
+    /// The size of the terminal, in columns and rows.
+    fn size() -> io::Result<(u16, u16)>;
+}
+
+/// The output of the terminal in raw mode.
+pub type RawOutput = <Terminal as Backend>::Output;
+
+/// The key that interrupts a program in a terminal. In raw mode, it's
+/// read like any other key instead, so the game has to handle it.
+pub const INTERRUPT: Key = Key::Ctrl('c');
+
+/// The message of a panic that happened while the terminal was guarded,
+/// kept until it's been put back to normal.
+static PANIC_MESSAGE: Mutex<Option<String>> = Mutex::new(None);
+
+/// The terminal in raw mode, for as long as the guard is kept.
+///
+/// However it's dropped, even while panicking, the guard shows the
+/// cursor, clears the screen and puts the terminal back in cooked
+/// mode, so the shell is never left unusable.
+pub struct Guard(RawOutput);
+
+impl Guard {
+    /// Switch the terminal to raw mode.
+    pub fn new() -> io::Result<Self> {
+        Ok(Guard(Terminal::raw_output()?))
+    }
+}
+
+impl Deref for Guard {
+    type Target = RawOutput;
+
+    fn deref(&self) -> &RawOutput {
+        &self.0
+    }
+}
+
+impl DerefMut for Guard {
+    fn deref_mut(&mut self) -> &mut RawOutput {
+        &mut self.0
+    }
+}
+
+impl Drop for Guard {
+    fn drop(&mut self) {
+        // the output is dropped after this, which leaves raw mode.
+        write!(
+            self.0,
+            "{}{}{}{}",
+            style::Reset,
+            cursor::Show,
+            clear::All,
+            cursor::Goto(1, 1)
+        )
+        .ok();
+        self.0.flush().ok();
+    }
+}
+
+/// Run something that takes over the terminal with a `Guard`.
+///
+/// A panic's message is held back until the guard has put the terminal
+/// back, since it would come out garbled in raw mode, and then the panic
+/// carries on as normal.
+pub fn run_guarded<T>(run: impl FnOnce() -> T) -> T {
+    let default_hook = panic::take_hook();
+
+    panic::set_hook(Box::new(|info| {
+        *PANIC_MESSAGE.lock().unwrap() = Some(info.to_string());
+    }));
+
+    let result = panic::catch_unwind(AssertUnwindSafe(run));
+    panic::set_hook(default_hook);
+
+    result.unwrap_or_else(|payload| {
+        if let Some(message) = PANIC_MESSAGE.lock().unwrap().take() {
+            eprintln!("{}", message);
+        }
+
+        panic::resume_unwind(payload)
+    })
+}
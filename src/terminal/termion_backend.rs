@@ -0,0 +1,81 @@
+use std::io::{self, stdin, stdout, Stdin, Stdout};
+
+use termion::event::{self, Event};
+use termion::input::{Events, TermRead};
+use termion::raw::{IntoRawMode, RawTerminal};
+
+pub use termion::{clear, color, cursor, style};
+
+use super::{Backend, Key};
+
+/// The terminal, through termion.
+pub struct TermionBackend;
+
+/// Keys read from stdin by termion.
+pub struct KeyReader(Events<Stdin>);
+
+impl Iterator for KeyReader {
+    type Item = Key;
+
+    fn next(&mut self) -> Option<Key> {
+        loop {
+            let key = match self.0.next()?.ok()? {
+                Event::Key(key) => match key {
+                    event::Key::Char(c) => Key::Char(c),
+                    event::Key::Ctrl(c) => Key::Ctrl(c),
+                    event::Key::Up => Key::Up,
+                    event::Key::Down => Key::Down,
+                    event::Key::Left => Key::Left,
+                    event::Key::Right => Key::Right,
+                    event::Key::Home => Key::Home,
+                    event::Key::End => Key::End,
+                    event::Key::PageUp => Key::PageUp,
+                    event::Key::PageDown => Key::PageDown,
+                    event::Key::Backspace => Key::Backspace,
+                    event::Key::Esc => Key::Esc,
+                    _ => continue,
+                },
+
+                // termion doesn't know arrows with modifiers, which come
+                // as `ESC [ 1 ; m X`, where m is 2 for shift or 5 for ctrl.
+                Event::Unsupported(bytes) => match &bytes[..] {
+                    b"\x1b[1;2A" => Key::ShiftUp,
+                    b"\x1b[1;2B" => Key::ShiftDown,
+                    b"\x1b[1;2C" => Key::ShiftRight,
+                    b"\x1b[1;2D" => Key::ShiftLeft,
+                    b"\x1b[1;5A" => Key::CtrlUp,
+                    b"\x1b[1;5B" => Key::CtrlDown,
+                    b"\x1b[1;5C" => Key::CtrlRight,
+                    b"\x1b[1;5D" => Key::CtrlLeft,
+                    _ => continue,
+                },
+                Event::Mouse(_) => continue,
+            };
+
+            return Some(key);
+        }
+    }
+}
+
+impl Backend for TermionBackend {
+    type Output = RawTerminal<Stdout>;
+    type Keys = KeyReader;
+
+    fn raw_output() -> io::Result<Self::Output> {
+        stdout().into_raw_mode()
+    }
+
+    fn keys() -> Self::Keys {
+        KeyReader(stdin().events())
+    }
+
+    // termion can't parse focus reports, and stops reading keys
+    // altogether when it meets one, so they're never switched on.
+    fn report_focus(_output: &mut Self::Output) -> io::Result<bool> {
+        Ok(false)
+    }
+
+    fn size() -> io::Result<(u16, u16)> {
+        termion::terminal_size()
+    }
+}
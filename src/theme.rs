@@ -0,0 +1,317 @@
+use crate::terminal::{color, style};
+use crate::tile::Note;
+
+/// The colours of the numbers 1 to 8 on the default theme, as colours
+/// in the terminal's palette.
+const NUMBER_COLORS: [u8; 8] = [12, 2, 9, 4, 1, 6, 7, 8];
+
+/// The colours of the numbers 1 to 8 on the colourblind theme, taken
+/// from the Okabe-Ito palette so that no two are told apart by red and
+/// green alone.
+const COLORBLIND_NUMBER_COLORS: [u8; 8] = [74, 36, 166, 25, 175, 178, 227, 245];
+
+/// How tiles are drawn: which characters they use, and their colours.
+#[derive(Debug, Default, Eq, PartialEq, Copy, Clone)]
+pub enum Theme {
+    /// Coloured numbers and flags.
+    #[default]
+    Default,
+
+    /// Like the default theme, with numbers in colours that
+    /// stay distinct with any common kind of colourblindness.
+    Colorblind,
+
+    /// Plain ASCII without any colour, for terminals that can't show it.
+    /// Flags are `F`, mines are `*` and hidden target tiles are `+`.
+    Mono,
+}
+
+impl Theme {
+    /// Parse the name of a theme, as given on the command line or in the config.
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "default" => Some(Theme::Default),
+            "colorblind" | "colourblind" => Some(Theme::Colorblind),
+            "mono" => Some(Theme::Mono),
+            _ => None,
+        }
+    }
+
+    /// Check if the theme is drawn in colour at all.
+    #[inline]
+    pub fn has_color(self) -> bool {
+        self != Theme::Mono
+    }
+
+    /// Draw some text in a colour, or leave it plain without colour.
+    pub fn paint(self, text: &str, color: &dyn color::Color) -> String {
+        if self.has_color() {
+            format!("{}{}{}", color::Fg(color), text, color::Fg(color::Reset))
+        } else {
+            String::from(text)
+        }
+    }
+
+    /// Draw a hidden tile, highlighting it if it's in the target region.
+    pub fn hidden(self, skin: Skin, in_target: bool) -> String {
+        match (self.has_color(), in_target) {
+            (false, true) => String::from(skin.target(true)),
+            (true, true) => self.paint(skin.target(false), &color::Yellow),
+            (_, false) => String::from(skin.hidden()),
+        }
+    }
+
+    /// Draw a flagged tile.
+    pub fn flag(self, skin: Skin) -> String {
+        self.paint(skin.flag(!self.has_color()), &color::LightMagenta)
+    }
+
+    /// Draw a hidden tile with a note on it, each kind in its own
+    /// colour that none of the numbers use.
+    pub fn note(self, skin: Skin, note: Note) -> String {
+        let color: &dyn color::Color = match note {
+            Note::Mine => &color::LightYellow,
+            Note::Safe => &color::LightGreen,
+            Note::Mark(_) => &color::LightCyan,
+        };
+
+        self.paint(&skin.note(note), color)
+    }
+
+    /// Draw a mine that has been dug up.
+    pub fn mine(self, skin: Skin) -> String {
+        String::from(skin.mine(!self.has_color()))
+    }
+
+    /// Draw the mine that lost the game, on a red background.
+    pub fn fatal_mine(self, skin: Skin) -> String {
+        if self.has_color() {
+            let mine = skin.fatal_mine(false);
+            format!(
+                "{}{}{}",
+                color::Bg(color::Red),
+                mine,
+                color::Bg(color::Reset)
+            )
+        } else {
+            String::from(skin.fatal_mine(true))
+        }
+    }
+
+    /// Draw a flag that turned out to have no mine under it, crossed out.
+    pub fn wrong_flag(self, skin: Skin) -> String {
+        if self.has_color() {
+            let flag = self.paint(skin.wrong_flag(false), &color::Red);
+            format!("{}{}{}", style::CrossedOut, flag, style::NoCrossedOut)
+        } else {
+            String::from(skin.wrong_flag(true))
+        }
+    }
+
+    /// Draw a revealed tile with some mines around it. Counts past 8
+    /// share the colour of 8.
+    pub fn number(self, skin: Skin, mines: usize) -> String {
+        let digit = skin.number(mines);
+
+        match self.number_color(mines) {
+            Some(value) => self.paint(&digit, &color::AnsiValue(value)),
+            None => digit,
+        }
+    }
+
+    /// The colour in the terminal's palette that a number is drawn in,
+    /// for frontends that draw it themselves. Counts past 8 share the
+    /// colour of 8, and the mono theme has none.
+    pub fn number_color(self, mines: usize) -> Option<u8> {
+        let index = mines.min(8) - 1;
+
+        match self {
+            Theme::Default => Some(NUMBER_COLORS[index]),
+            Theme::Colorblind => Some(COLORBLIND_NUMBER_COLORS[index]),
+            Theme::Mono => None,
+        }
+    }
+}
+
+/// The glyphs that tiles are drawn with.
+///
+/// Every tile takes up a cell two columns wide. Narrow glyphs are
+/// followed by a space, and wide ones like emoji fill the cell, so
+/// the grid lines up the same way whichever skin is used.
+#[derive(Debug, Default, Eq, PartialEq, Copy, Clone)]
+pub enum Skin {
+    /// Plain ASCII, with `#` for hidden tiles.
+    #[default]
+    Ascii,
+
+    /// Shaded blocks for hidden tiles, with symbols for flags and mines.
+    Unicode,
+
+    /// Emoji flags and mines, and full-width numbers to match them.
+    Emoji,
+}
+
+impl Skin {
+    /// Parse the name of a skin, as given on the command line or in the config.
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "ascii" => Some(Skin::Ascii),
+            "unicode" => Some(Skin::Unicode),
+            "emoji" => Some(Skin::Emoji),
+            _ => None,
+        }
+    }
+
+    /// Check if every glyph is two columns wide, filling its cell.
+    #[inline]
+    pub fn is_wide(self) -> bool {
+        self == Skin::Emoji
+    }
+
+    /// An empty cell, for revealed tiles without mines around them
+    /// and void tiles.
+    pub fn blank(self) -> &'static str {
+        match self {
+            Skin::Emoji => "  ",
+            _ => " ",
+        }
+    }
+
+    fn hidden(self) -> &'static str {
+        match self {
+            Skin::Ascii => "#",
+            Skin::Unicode => "▒",
+            Skin::Emoji => "⬜",
+        }
+    }
+
+    /// A hidden tile in the target region, which has to stand out
+    /// on its own when it isn't highlighted by colour.
+    fn target(self, plain: bool) -> &'static str {
+        match (self, plain) {
+            (Skin::Ascii, true) => "+",
+            (Skin::Unicode, true) => "▓",
+            (Skin::Emoji, _) => "🟨",
+            (skin, false) => skin.hidden(),
+        }
+    }
+
+    fn flag(self, plain: bool) -> &'static str {
+        match (self, plain) {
+            (Skin::Ascii, true) => "F",
+            (Skin::Ascii, false) => "~",
+            (Skin::Unicode, _) => "⚑",
+            (Skin::Emoji, _) => "🚩",
+        }
+    }
+
+    fn mine(self, plain: bool) -> &'static str {
+        match (self, plain) {
+            (Skin::Ascii, true) => "*",
+            (Skin::Ascii, false) => "X",
+            (Skin::Unicode, _) => "✹",
+            (Skin::Emoji, _) => "💣",
+        }
+    }
+
+    /// The mine that lost the game, which needs a glyph of its own
+    /// when there's no background colour to pick it out.
+    fn fatal_mine(self, plain: bool) -> &'static str {
+        match (self, plain) {
+            (Skin::Ascii, true) => "X",
+            (Skin::Unicode, true) => "✖",
+            (Skin::Emoji, _) => "💥",
+            (skin, false) => skin.mine(false),
+        }
+    }
+
+    /// A flag without a mine under it, which is struck through when
+    /// there's colour, and has a glyph of its own otherwise.
+    fn wrong_flag(self, plain: bool) -> &'static str {
+        match (self, plain) {
+            (Skin::Ascii, true) => "f",
+            (Skin::Unicode, true) => "⚐",
+            (Skin::Emoji, _) => "❌",
+            (skin, false) => skin.flag(false),
+        }
+    }
+
+    /// A note on a hidden tile. Marks are letters, so they're never
+    /// mistaken for the numbers on revealed tiles.
+    fn note(self, note: Note) -> String {
+        match (self, note) {
+            (Skin::Ascii, Note::Mine) => String::from("?"),
+            (Skin::Ascii, Note::Safe) => String::from("o"),
+            (Skin::Unicode, Note::Mine) => String::from("?"),
+            (Skin::Unicode, Note::Safe) => String::from("○"),
+            (Skin::Emoji, Note::Mine) => String::from("❓"),
+            (Skin::Emoji, Note::Safe) => String::from("🟢"),
+
+            // full-width capitals start at U+FF21.
+            (Skin::Emoji, Note::Mark(mark)) => std::char::from_u32(0xFF20 + mark as u32)
+                .unwrap()
+                .to_string(),
+            (_, Note::Mark(mark)) => char::from(b'A' + mark - 1).to_string(),
+        }
+    }
+
+    /// Counts past 9, when tiles touch more than eight others, carry on
+    /// in letters the way hexadecimal does, and past 35 are just '+'.
+    fn number(self, mines: usize) -> String {
+        match self {
+            // full-width digits start at U+FF10, and letters at U+FF41.
+            Skin::Emoji => {
+                let code = match mines {
+                    0..=9 => 0xFF10 + mines as u32,
+                    10..=35 => 0xFF41 + mines as u32 - 10,
+                    _ => 0xFF0B,
+                };
+
+                std::char::from_u32(code).unwrap().to_string()
+            }
+            _ => std::char::from_digit(mines as u32, 36)
+                .unwrap_or('+')
+                .to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mono_theme_has_no_escape_codes() {
+        let theme = Theme::parse("mono").unwrap();
+        let skin = Skin::Ascii;
+        let drawn = [
+            theme.hidden(skin, false),
+            theme.hidden(skin, true),
+            theme.flag(skin),
+            theme.mine(skin),
+            theme.number(skin, 3),
+            theme.paint("#", &color::Green),
+        ];
+
+        assert_eq!(drawn.concat(), "#+F*3#");
+        assert_ne!(
+            Theme::Colorblind.number(skin, 3),
+            Theme::Default.number(skin, 3)
+        );
+        assert!(Theme::parse("neon").is_none());
+        assert_eq!(Theme::Mono.number_color(3), None);
+        assert_eq!(
+            Theme::Default.number_color(9),
+            Theme::Default.number_color(8)
+        );
+    }
+
+    #[test]
+    fn emoji_skin_fills_whole_cells() {
+        let skin = Skin::parse("emoji").unwrap();
+        assert!(skin.is_wide());
+        assert_eq!(Theme::Mono.number(skin, 8), "８");
+        assert_eq!(Theme::Mono.hidden(skin, true), "🟨");
+        assert_eq!(skin.blank(), "  ");
+    }
+}
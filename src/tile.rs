@@ -9,4 +9,53 @@ pub enum TileState {
 pub struct Tile {
     pub state: TileState,
     pub has_mine: bool,
+
+    /// Void tiles aren't part of the field's shape at all.
+    pub is_void: bool,
+
+    /// A note the player has left on the tile, shown while it's hidden.
+    pub note: Option<Note>,
+}
+
+/// A note left on a hidden tile, to help work out the mines by hand.
+///
+/// Notes aren't flags: they don't count towards the mines, and chords
+/// dig straight through them.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum Note {
+    /// The tile might be a mine.
+    Mine,
+
+    /// The tile is probably safe, as a guess.
+    Safe,
+
+    /// One of a few numbered marks, for tying tiles together, like the
+    /// pair that one of two mines has to be in.
+    Mark(u8),
+}
+
+impl Note {
+    /// How many numbered marks there are.
+    pub const MARKS: u8 = 3;
+
+    /// The note after this one, cycling through each of them and then
+    /// back to no note at all.
+    pub fn next(note: Option<Note>) -> Option<Note> {
+        match note {
+            None => Some(Note::Mine),
+            Some(Note::Mine) => Some(Note::Safe),
+            Some(Note::Safe) => Some(Note::Mark(1)),
+            Some(Note::Mark(mark)) if mark < Self::MARKS => Some(Note::Mark(mark + 1)),
+            Some(Note::Mark(_)) => None,
+        }
+    }
+
+    /// Describe the note in a few words, like `a maybe-mine`.
+    pub fn describe(self) -> String {
+        match self {
+            Note::Mine => String::from("a maybe-mine"),
+            Note::Safe => String::from("a safe guess"),
+            Note::Mark(mark) => format!("mark {}", mark),
+        }
+    }
 }
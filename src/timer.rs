@@ -0,0 +1,79 @@
+use std::time::Duration;
+
+#[cfg(not(all(target_arch = "wasm32", feature = "wasm")))]
+use std::time::Instant;
+
+#[cfg(all(target_arch = "wasm32", feature = "wasm"))]
+use clock::Instant;
+
+/// A stand-in for `std::time::Instant` in the browser, where there's no
+/// clock to read except the page's own.
+#[cfg(all(target_arch = "wasm32", feature = "wasm"))]
+mod clock {
+    use std::time::Duration;
+
+    #[derive(Debug, Copy, Clone)]
+    pub struct Instant(f64);
+
+    impl Instant {
+        pub fn now() -> Self {
+            Self(js_sys::Date::now())
+        }
+
+        pub fn duration_since(&self, earlier: Instant) -> Duration {
+            Duration::from_secs_f64((self.0 - earlier.0).max(0.0) / 1000.0)
+        }
+
+        pub fn elapsed(&self) -> Duration {
+            Self::now().duration_since(*self)
+        }
+    }
+}
+
+/// Measures the time spent playing, leaving out any pauses.
+pub struct Timer {
+    started: Instant,
+    paused_at: Option<Instant>,
+    paused_time: Duration,
+
+    /// Time played before the timer was started, in a game that's
+    /// being carried on from where it was left.
+    earlier_time: Duration,
+}
+
+impl Timer {
+    /// Start a new timer from zero.
+    pub fn start() -> Self {
+        Self::start_from(Duration::new(0, 0))
+    }
+
+    /// Start a new timer that's already counted some time.
+    pub fn start_from(earlier_time: Duration) -> Self {
+        Self {
+            started: Instant::now(),
+            paused_at: None,
+            paused_time: Duration::new(0, 0),
+            earlier_time,
+        }
+    }
+
+    /// Stop the timer from counting until it's resumed.
+    pub fn pause(&mut self) {
+        if self.paused_at.is_none() {
+            self.paused_at = Some(Instant::now());
+        }
+    }
+
+    /// Carry on counting after a pause.
+    pub fn resume(&mut self) {
+        if let Some(paused_at) = self.paused_at.take() {
+            self.paused_time += paused_at.elapsed();
+        }
+    }
+
+    /// Get the time spent playing so far.
+    pub fn elapsed(&self) -> Duration {
+        let now = self.paused_at.unwrap_or_else(Instant::now);
+        now.duration_since(self.started) - self.paused_time + self.earlier_time
+    }
+}
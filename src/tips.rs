@@ -0,0 +1,141 @@
+use std::io;
+
+use crate::minefield::MineField;
+use crate::position::Position;
+use crate::storage;
+use crate::tile::TileState;
+
+const TIPS_FILE: &str = "tips";
+
+/// A hint about one of the controls, shown until the player has used it.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum Tip {
+    Move,
+    Dig,
+    Flag,
+    Chord,
+}
+
+impl Tip {
+    /// Every tip, in the order they should be introduced.
+    const ALL: [Tip; 4] = [Tip::Move, Tip::Dig, Tip::Flag, Tip::Chord];
+
+    /// The name used to remember the tip in the tips file.
+    fn name(self) -> &'static str {
+        match self {
+            Tip::Move => "move",
+            Tip::Dig => "dig",
+            Tip::Flag => "flag",
+            Tip::Chord => "chord",
+        }
+    }
+
+    /// The text shown to the player.
+    pub fn message(self) -> &'static str {
+        match self {
+            Tip::Move => "Tip: use the arrow keys or hjkl to move around",
+            Tip::Dig => "Tip: press space to dig this tile",
+            Tip::Flag => "Tip: this must be a mine, press f to flag it",
+            Tip::Chord => "Tip: press d on a satisfied number to chord",
+        }
+    }
+
+    /// Check whether the situation under the cursor calls for this tip.
+    fn applies(self, field: &MineField, position: Position) -> bool {
+        // there's nothing to do with void tiles, or with no tile at all.
+        let state = match field.get_tile(position) {
+            Ok(tile) if !tile.is_void => tile.state,
+            _ => return false,
+        };
+
+        match self {
+            Tip::Move => true,
+            Tip::Dig => state == TileState::Hidden,
+            Tip::Flag => state == TileState::Hidden && is_obvious_mine(field, position),
+            Tip::Chord => state == TileState::Visible && is_satisfied(field, position),
+        }
+    }
+}
+
+/// Count the hidden and flagged tiles around a position.
+fn count_covered_near(field: &MineField, position: Position) -> (usize, usize) {
+    let mut hidden = 0;
+    let mut flagged = 0;
+
+    for neighbour in field.get_indices_near(position).unwrap() {
+        match field.get_tile_state(neighbour).unwrap() {
+            TileState::Hidden => hidden += 1,
+            TileState::Flagged => flagged += 1,
+            TileState::Visible => (),
+        }
+    }
+
+    (hidden, flagged)
+}
+
+/// Check if a visible number has all of its mines flagged,
+/// with some hidden tiles left around it to chord open.
+fn is_satisfied(field: &MineField, position: Position) -> bool {
+    let number = field.count_mines_near(position).unwrap();
+    let (hidden, flagged) = count_covered_near(field, position);
+
+    number > 0 && hidden > 0 && flagged == number
+}
+
+/// Check if a hidden tile is next to a visible number that
+/// is only touching as many covered tiles as its count.
+fn is_obvious_mine(field: &MineField, position: Position) -> bool {
+    field
+        .get_indices_near(position)
+        .unwrap()
+        .into_iter()
+        .filter(|&number| field.get_tile_state(number).unwrap() == TileState::Visible)
+        .any(|number| {
+            let count = field.count_mines_near(number).unwrap();
+            let (hidden, flagged) = count_covered_near(field, number);
+
+            count > 0 && hidden + flagged == count
+        })
+}
+
+/// Keeps track of which tips the player no longer needs.
+pub struct Tips {
+    used: Vec<Tip>,
+}
+
+impl Tips {
+    /// Load the tips that have already been used from previous games.
+    pub fn load() -> Self {
+        let contents = storage::read_data_file(TIPS_FILE).unwrap_or_default();
+
+        let used = Tip::ALL
+            .iter()
+            .copied()
+            .filter(|tip| contents.lines().any(|line| line.trim() == tip.name()))
+            .collect();
+
+        Self { used }
+    }
+
+    /// Remember the used tips for future games.
+    pub fn save(&self) -> io::Result<()> {
+        let names: Vec<&str> = self.used.iter().map(|tip| tip.name()).collect();
+        storage::write_data_file(TIPS_FILE, &names.join("\n"))
+    }
+
+    /// Note that the player has performed the action a tip describes.
+    pub fn mark_used(&mut self, tip: Tip) {
+        if !self.used.contains(&tip) {
+            self.used.push(tip);
+        }
+    }
+
+    /// Pick the first unused tip that fits the tile under the cursor.
+    pub fn tip_for(&self, field: &MineField, position: Position) -> Option<Tip> {
+        Tip::ALL
+            .iter()
+            .copied()
+            .filter(|tip| !self.used.contains(tip))
+            .find(|tip| tip.applies(field, position))
+    }
+}
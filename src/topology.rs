@@ -0,0 +1,236 @@
+use std::fmt;
+
+use crate::position::Position;
+
+/// The shape of the grid that a field's tiles are laid out on.
+///
+/// A topology decides which tiles touch each other, and how far
+/// each row is shifted along when the field is drawn.
+pub trait Topology: fmt::Debug {
+    /// Every position touching this one that's on a field of the given size.
+    fn neighbours(&self, position: Position, height: usize, width: usize) -> Vec<Position>;
+
+    /// How many characters a row is shifted to the right when it's drawn.
+    fn row_offset(&self, _row: usize) -> usize {
+        0
+    }
+
+    /// The most that any row is shifted by, so there's room to draw it.
+    fn max_row_offset(&self) -> usize {
+        0
+    }
+
+    /// A short name for the topology, or `None` for the usual square grid.
+    fn name(&self) -> Option<&'static str> {
+        None
+    }
+}
+
+/// The usual grid, where each tile touches the eight around it.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub struct Square;
+
+/// A grid of hexagons, where each tile touches six others.
+///
+/// Hexagons are laid out in rows, with every odd row shifted half a
+/// tile to the right. A tile touches the two beside it, and two in
+/// each of the rows above and below.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub struct Hex;
+
+pub static SQUARE: Square = Square;
+pub static HEX: Hex = Hex;
+
+impl Topology for Square {
+    fn neighbours(&self, position: Position, height: usize, width: usize) -> Vec<Position> {
+        position.neighbours(height, width).collect()
+    }
+}
+
+impl Topology for Hex {
+    fn neighbours(&self, position: Position, height: usize, width: usize) -> Vec<Position> {
+        let Position { row, column } = position;
+
+        // the rows above and below lean towards the side this row is shifted to.
+        let (near, far) = if row % 2 == 0 {
+            (column.checked_sub(1), Some(column))
+        } else {
+            (Some(column), column.checked_add(1))
+        };
+
+        let above = row.checked_sub(1);
+        let below = row.checked_add(1);
+
+        let candidates = [
+            (above, near),
+            (above, far),
+            (Some(row), column.checked_sub(1)),
+            (Some(row), column.checked_add(1)),
+            (below, near),
+            (below, far),
+        ];
+
+        candidates
+            .iter()
+            .filter_map(|&(row, column)| Some(Position::new(row?, column?)))
+            .filter(|position| position.is_within(height, width))
+            .collect()
+    }
+
+    fn row_offset(&self, row: usize) -> usize {
+        // tiles are two characters wide, so half a tile is one.
+        row % 2
+    }
+
+    fn max_row_offset(&self) -> usize {
+        1
+    }
+
+    fn name(&self) -> Option<&'static str> {
+        Some("hex")
+    }
+}
+
+/// A square grid where each tile touches the ones a fixed set of steps
+/// away from it, instead of the eight around it.
+///
+/// Each step is a number of rows down and columns right, either of
+/// which can be negative. A tile's number counts the mines on every
+/// tile that one of the steps lands on.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Steps {
+    name: &'static str,
+    steps: &'static [(isize, isize)],
+}
+
+/// Tiles touch the eight a knight's move away, like in Knight-Mine sweeper.
+pub static KNIGHT: Steps = Steps {
+    name: "knight",
+    steps: &[
+        (-2, -1),
+        (-2, 1),
+        (-1, -2),
+        (-1, 2),
+        (1, -2),
+        (1, 2),
+        (2, -1),
+        (2, 1),
+    ],
+};
+
+impl Topology for Steps {
+    fn neighbours(&self, position: Position, height: usize, width: usize) -> Vec<Position> {
+        self.steps
+            .iter()
+            .filter_map(|&(down, right)| {
+                Some(Position::new(
+                    position.row.checked_add_signed(down)?,
+                    position.column.checked_add_signed(right)?,
+                ))
+            })
+            .filter(|position| position.is_within(height, width))
+            .collect()
+    }
+
+    fn name(&self) -> Option<&'static str> {
+        Some(self.name)
+    }
+}
+
+/// Which tiles of a square grid touch each other, when it's not the
+/// usual eight around each one.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum Adjacency {
+    Knight,
+
+    /// The steps from a tile to the ones it touches, sorted and without
+    /// repeats so the same set always gets the same scores.
+    Custom(Vec<(isize, isize)>),
+}
+
+impl Adjacency {
+    /// Parse either `knight`, or a list of steps like `-2,0 2,0 0,-2 0,2`,
+    /// each given as rows down and columns right.
+    pub fn parse(text: &str) -> Option<Self> {
+        let text = text.trim();
+
+        if text == "knight" {
+            return Some(Adjacency::Knight);
+        }
+
+        let mut steps = text
+            .split_whitespace()
+            .map(|step| {
+                let (down, right) = step.split_once(',')?;
+                Some((down.parse().ok()?, right.parse().ok()?))
+            })
+            .collect::<Option<Vec<(isize, isize)>>>()?;
+
+        steps.sort_unstable();
+        steps.dedup();
+
+        // a tile can't count itself.
+        match steps.is_empty() || steps.contains(&(0, 0)) {
+            true => None,
+            false => Some(Adjacency::Custom(steps)),
+        }
+    }
+
+    /// The topology of a field with this adjacency.
+    ///
+    /// Fields keep their topology for as long as the program runs, so
+    /// a custom one is leaked. Its name is a hash of its steps, which
+    /// keeps the scores of each set apart.
+    pub fn topology(&self) -> &'static dyn Topology {
+        let steps = match self {
+            Adjacency::Knight => return &KNIGHT,
+            Adjacency::Custom(steps) => steps,
+        };
+
+        // FNV-1a, so the name stays the same between runs and versions.
+        let hash = steps
+            .iter()
+            .flat_map(|&(down, right)| [down, right])
+            .flat_map(|number| (number as i64).to_le_bytes())
+            .fold(0x811c9dc5u32, |hash, byte| {
+                (hash ^ byte as u32).wrapping_mul(0x01000193)
+            });
+
+        Box::leak(Box::new(Steps {
+            name: Box::leak(format!("steps-{:08x}", hash).into_boxed_str()),
+            steps: Box::leak(steps.clone().into_boxed_slice()),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn knights_touch_tiles_a_knights_move_away() {
+        let mut corner = KNIGHT.neighbours(Position::new(0, 0), 8, 8);
+        corner.sort();
+        assert_eq!(corner, vec![Position::new(1, 2), Position::new(2, 1)]);
+
+        assert_eq!(KNIGHT.neighbours(Position::new(4, 4), 8, 8).len(), 8);
+    }
+
+    #[test]
+    fn custom_adjacency_is_parsed_from_steps() {
+        assert_eq!(Adjacency::parse("knight"), Some(Adjacency::Knight));
+        assert_eq!(
+            Adjacency::parse("0,2 -2,0 0,2"),
+            Some(Adjacency::Custom(vec![(-2, 0), (0, 2)]))
+        );
+        assert_eq!(Adjacency::parse("0,0 1,1"), None);
+        assert_eq!(Adjacency::parse("1;1"), None);
+        assert_eq!(Adjacency::parse(""), None);
+
+        let topology = Adjacency::parse("-2,0 2,0").unwrap().topology();
+        assert_eq!(
+            topology.neighbours(Position::new(2, 0), 5, 1),
+            vec![Position::new(0, 0), Position::new(4, 0)]
+        );
+    }
+}
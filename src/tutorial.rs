@@ -0,0 +1,335 @@
+use std::io::{self, Write};
+
+use crate::engine::{Action, GameEngine};
+use crate::minefield::MineField;
+use crate::motion::MotionKeys;
+use crate::position::Position;
+use crate::terminal::{self, clear, cursor, Backend, Guard, Key, RawOutput, Terminal};
+use crate::theme::{Skin, Theme};
+
+/// How many characters wide the lesson text is wrapped to.
+const TEXT_WIDTH: usize = 64;
+
+/// The keys that work in every lesson.
+const CONTROLS: &str = "arrows or hjkl move  space dig  f flag  d chord  q quit";
+
+/// A move the player has to make to carry on with a lesson.
+struct Step {
+    text: &'static str,
+    action: Action,
+
+    /// The tile to make the move on, named in A1 notation.
+    tile: &'static str,
+}
+
+/// A small board that teaches one thing, a step at a time.
+struct Lesson {
+    name: &'static str,
+
+    /// The board, written as a layout.
+    layout: &'static str,
+    steps: &'static [Step],
+
+    /// What to take away from the lesson, once every step is done.
+    summary: &'static str,
+}
+
+/// Every lesson, in the order they're taught.
+const LESSONS: [Lesson; 5] = [
+    Lesson {
+        name: "digging",
+        layout: "......\n......\n....*.\n......",
+        steps: &[Step {
+            text: "Somewhere under these tiles is a mine, and the rest are safe. \
+                   Move the cursor with the arrow keys, or h, j, k and l, and press \
+                   space to dig the tile under it. Dig A1, in the top left corner.",
+            action: Action::Dig,
+            tile: "A1",
+        }],
+        summary: "A tile with no mines around it opens up its neighbours too, \
+                  so one dig can clear a lot of the board. Only the mine is left \
+                  hidden here, so the board is cleared.",
+    },
+    Lesson {
+        name: "numbers",
+        layout: "01*\n01.\n01.\n01*",
+        steps: &[
+            Step {
+                text: "Each number counts the mines in the eight tiles around it. \
+                       B1 is a 1 touching two hidden tiles, C1 and C2, so one of them \
+                       is a mine. B2 is a 1 as well, touching C1, C2 and C3. Its mine \
+                       has to be the one next to B1, so C3 is safe. Dig C3.",
+                action: Action::Dig,
+                tile: "C3",
+            },
+            Step {
+                text: "Now B4 only touches one hidden tile, C4, so that's its mine. \
+                       B3 touches C4 too, which makes its other hidden tile, C2, safe. \
+                       Dig C2.",
+                action: Action::Dig,
+                tile: "C2",
+            },
+        ],
+        summary: "Reading the numbers against each other like this is how the \
+                  whole game is played. The tiles left are both mines.",
+    },
+    Lesson {
+        name: "flags and chords",
+        layout: "01..\n01*.\n0111\n0000",
+        steps: &[
+            Step {
+                text: "B3 is a 1, and the only hidden tile it touches is C2, so \
+                       that's where its mine is. Move onto C2 and press f to flag it.",
+                action: Action::Flag,
+                tile: "C2",
+            },
+            Step {
+                text: "B1 is a 1 with its mine flagged now, so every other tile \
+                       around it is safe. Press d on B1 to chord, which digs all of \
+                       its other hidden neighbours at once.",
+                action: Action::Chord,
+                tile: "B1",
+            },
+            Step {
+                text: "D3 has its mine flagged too. Chord it to dig D2.",
+                action: Action::Chord,
+                tile: "D3",
+            },
+            Step {
+                text: "C1 is a 1 touching the flag, so the last tile, D1, is safe. \
+                       Chord C1 to finish the board.",
+                action: Action::Chord,
+                tile: "C1",
+            },
+        ],
+        summary: "Flags mark the mines you've found, and chording a number with \
+                  all of its mines flagged digs everything else around it.",
+    },
+    Lesson {
+        name: "the 1-2-1",
+        layout: "000\n121\n*.*",
+        steps: &[
+            Step {
+                text: "Along row 2 is a 1-2-1. The 2 at B2 needs two mines among A3, \
+                       B3 and C3, but the 1 at A2 says only one of A3 and B3 is a mine, \
+                       so C3 must be the other. C2 says the same the other way round, \
+                       so the mines are A3 and C3. Flag A3.",
+                action: Action::Flag,
+                tile: "A3",
+            },
+            Step {
+                text: "Now flag C3.",
+                action: Action::Flag,
+                tile: "C3",
+            },
+            Step {
+                text: "B3, between the two mines, is safe. Dig it.",
+                action: Action::Dig,
+                tile: "B3",
+            },
+        ],
+        summary: "Whenever there's a 1-2-1 along a wall, the mines are under \
+                  the 1s and the tile under the 2 is safe.",
+    },
+    Lesson {
+        name: "the 1-2-2-1",
+        layout: "0000\n1221\n.**.",
+        steps: &[
+            Step {
+                text: "This is a 1-2-2-1. The 2 at B2 touches A3, B3 and C3, but A2 \
+                       says only one mine is in A3 and B3, so C3 is a mine. From the \
+                       other side, C2 and D2 say the same about B3. Flag B3.",
+                action: Action::Flag,
+                tile: "B3",
+            },
+            Step {
+                text: "Now flag C3.",
+                action: Action::Flag,
+                tile: "C3",
+            },
+            Step {
+                text: "A2 has its mine flagged, so chord it to dig A3.",
+                action: Action::Chord,
+                tile: "A2",
+            },
+            Step {
+                text: "Chord D2 to dig the last tile, D3.",
+                action: Action::Chord,
+                tile: "D2",
+            },
+        ],
+        summary: "A 1-2-2-1 along a wall always has its mines under the 2s, \
+                  with the tiles under the 1s safe.",
+    },
+];
+
+/// Split text into lines no wider than a width, between words.
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines = vec![String::new()];
+
+    for word in text.split_whitespace() {
+        let line = lines.last_mut().unwrap();
+
+        if !line.is_empty() && line.len() + 1 + word.len() > width {
+            lines.push(String::from(word));
+        } else {
+            if !line.is_empty() {
+                line.push(' ');
+            }
+
+            line.push_str(word);
+        }
+    }
+
+    lines
+}
+
+/// Draw a lesson's board with its labels, and the text below it, with
+/// the cursor put on the tile it's over.
+fn draw(
+    output: &mut RawOutput,
+    number: usize,
+    engine: &GameEngine,
+    text: &str,
+    message: Option<&str>,
+) -> io::Result<()> {
+    let field = engine.field();
+    let mut lines: Vec<String> = format!("{:#}", field)
+        .split("\r\n")
+        .map(String::from)
+        .collect();
+
+    lines.push(String::new());
+    lines.push(format!(
+        "Lesson {} of {}: {}",
+        number + 1,
+        LESSONS.len(),
+        LESSONS[number].name
+    ));
+    lines.extend(wrap(text, TEXT_WIDTH));
+    lines.push(String::new());
+    lines.push(String::from(message.unwrap_or("")));
+    lines.push(String::from(CONTROLS));
+
+    // rows start below the letters, and columns after the numbers.
+    let Position { row, column } = engine.cursor();
+    let x_pos = field.row_label_width() + 2 + column * field.label_cell_width();
+    let y_pos = row + 2;
+
+    write!(
+        output,
+        "{}{}{}{}{}",
+        clear::All,
+        cursor::Show,
+        cursor::Goto(1, 1),
+        lines.join("\r\n"),
+        cursor::Goto(x_pos as u16, y_pos as u16)
+    )?;
+    output.flush()
+}
+
+/// Teach the game through each lesson in turn, only moving on when the
+/// player makes the move that a step asks for. Returns whether they got
+/// through every lesson, rather than stopping partway.
+pub fn play(theme: Theme, skin: Skin) -> io::Result<bool> {
+    let mut output = Guard::new()?;
+    let mut keys = Terminal::keys();
+
+    for (number, lesson) in LESSONS.iter().enumerate() {
+        let mut field = MineField::from_layout(lesson.layout).unwrap();
+        field.set_theme(theme);
+        field.set_skin(skin);
+
+        let mut engine = GameEngine::new(field);
+        let mut motion_keys = MotionKeys::new();
+        let mut message = None;
+
+        for step in lesson.steps {
+            let tile = Position::from_a1(step.tile).unwrap();
+
+            loop {
+                draw(&mut output, number, &engine, step.text, message)?;
+
+                let key = keys.next().unwrap_or(terminal::INTERRUPT);
+                message = None;
+
+                if let Some(motion) = motion_keys.feed(key) {
+                    engine.apply(Action::Move(motion));
+                    continue;
+                }
+
+                let action = match key {
+                    Key::Char(' ') => Action::Dig,
+                    Key::Char('f') => Action::Flag,
+                    Key::Char('d') => Action::Chord,
+                    Key::Char('q') | terminal::INTERRUPT => return Ok(false),
+                    _ => continue,
+                };
+
+                // only the move the step asks for is made, so no other
+                // move can get the board into a state it can't teach.
+                if (action, engine.cursor()) == (step.action, tile) {
+                    engine.apply(action);
+                    break;
+                }
+
+                message = Some("That's not the move this step is after. Try again.");
+            }
+        }
+
+        let next = match number + 1 == LESSONS.len() {
+            true => "That's the tutorial done! Press enter to finish.",
+            false => "Press enter for the next lesson, or q to stop.",
+        };
+
+        let text = format!("{} {}", lesson.summary, next);
+        draw(&mut output, number, &engine, &text, None)?;
+
+        loop {
+            match keys.next().unwrap_or(terminal::INTERRUPT) {
+                Key::Char('\n') => break,
+                Key::Char('q') | Key::Esc | terminal::INTERRUPT => return Ok(false),
+                _ => (),
+            }
+        }
+    }
+
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::Event;
+    use crate::minefield::MineFieldState;
+
+    #[test]
+    fn every_step_is_a_move_that_works() {
+        for lesson in LESSONS.iter() {
+            let mut engine = GameEngine::new(MineField::from_layout(lesson.layout).unwrap());
+
+            for step in lesson.steps {
+                let tile = Position::from_a1(step.tile).unwrap();
+                assert_eq!(engine.field().get_state(), MineFieldState::InProgress);
+
+                engine.set_cursor(tile);
+                let events = engine.apply(step.action);
+
+                assert!(
+                    !events.contains(&Event::Ignored) && !events.contains(&Event::GameLost),
+                    "{} in '{}' didn't work",
+                    step.tile,
+                    lesson.name
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn text_is_wrapped_between_words() {
+        assert_eq!(
+            wrap("dig the tile under it", 10),
+            ["dig the", "tile under", "it"]
+        );
+    }
+}
@@ -0,0 +1,163 @@
+use std::cmp::Ordering;
+use std::io::Write;
+use std::sync::mpsc::Receiver;
+use std::time::Duration;
+
+use crate::game::{self, Minesweeper};
+use crate::terminal::{clear, cursor, Guard, Key};
+
+/// How many players take turns in a versus game.
+pub const PLAYERS: usize = 2;
+
+/// How one player's turn went.
+#[derive(Debug, Default, PartialEq, Copy, Clone)]
+pub struct Outcome {
+    pub is_cleared: bool,
+    pub time_taken: Duration,
+
+    /// How much of the board was cleared, from 0 to 1.
+    pub progress: f64,
+}
+
+impl Outcome {
+    /// Describe the turn, like `cleared in 42.3 seconds`.
+    fn describe(&self) -> String {
+        match self.is_cleared {
+            true => format!("cleared in {:.1} seconds", self.time_taken.as_secs_f64()),
+            false => format!(
+                "out after {:.1} seconds, {:.0}% cleared",
+                self.time_taken.as_secs_f64(),
+                self.progress * 100.0
+            ),
+        }
+    }
+
+    /// Compare two turns, with the better one first. Clearing the board
+    /// beats not clearing it, and a faster clear beats a slower one.
+    /// Otherwise, whoever cleared more of the board did better, and then
+    /// whoever survived longer.
+    fn compare(&self, other: &Self) -> Ordering {
+        match (self.is_cleared, other.is_cleared) {
+            (true, false) => Ordering::Less,
+            (false, true) => Ordering::Greater,
+            (true, true) => self.time_taken.cmp(&other.time_taken),
+            (false, false) => other
+                .progress
+                .partial_cmp(&self.progress)
+                .unwrap_or(Ordering::Equal)
+                .then(other.time_taken.cmp(&self.time_taken)),
+        }
+    }
+}
+
+/// Work out who won from each player's turn. Returns a line for each
+/// player in the order they played, followed by the result.
+pub fn verdict(turns: &[(String, Outcome)]) -> Vec<String> {
+    let mut lines: Vec<String> = turns
+        .iter()
+        .map(|(name, outcome)| format!("{}: {}", name, outcome.describe()))
+        .collect();
+
+    let best = turns
+        .iter()
+        .map(|(_, outcome)| outcome)
+        .min_by(|a, b| a.compare(b));
+
+    let winners: Vec<&str> = turns
+        .iter()
+        .filter(|(_, outcome)| best.map(|best| outcome.compare(best)) == Some(Ordering::Equal))
+        .map(|(name, _)| name.as_str())
+        .collect();
+
+    lines.push(match winners[..] {
+        [winner] => format!("{} wins!", winner),
+        _ => String::from("It's a draw!"),
+    });
+
+    lines
+}
+
+/// Hand the keyboard over to the next player, and wait until they're
+/// ready. Returns false if the game was interrupted instead.
+fn hand_over(keys: &Receiver<Key>, name: &str) -> bool {
+    let mut raw_stdout = Guard::new().unwrap();
+
+    write!(
+        raw_stdout,
+        "{}{}{}{}'s turn. Press enter when you're ready.",
+        clear::All,
+        cursor::Hide,
+        cursor::Goto(1, 1),
+        name
+    )
+    .unwrap();
+    raw_stdout.flush().unwrap();
+
+    game::wait_for_key(keys, Key::Char('\n'))
+}
+
+/// Play a versus game, where each player takes a turn on the same board
+/// one after another and the best turn wins. `new_game` sets up a fresh
+/// game on that board for each of them, so it should be seeded.
+///
+/// Returns each player's name and turn, or `None` if the game was
+/// interrupted before everyone had played.
+pub fn play(mut new_game: impl FnMut() -> Minesweeper) -> Option<Vec<(String, Outcome)>> {
+    // the keys are read for the whole game, so none go missing between turns.
+    let keys = game::spawn_key_reader();
+    let mut turns = Vec::new();
+
+    for player in 1..=PLAYERS {
+        let name = format!("player {}", player);
+
+        if !hand_over(&keys, &name) {
+            return None;
+        }
+
+        let outcome = new_game().play_turn(&keys, &name)?;
+        turns.push((name, outcome));
+    }
+
+    Some(turns)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn turn(name: &str, is_cleared: bool, seconds: u64, progress: f64) -> (String, Outcome) {
+        let outcome = Outcome {
+            is_cleared,
+            time_taken: Duration::from_secs(seconds),
+            progress,
+        };
+
+        (String::from(name), outcome)
+    }
+
+    #[test]
+    fn the_best_turn_wins() {
+        let faster = [turn("ann", true, 40, 1.0), turn("bob", true, 30, 1.0)];
+        assert_eq!(
+            verdict(&faster),
+            [
+                "ann: cleared in 40.0 seconds",
+                "bob: cleared in 30.0 seconds",
+                "bob wins!"
+            ]
+        );
+
+        let survived = [turn("ann", true, 90, 1.0), turn("bob", false, 5, 0.9)];
+        assert_eq!(verdict(&survived)[2], "ann wins!");
+
+        let further = [turn("ann", false, 50, 0.25), turn("bob", false, 20, 0.5)];
+        assert_eq!(
+            verdict(&further)[0],
+            "ann: out after 50.0 seconds, 25% cleared"
+        );
+        assert_eq!(verdict(&further)[2], "bob wins!");
+
+        let level = [turn("ann", true, 30, 1.0), turn("bob", true, 30, 1.0)];
+        assert_eq!(verdict(&level)[2], "It's a draw!");
+    }
+}
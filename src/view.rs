@@ -0,0 +1,92 @@
+use crate::minefield::MineField;
+use crate::position::Position;
+use crate::tile::TileState;
+
+/// What the player can see of a single tile.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum Cell {
+    Hidden,
+    Flagged,
+    Revealed(usize),
+
+    /// Not part of the board's shape, so never a mine or a neighbour.
+    Void,
+}
+
+/// A read-only view of a board, as the player sees it.
+///
+/// Nothing about hidden tiles is given away, so anything working
+/// from a view can't accidentally peek at where the mines are.
+pub trait BoardView {
+    /// The tile width of the board.
+    fn width(&self) -> usize;
+
+    /// The tile height of the board.
+    fn height(&self) -> usize;
+
+    /// The total amount of mines on the board, if it's known.
+    fn mines(&self) -> Option<usize>;
+
+    /// What can be seen of a tile. The position must be in range.
+    fn cell(&self, row: usize, column: usize) -> Cell;
+
+    /// Get the positions of the tiles around a tile.
+    fn neighbours(&self, row: usize, column: usize) -> Vec<(usize, usize)> {
+        let rows = row.saturating_sub(1)..(row + 2).min(self.height());
+        let columns = column.saturating_sub(1)..(column + 2).min(self.width());
+
+        let mut positions = Vec::new();
+
+        for adj_row in rows {
+            for adj_column in columns.clone() {
+                if (adj_row, adj_column) != (row, column) {
+                    positions.push((adj_row, adj_column));
+                }
+            }
+        }
+
+        positions
+    }
+
+    /// Get the position of every tile on the board, in order.
+    fn positions(&self) -> Vec<(usize, usize)> {
+        let width = self.width();
+
+        (0..width * self.height())
+            .map(|index| (index / width, index % width))
+            .collect()
+    }
+}
+
+impl BoardView for MineField {
+    fn width(&self) -> usize {
+        self.width()
+    }
+
+    fn height(&self) -> usize {
+        self.height()
+    }
+
+    fn mines(&self) -> Option<usize> {
+        Some(self.mines())
+    }
+
+    fn cell(&self, row: usize, column: usize) -> Cell {
+        let position = Position::new(row, column);
+
+        if self.get_tile(position).unwrap().is_void {
+            return Cell::Void;
+        }
+
+        match self.get_tile_state(position).unwrap() {
+            TileState::Hidden => Cell::Hidden,
+            TileState::Flagged => Cell::Flagged,
+            TileState::Visible => Cell::Revealed(self.count_mines_near(position).unwrap()),
+        }
+    }
+
+    fn neighbours(&self, row: usize, column: usize) -> Vec<(usize, usize)> {
+        let neighbours = self.get_indices_near(Position::new(row, column)).unwrap();
+        neighbours.into_iter().map(Into::into).collect()
+    }
+}
@@ -0,0 +1,128 @@
+use crate::position::Position;
+
+/// The part of the minefield that fits on the screen.
+///
+/// Boards larger than the terminal are drawn through the viewport,
+/// which scrolls around to keep the cursor in view.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub struct Viewport {
+    top: usize,
+    left: usize,
+    rows: usize,
+    columns: usize,
+    cell_width: usize,
+}
+
+impl Viewport {
+    /// Create a viewport showing the top left of the field.
+    pub fn new(rows: usize, columns: usize) -> Self {
+        Self {
+            top: 0,
+            left: 0,
+            rows: rows.max(1),
+            columns: columns.max(1),
+            cell_width: 2,
+        }
+    }
+
+    /// The first visible row.
+    #[inline]
+    pub fn top(&self) -> usize {
+        self.top
+    }
+
+    /// The first visible column.
+    #[inline]
+    pub fn left(&self) -> usize {
+        self.left
+    }
+
+    /// The amount of visible rows.
+    #[inline]
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// The amount of visible columns.
+    #[inline]
+    pub fn columns(&self) -> usize {
+        self.columns
+    }
+
+    /// How many characters across each tile takes up on screen.
+    #[inline]
+    pub fn cell_width(&self) -> usize {
+        self.cell_width
+    }
+
+    /// Spread the tiles out to some amount of characters across, like to
+    /// fit the column labels over them.
+    pub fn set_cell_width(&mut self, cell_width: usize) {
+        self.cell_width = cell_width;
+    }
+
+    /// Change the size of the viewport, keeping it within the field.
+    pub fn resize(&mut self, rows: usize, columns: usize, height: usize, width: usize) {
+        self.rows = rows.min(height).max(1);
+        self.columns = columns.min(width).max(1);
+
+        // don't leave empty space past the bottom right of the field.
+        self.top = self.top.min(height.saturating_sub(self.rows));
+        self.left = self.left.min(width.saturating_sub(self.columns));
+    }
+
+    /// Move along with the field when tiles are added above or to the
+    /// left of it, so the same tiles stay in view.
+    pub fn shift(&mut self, rows: usize, columns: usize) {
+        self.top += rows;
+        self.left += columns;
+    }
+
+    /// Scroll just far enough to bring a tile into view.
+    pub fn follow(&mut self, position: Position) {
+        let Position { row, column } = position;
+
+        if row < self.top {
+            self.top = row;
+        } else if row >= self.top + self.rows {
+            self.top = row + 1 - self.rows;
+        }
+
+        if column < self.left {
+            self.left = column;
+        } else if column >= self.left + self.columns {
+            self.left = column + 1 - self.columns;
+        }
+    }
+
+    /// Check if a tile is inside the visible area.
+    pub fn contains(&self, position: Position) -> bool {
+        (self.top..self.top + self.rows).contains(&position.row)
+            && (self.left..self.left + self.columns).contains(&position.column)
+    }
+
+    /// Get the (x, y) console position of a visible tile.
+    pub fn screen_position(&self, position: Position) -> (u16, u16) {
+        let x = (position.column - self.left) * self.cell_width + 1;
+        let y = position.row - self.top + 1;
+
+        (x as u16, y as u16)
+    }
+
+    /// Describe which directions have more of the field to scroll to.
+    pub fn scroll_indicators(&self, height: usize, width: usize) -> String {
+        let directions = [
+            (self.top > 0, '^'),
+            (self.top + self.rows < height, 'v'),
+            (self.left > 0, '<'),
+            (self.left + self.columns < width, '>'),
+        ];
+
+        directions
+            .iter()
+            .filter(|(more, _)| *more)
+            .map(|(_, arrow)| arrow.to_string())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
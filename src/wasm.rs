@@ -0,0 +1,119 @@
+use serde_json::{json, Value};
+use wasm_bindgen::prelude::*;
+
+use crate::engine::{Action, Event, GameEngine};
+use crate::minefield::{MineField, Opening};
+use crate::position::Position;
+use crate::protocol::GameState;
+use crate::tile::Note;
+
+/// An event as JavaScript sees it, like `{"event":"flag","row":3,"column":4,"flagged":true}`.
+fn event_json(event: Event) -> Value {
+    let position = |position: Position| json!([position.row, position.column]);
+
+    match event {
+        Event::CursorMoved(cursor) => json!({"event": "cursor", "tile": position(cursor)}),
+        Event::TilesRevealed(waves) => {
+            let waves: Vec<Vec<Value>> = waves
+                .into_iter()
+                .map(|wave| wave.into_iter().map(position).collect())
+                .collect();
+
+            json!({"event": "revealed", "waves": waves})
+        }
+        Event::FlagToggled {
+            position: tile,
+            is_flagged,
+        } => json!({"event": "flag", "tile": position(tile), "flagged": is_flagged}),
+        Event::NoteChanged {
+            position: tile,
+            note,
+        } => {
+            let note = note.map(|note| match note {
+                Note::Mine => String::from("mine"),
+                Note::Safe => String::from("safe"),
+                Note::Mark(mark) => format!("mark-{}", mark),
+            });
+
+            json!({"event": "note", "tile": position(tile), "note": note})
+        }
+        Event::NoFlagsLeft => json!({"event": "no-flags-left"}),
+        Event::FlagsDisabled => json!({"event": "flags-disabled"}),
+        Event::MinesFlagged(tiles) => {
+            let tiles: Vec<Value> = tiles.into_iter().map(position).collect();
+            json!({"event": "mines-flagged", "tiles": tiles})
+        }
+        Event::LifeLost { lives_left } => json!({"event": "life-lost", "lives": lives_left}),
+        Event::MoveUndone => json!({"event": "undone"}),
+        Event::MoveRedone => json!({"event": "redone"}),
+        Event::Ignored => json!({"event": "ignored"}),
+        Event::Paused => json!({"event": "paused"}),
+        Event::Resumed => json!({"event": "resumed"}),
+        Event::GameWon => json!({"event": "won"}),
+        Event::GameLost => json!({"event": "lost"}),
+    }
+}
+
+/// A game for a browser to play, on the same engine as the terminal.
+///
+/// Everything going out is JSON: the board is a `GameState`, just like
+/// the one a headless game writes, and each action is answered with a
+/// list of events.
+#[wasm_bindgen]
+pub struct Game {
+    engine: GameEngine,
+}
+
+#[wasm_bindgen]
+impl Game {
+    /// Start a game on a new field, with its first opening cleared.
+    #[wasm_bindgen(constructor)]
+    pub fn new(width: usize, height: usize, mines: usize) -> Result<Game, JsValue> {
+        let mut field =
+            MineField::new(width, height, mines).map_err(|e| JsValue::from(e.to_string()))?;
+        field.clear_first_opening(Opening::default());
+
+        Ok(Game {
+            engine: GameEngine::new(field),
+        })
+    }
+
+    /// Apply an action by name to a tile: `dig`, `flag`, `chord` or `note`, or
+    /// `pause`, which ignores the tile. Returns the events it caused.
+    pub fn apply(&mut self, action: &str, row: usize, column: usize) -> Result<String, JsValue> {
+        let action = match action {
+            "dig" => Action::Dig,
+            "flag" => Action::Flag,
+            "chord" => Action::Chord,
+            "note" => Action::Note,
+            "pause" => Action::Pause,
+            _ => return Err(JsValue::from(format!("unknown action '{}'", action))),
+        };
+
+        let position = Position::new(row, column);
+
+        if !self.engine.field().position_is_valid(position) {
+            return Err(JsValue::from(format!("no tile at {}, {}", row, column)));
+        }
+
+        self.engine.set_cursor(position);
+
+        let events: Vec<Value> = self
+            .engine
+            .apply(action)
+            .into_iter()
+            .map(event_json)
+            .collect();
+        Ok(Value::from(events).to_string())
+    }
+
+    /// The board as the player can see it.
+    pub fn snapshot(&self) -> String {
+        serde_json::to_string(&GameState::of(self.engine.field())).unwrap()
+    }
+
+    /// The time spent playing so far, in seconds.
+    pub fn seconds(&self) -> f64 {
+        self.engine.elapsed().as_secs_f64()
+    }
+}